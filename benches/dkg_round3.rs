@@ -0,0 +1,90 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Run with and without `--features parallel` to compare serial and parallel package processing.
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use ironfish_frost::dkg::round1;
+use ironfish_frost::dkg::round2;
+use ironfish_frost::dkg::round3::round3;
+use ironfish_frost::participant::Secret;
+use rand::thread_rng;
+
+struct Round3Inputs {
+    secret: Secret,
+    round2_secret_package: Vec<u8>,
+    round1_public_packages: Vec<round1::PublicPackage>,
+    round2_public_packages: Vec<round2::CombinedPublicPackage>,
+}
+
+fn round3_inputs(count: usize) -> Round3Inputs {
+    let secrets = (0..count)
+        .map(|_| Secret::random(thread_rng()))
+        .collect::<Vec<_>>();
+    let identities = secrets
+        .iter()
+        .map(|secret| secret.to_identity())
+        .collect::<Vec<_>>();
+    let min_signers = (count / 2) as u16;
+
+    let (round1_secret_packages, round1_public_packages): (Vec<_>, Vec<_>) = identities
+        .iter()
+        .map(|identity| {
+            round1::round1(identity, min_signers, &identities, thread_rng())
+                .expect("round 1 failed")
+        })
+        .unzip();
+
+    let (mut round2_secret_packages, mut round2_public_packages): (Vec<_>, Vec<_>) = secrets
+        .iter()
+        .zip(round1_secret_packages.iter())
+        .map(|(secret, round1_secret_package)| {
+            round2::round2(
+                secret,
+                round1_secret_package,
+                &round1_public_packages,
+                thread_rng(),
+            )
+            .expect("round 2 failed")
+        })
+        .unzip();
+
+    round2_public_packages.remove(0);
+
+    Round3Inputs {
+        secret: secrets.into_iter().next().expect("no participants"),
+        round2_secret_package: round2_secret_packages.swap_remove(0),
+        round1_public_packages,
+        round2_public_packages,
+    }
+}
+
+fn bench_round3(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dkg_round3");
+    group.sample_size(10);
+
+    for count in [10, 50, 100] {
+        let inputs = round3_inputs(count);
+
+        group.bench_with_input(BenchmarkId::from_parameter(count), &inputs, |b, inputs| {
+            b.iter(|| {
+                round3(
+                    &inputs.secret,
+                    &inputs.round2_secret_package,
+                    &inputs.round1_public_packages,
+                    &inputs.round2_public_packages,
+                )
+                .expect("round 3 failed")
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_round3);
+criterion_main!(benches);
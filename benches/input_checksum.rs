@@ -0,0 +1,49 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use ironfish_frost::participant::Identity;
+use ironfish_frost::participant::Secret;
+use ironfish_frost::signing_commitment::input_checksum;
+use ironfish_frost::signing_commitment::input_checksum_presorted;
+use rand::thread_rng;
+
+fn random_identities(count: usize) -> Vec<Identity> {
+    (0..count)
+        .map(|_| Secret::random(thread_rng()).to_identity())
+        .collect()
+}
+
+fn bench_input_checksum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("input_checksum");
+
+    for count in [3, 10, 100, 250] {
+        let signing_participants = random_identities(count);
+        let mut sorted_signing_participants = signing_participants.clone();
+        sorted_signing_participants.sort_unstable();
+
+        group.bench_with_input(
+            BenchmarkId::new("unsorted", count),
+            &signing_participants,
+            |b, signing_participants| {
+                b.iter(|| input_checksum(b"transaction hash", signing_participants))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("presorted", count),
+            &sorted_signing_participants,
+            |b, signing_participants| {
+                b.iter(|| input_checksum_presorted(b"transaction hash", signing_participants))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_input_checksum);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use ironfish_frost::frost::keys::SigningShare;
+use ironfish_frost::participant::Secret;
+use ironfish_frost::signing_commitment::verify_commitments_authenticity;
+use ironfish_frost::signing_commitment::SigningCommitment;
+use rand::thread_rng;
+
+fn random_commitments(count: usize) -> Vec<SigningCommitment> {
+    let signing_share = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+    let secrets = (0..count)
+        .map(|_| Secret::random(thread_rng()))
+        .collect::<Vec<_>>();
+    let signing_participants = secrets
+        .iter()
+        .map(|secret| secret.to_identity())
+        .collect::<Vec<_>>();
+
+    secrets
+        .iter()
+        .map(|secret| {
+            SigningCommitment::try_from_secrets(
+                secret,
+                &signing_share,
+                b"transaction hash",
+                &signing_participants,
+            )
+            .expect("commitment generation failed")
+        })
+        .collect()
+}
+
+fn bench_verify_commitments(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_commitments");
+
+    for count in [10, 50, 100, 250] {
+        let commitments = random_commitments(count);
+
+        group.bench_with_input(
+            BenchmarkId::new("serial", count),
+            &commitments,
+            |b, commitments| {
+                b.iter(|| {
+                    commitments
+                        .iter()
+                        .try_for_each(|commitment| commitment.verify_authenticity())
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("batch", count),
+            &commitments,
+            |b, commitments| b.iter(|| verify_commitments_authenticity(commitments)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify_commitments);
+criterion_main!(benches);
@@ -2,13 +2,22 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use std::error;
-use std::fmt;
+use core::fmt;
 
 use siphasher::sip::SipHasher24;
 
+/// Length of a checksum, excluding the version byte that prefixes it in serializations that
+/// carry [`CHECKSUM_VERSION`].
 pub(crate) const CHECKSUM_LEN: usize = 8;
 
+/// Version of the algorithm used to compute checksums with [`ChecksumHasher`]. Must be changed
+/// whenever the algorithm changes, so that peers using different algorithms can tell why their
+/// checksums don't match.
+pub(crate) const CHECKSUM_VERSION: u8 = 1;
+
+/// Length of a checksum prefixed by its version.
+pub(crate) const VERSIONED_CHECKSUM_LEN: usize = 1 + CHECKSUM_LEN;
+
 pub(crate) type Checksum = u64;
 
 pub(crate) type ChecksumHasher = SipHasher24;
@@ -17,6 +26,7 @@ pub(crate) type ChecksumHasher = SipHasher24;
 pub enum ChecksumError {
     SigningCommitmentError,
     DkgPublicPackageError,
+    UnsupportedVersion(u8),
 }
 
 impl fmt::Display for ChecksumError {
@@ -29,8 +39,22 @@ impl fmt::Display for ChecksumError {
             Self::DkgPublicPackageError => {
                 fmt::Display::fmt("PublicPackage checksum doesn't match", f)
             }
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported checksum version {}", version)
+            }
         }
     }
 }
 
-impl error::Error for ChecksumError {}
+#[cfg(feature = "std")]
+impl std::error::Error for ChecksumError {}
+
+/// Checks that a checksum was computed with the algorithm identified by [`CHECKSUM_VERSION`].
+#[inline]
+pub(crate) fn check_version(version: u8) -> Result<(), ChecksumError> {
+    if version == CHECKSUM_VERSION {
+        Ok(())
+    } else {
+        Err(ChecksumError::UnsupportedVersion(version))
+    }
+}
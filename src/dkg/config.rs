@@ -0,0 +1,113 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dkg::round1::CeremonyId;
+use crate::dkg::round1::DEFAULT_CEREMONY_ID;
+use crate::participant::Identity;
+use std::borrow::Borrow;
+
+pub const CONFIG_HASH_LEN: usize = 32;
+
+const CONFIG_HASH_CONTEXT: &str = "ironfish-frost 2024 DKG config hash";
+
+/// Computes a hash of the configuration of a DKG ceremony: the identities of the `participants`,
+/// the `min_signers` threshold, and the `ceremony_id` passed to
+/// [`round1_with_ceremony_id`](crate::dkg::round1::round1_with_ceremony_id), if any.
+///
+/// Participants can compare this value out-of-band before starting round 1, to make sure that
+/// they all agree on the same configuration. The order of `participants` does not influence the
+/// output, and duplicates are ignored. Passing `None` or the
+/// [`DEFAULT_CEREMONY_ID`] as `ceremony_id` gives the same hash, because they describe the same
+/// ceremony.
+#[must_use]
+pub fn config_hash<I>(
+    participants: &[I],
+    min_signers: u16,
+    ceremony_id: Option<&CeremonyId>,
+) -> [u8; CONFIG_HASH_LEN]
+where
+    I: Borrow<Identity>,
+{
+    let mut identities = participants
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<Vec<&Identity>>();
+    identities.sort_unstable();
+    identities.dedup();
+
+    let mut hasher = blake3::Hasher::new_derive_key(CONFIG_HASH_CONTEXT);
+    hasher.update(&min_signers.to_le_bytes());
+    hasher.update(&(identities.len() as u64).to_le_bytes());
+    for identity in identities {
+        hasher.update(&identity.serialize());
+    }
+    if let Some(ceremony_id) = ceremony_id.filter(|id| **id != DEFAULT_CEREMONY_ID) {
+        hasher.update(ceremony_id);
+    }
+
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::config_hash;
+    use crate::dkg::round1::CEREMONY_ID_LEN;
+    use crate::dkg::round1::DEFAULT_CEREMONY_ID;
+    use crate::participant::Secret;
+    use rand::thread_rng;
+
+    #[test]
+    fn participants_order() {
+        let identities = (0..3)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+        let mut reordered = identities.clone();
+        reordered.reverse();
+
+        assert_eq!(
+            config_hash(&identities, 2, None),
+            config_hash(&reordered, 2, None)
+        );
+        assert_eq!(
+            config_hash(&identities, 2, None),
+            config_hash(&[&identities[1], &identities[0], &identities[2]], 2, None)
+        );
+    }
+
+    #[test]
+    fn threshold_and_participants_variation() {
+        let identities = (0..3)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+
+        assert_ne!(
+            config_hash(&identities, 2, None),
+            config_hash(&identities, 3, None)
+        );
+        assert_ne!(
+            config_hash(&identities, 2, None),
+            config_hash(&identities[..2], 2, None)
+        );
+    }
+
+    #[test]
+    fn ceremony_id_variation() {
+        let identities = (0..3)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            config_hash(&identities, 2, None),
+            config_hash(&identities, 2, Some(&DEFAULT_CEREMONY_ID))
+        );
+        assert_ne!(
+            config_hash(&identities, 2, None),
+            config_hash(&identities, 2, Some(&[1u8; CEREMONY_ID_LEN]))
+        );
+        assert_ne!(
+            config_hash(&identities, 2, Some(&[1u8; CEREMONY_ID_LEN])),
+            config_hash(&identities, 2, Some(&[2u8; CEREMONY_ID_LEN]))
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::checksum::ChecksumError;
 use crate::frost;
+use crate::participant::Identity;
 use std::fmt;
 use std::io;
 
@@ -14,6 +15,8 @@ pub enum Error {
     EncryptionError(io::Error),
     DecryptionError(io::Error),
     ChecksumError(ChecksumError),
+    IdentifierCollision { identities: [Identity; 2] },
+    TooManyParticipants(usize),
 }
 
 impl fmt::Display for Error {
@@ -39,8 +42,35 @@ impl fmt::Display for Error {
                 write!(f, "checksum error: ")?;
                 e.fmt(f)
             }
+            Self::IdentifierCollision { identities } => write!(
+                f,
+                "identities {} and {} map to the same identifier",
+                identities[0], identities[1]
+            ),
+            Self::TooManyParticipants(max) => {
+                write!(f, "too many participants: at most {} are allowed", max)
+            }
         }
     }
 }
 
 impl std::error::Error for Error {}
+
+/// Errors of the signing APIs that are used while running a DKG ceremony, or with its output, can
+/// be propagated as DKG errors.
+impl From<crate::error::Error> for Error {
+    fn from(err: crate::error::Error) -> Self {
+        match err {
+            crate::error::Error::InvalidInput(e) => Self::InvalidInput(e),
+            crate::error::Error::FrostError(e) => Self::FrostError(e),
+            crate::error::Error::ChecksumError(e) => Self::ChecksumError(e),
+            crate::error::Error::IdentifierCollision { identities } => {
+                Self::IdentifierCollision { identities }
+            }
+            err @ (crate::error::Error::MissingSignatureShare(_)
+            | crate::error::Error::DuplicateSignatureShare(_)) => {
+                Self::InvalidInput(err.to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dkg::group_key::GroupSecretKey;
+use crate::dkg::group_key::GroupSecretKeyShard;
+use crate::dkg::round3::PublicKeyPackage;
+use crate::frost::keys::KeyPackage;
+use zeroize::Zeroize;
+
+/// Overwrites the intermediate packages of a DKG ceremony with zeros, and returns the output of
+/// [`round3`](crate::dkg::round3::round3) unchanged.
+///
+/// `round1_secret_package` and `round2_secret_package` are the secret packages returned by the
+/// first two rounds, and `gsk_shards` any group secret key shards held by the caller. None of them
+/// are needed once round 3 has completed. The buffers keep their size, but all their contents are
+/// zero after this call.
+///
+/// The secret packages are encrypted for the identity of the participant, so wiping them mostly
+/// limits what can be recovered if that identity is later compromised. The decrypted FROST secrets
+/// never reach the caller: they only live inside [`round2`](crate::dkg::round2::round2) and
+/// [`round3`](crate::dkg::round3::round3), and this function cannot reach them. The group secret
+/// key shards, on the other hand, are held in the clear.
+pub fn finalize_and_wipe(
+    round1_secret_package: &mut [u8],
+    round2_secret_package: &mut [u8],
+    gsk_shards: &mut [GroupSecretKeyShard],
+    round3_output: (KeyPackage, PublicKeyPackage, GroupSecretKey),
+) -> (KeyPackage, PublicKeyPackage, GroupSecretKey) {
+    round1_secret_package.zeroize();
+    round2_secret_package.zeroize();
+    for shard in gsk_shards {
+        shard.wipe();
+    }
+    round3_output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::finalize_and_wipe;
+    use crate::dkg::group_key::GroupSecretKeyShard;
+    use crate::dkg::round1;
+    use crate::dkg::round2;
+    use crate::dkg::round3::round3;
+    use crate::participant::Secret;
+    use rand::thread_rng;
+
+    #[test]
+    fn intermediate_secrets_are_wiped() {
+        let secrets = (0..3)
+            .map(|_| Secret::random(thread_rng()))
+            .collect::<Vec<_>>();
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let (mut round1_secret_packages, round1_public_packages): (Vec<_>, Vec<_>) = identities
+            .iter()
+            .map(|identity| {
+                round1::round1(identity, 2, &identities, thread_rng()).expect("round 1 failed")
+            })
+            .unzip();
+
+        let (mut round2_secret_packages, round2_public_packages): (Vec<_>, Vec<_>) = secrets
+            .iter()
+            .zip(round1_secret_packages.iter())
+            .map(|(secret, round1_secret_package)| {
+                round2::round2(
+                    secret,
+                    round1_secret_package,
+                    &round1_public_packages,
+                    thread_rng(),
+                )
+                .expect("round 2 failed")
+            })
+            .unzip();
+
+        let round3_output = round3(
+            &secrets[0],
+            &round2_secret_packages[0],
+            &round1_public_packages,
+            &round2_public_packages[1..],
+        )
+        .expect("round 3 failed");
+        let expected_output = round3_output.clone();
+
+        let mut gsk_shards = [
+            GroupSecretKeyShard::random(thread_rng()),
+            GroupSecretKeyShard::random(thread_rng()),
+        ];
+
+        let output = finalize_and_wipe(
+            &mut round1_secret_packages[0],
+            &mut round2_secret_packages[0],
+            &mut gsk_shards,
+            round3_output,
+        );
+
+        assert_eq!(output, expected_output);
+        assert!(!round1_secret_packages[0].is_empty());
+        assert!(round1_secret_packages[0].iter().all(|byte| *byte == 0));
+        assert!(!round2_secret_packages[0].is_empty());
+        assert!(round2_secret_packages[0].iter().all(|byte| *byte == 0));
+        for shard in &gsk_shards {
+            assert_eq!(shard.serialize(), [0u8; 32]);
+        }
+    }
+}
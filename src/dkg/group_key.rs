@@ -8,6 +8,7 @@ use crate::participant::Secret;
 use rand_core::CryptoRng;
 use rand_core::RngCore;
 use std::io;
+use zeroize::Zeroize;
 
 pub const GROUP_SECRET_KEY_LEN: usize = 32;
 
@@ -51,6 +52,19 @@ impl GroupSecretKeyShard {
         key
     }
 
+    /// Combines this shard into `key`. Starting from an all-zero key and combining every shard
+    /// yields the same result as [`GroupSecretKeyShard::combine`].
+    pub(crate) fn combine_into(&self, key: &mut GroupSecretKey) {
+        for (key_byte, shard_byte) in key.iter_mut().zip(self.shard.iter()) {
+            *key_byte ^= *shard_byte;
+        }
+    }
+
+    /// Overwrites this shard with zeros.
+    pub(crate) fn wipe(&mut self) {
+        self.shard.zeroize();
+    }
+
     #[must_use]
     pub fn serialize(&self) -> GroupSecretKeyShardSerialization {
         let mut s = [0u8; GROUP_SECRET_KEY_LEN];
@@ -79,7 +93,7 @@ impl GroupSecretKeyShard {
     }
 
     pub fn import(secret: &Secret, exported: &[u8]) -> io::Result<Self> {
-        let bytes = multienc::decrypt(secret, &exported).map_err(io::Error::other)?;
+        let bytes = multienc::decrypt(secret, &exported).map_err(io::Error::from)?;
 
         if bytes.len() != GROUP_SECRET_KEY_LEN {
             return Err(io::Error::other(
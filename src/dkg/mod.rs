@@ -2,8 +2,23 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+pub mod config;
 pub mod error;
+pub mod finalize;
 pub mod group_key;
 pub mod round1;
 pub mod round2;
 pub mod round3;
+pub mod session;
+pub mod threshold;
+pub mod traffic;
+
+pub use config::config_hash;
+pub use finalize::finalize_and_wipe;
+pub use session::DkgSession;
+pub use session::SessionError;
+pub use session::SessionState;
+pub use threshold::validate_threshold;
+pub use threshold::ThresholdWarning;
+pub use traffic::estimate_traffic;
+pub use traffic::TrafficEstimate;
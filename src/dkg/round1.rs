@@ -12,18 +12,20 @@ use crate::frost::keys::dkg::round1::Package;
 use crate::frost::keys::dkg::round1::SecretPackage;
 use crate::frost::keys::VerifiableSecretSharingCommitment;
 use crate::frost::Field;
+use crate::frost::Group;
 use crate::frost::Identifier;
+use crate::frost::JubjubGroup;
 use crate::frost::JubjubScalarField;
 use crate::multienc;
 use crate::multienc::read_encrypted_blob;
 use crate::participant;
 use crate::participant::Identity;
 use crate::serde::read_u16;
+use crate::serde::read_u32;
 use crate::serde::read_variable_length;
-use crate::serde::read_variable_length_bytes;
 use crate::serde::write_u16;
+use crate::serde::write_u32;
 use crate::serde::write_variable_length;
-use crate::serde::write_variable_length_bytes;
 use rand_core::CryptoRng;
 use rand_core::RngCore;
 use std::borrow::Borrow;
@@ -33,6 +35,21 @@ use std::mem;
 
 type Scalar = <JubjubScalarField as Field>::Scalar;
 
+pub const CEREMONY_ID_LEN: usize = 16;
+
+/// Caller-supplied identifier of a DKG ceremony, used to prevent packages from one ceremony from
+/// being used in another ceremony with the same participants. See [`round1_with_ceremony_id`].
+pub type CeremonyId = [u8; CEREMONY_ID_LEN];
+
+/// Ceremony identifier used by [`round1`]. This identifier is not mixed into checksums, so that
+/// packages created without a ceremony identifier keep the same checksums.
+pub const DEFAULT_CEREMONY_ID: CeremonyId = [0u8; CEREMONY_ID_LEN];
+
+/// Bit set in the length prefix of the FROST package when a [`PublicPackage`] contains a ceremony
+/// identifier. Packages with the [`DEFAULT_CEREMONY_ID`] do not contain the identifier, so that
+/// they keep the same layout as packages created before ceremony identifiers were introduced.
+const CEREMONY_ID_FLAG: u32 = 1 << 31;
+
 /// Copy of the [`frost_core::dkg::round1::SecretPackage`] struct. Necessary to implement
 /// serialization for this struct. This must be kept in sync with the upstream version.
 struct SerializableSecretPackage {
@@ -154,12 +171,16 @@ pub fn import_secret_package(
     exported: &[u8],
     secret: &participant::Secret,
 ) -> io::Result<SecretPackage> {
-    let serialized = multienc::decrypt(secret, &exported).map_err(io::Error::other)?;
+    let serialized = multienc::decrypt(secret, &exported).map_err(io::Error::from)?;
     SerializableSecretPackage::deserialize_from(&serialized[..]).map(|pkg| pkg.into())
 }
 
 #[must_use]
-pub(super) fn input_checksum<'a, I>(min_signers: u16, participants: I) -> Checksum
+pub(super) fn input_checksum<'a, I>(
+    min_signers: u16,
+    ceremony_id: &CeremonyId,
+    participants: I,
+) -> Checksum
 where
     I: IntoIterator<Item = &'a Identity>,
 {
@@ -172,6 +193,10 @@ where
 
     hasher.write(&min_signers.to_le_bytes());
 
+    if *ceremony_id != DEFAULT_CEREMONY_ID {
+        hasher.write(ceremony_id);
+    }
+
     for id in participants {
         hasher.write(&id.serialize());
     }
@@ -179,11 +204,36 @@ where
     hasher.finish()
 }
 
+/// Returns the ceremony identifier of the package in `round1_public_packages` that belongs to
+/// `self_identity`.
+///
+/// The package of the local participant was generated locally, so its ceremony identifier is
+/// the one that all the other packages are expected to have.
+pub(super) fn own_ceremony_id<'a, I>(
+    round1_public_packages: I,
+    self_identity: &Identity,
+) -> Result<CeremonyId, Error>
+where
+    I: IntoIterator<Item = &'a PublicPackage>,
+{
+    round1_public_packages
+        .into_iter()
+        .find(|pkg| pkg.identity() == self_identity)
+        .map(|pkg| pkg.ceremony_id)
+        .ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "missing round 1 public package for own identity {}",
+                self_identity
+            ))
+        })
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct PublicPackage {
     identity: Identity,
     frost_package: Package,
     group_secret_key_shard_encrypted: Vec<u8>,
+    ceremony_id: CeremonyId,
     checksum: Checksum,
 }
 
@@ -191,6 +241,7 @@ impl PublicPackage {
     pub(crate) fn new<I, R>(
         identity: Identity,
         min_signers: u16,
+        ceremony_id: CeremonyId,
         participants: &[I],
         frost_package: Package,
         group_secret_key_shard: GroupSecretKeyShard,
@@ -200,7 +251,11 @@ impl PublicPackage {
         I: Borrow<Identity>,
         R: RngCore + CryptoRng,
     {
-        let checksum = input_checksum(min_signers, participants.iter().map(Borrow::borrow));
+        let checksum = input_checksum(
+            min_signers,
+            &ceremony_id,
+            participants.iter().map(Borrow::borrow),
+        );
 
         let group_secret_key_shard_encrypted = multienc::encrypt(
             &group_secret_key_shard.serialize(),
@@ -212,6 +267,7 @@ impl PublicPackage {
             identity,
             frost_package,
             group_secret_key_shard_encrypted,
+            ceremony_id,
             checksum,
         }
     }
@@ -233,10 +289,14 @@ impl PublicPackage {
         secret: &participant::Secret,
     ) -> io::Result<GroupSecretKeyShard> {
         let serialized = multienc::decrypt(secret, &self.group_secret_key_shard_encrypted)
-            .map_err(io::Error::other)?;
+            .map_err(io::Error::from)?;
         GroupSecretKeyShard::deserialize_from(&serialized[..])
     }
 
+    pub fn ceremony_id(&self) -> &CeremonyId {
+        &self.ceremony_id
+    }
+
     pub fn checksum(&self) -> Checksum {
         self.checksum
     }
@@ -250,8 +310,21 @@ impl PublicPackage {
     pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         self.identity.serialize_into(&mut writer)?;
         let frost_package = self.frost_package.serialize().map_err(io::Error::other)?;
-        write_variable_length_bytes(&mut writer, &frost_package)?;
+        let frost_package_len = u32::try_from(frost_package.len())
+            .ok()
+            .filter(|len| len & CEREMONY_ID_FLAG == 0)
+            .ok_or_else(|| io::Error::other("frost package too large"))?;
+        let has_ceremony_id = self.ceremony_id != DEFAULT_CEREMONY_ID;
+        if has_ceremony_id {
+            write_u32(&mut writer, frost_package_len | CEREMONY_ID_FLAG)?;
+        } else {
+            write_u32(&mut writer, frost_package_len)?;
+        }
+        writer.write_all(&frost_package)?;
         writer.write_all(&self.group_secret_key_shard_encrypted[..])?;
+        if has_ceremony_id {
+            writer.write_all(&self.ceremony_id)?;
+        }
         writer.write_all(&self.checksum.to_le_bytes())?;
         Ok(())
     }
@@ -259,11 +332,24 @@ impl PublicPackage {
     pub fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
         let identity = Identity::deserialize_from(&mut reader).expect("reading identity failed");
 
-        let frost_package = read_variable_length_bytes(&mut reader)?;
+        let frost_package_len = read_u32(&mut reader)?;
+        let has_ceremony_id = frost_package_len & CEREMONY_ID_FLAG != 0;
+        let mut frost_package = vec![0u8; (frost_package_len & !CEREMONY_ID_FLAG) as usize];
+        reader.read_exact(&mut frost_package)?;
         let frost_package = Package::deserialize(&frost_package).map_err(io::Error::other)?;
 
         let group_secret_key_shard_encrypted = read_encrypted_blob(&mut reader)?;
 
+        let mut ceremony_id = DEFAULT_CEREMONY_ID;
+        if has_ceremony_id {
+            reader.read_exact(&mut ceremony_id)?;
+            if ceremony_id == DEFAULT_CEREMONY_ID {
+                return Err(io::Error::other(
+                    "the default ceremony identifier must not be serialized",
+                ));
+            }
+        }
+
         let mut checksum = [0u8; CHECKSUM_LEN];
         reader.read_exact(&mut checksum)?;
         let checksum = u64::from_le_bytes(checksum);
@@ -272,15 +358,155 @@ impl PublicPackage {
             identity,
             frost_package,
             group_secret_key_shard_encrypted,
+            ceremony_id,
             checksum,
         })
     }
 }
 
+/// Checks that the commitments of all the `round1_public_packages` are well-formed, so that
+/// malformed packages can be detected before starting round 2.
+///
+/// The commitment of each package must contain exactly `min_signers` coefficients (one for each
+/// coefficient of the secret polynomial of the sender), and none of them can be the identity
+/// element. On failure, the error names the sender of the first bad package found.
+pub fn verify_packages<'a, I>(round1_public_packages: I, min_signers: u16) -> Result<(), Error>
+where
+    I: IntoIterator<Item = &'a PublicPackage>,
+{
+    for package in round1_public_packages {
+        let coefficients = package.frost_package().commitment().serialize();
+        if coefficients.len() != min_signers as usize {
+            return Err(Error::InvalidInput(format!(
+                "round 1 public package from {} has {} commitment coefficients, expected {}",
+                package.identity(),
+                coefficients.len(),
+                min_signers
+            )));
+        }
+
+        for coefficient in coefficients {
+            let element = JubjubGroup::deserialize(&coefficient).map_err(|_| {
+                Error::InvalidInput(format!(
+                    "round 1 public package from {} has an invalid commitment coefficient",
+                    package.identity()
+                ))
+            })?;
+            if element == JubjubGroup::identity() {
+                return Err(Error::InvalidInput(format!(
+                    "round 1 public package from {} has an identity commitment coefficient",
+                    package.identity()
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn round1<'a, I, R>(
     self_identity: &Identity,
     min_signers: u16,
     participants: I,
+    csrng: R,
+) -> Result<(Vec<u8>, PublicPackage), Error>
+where
+    I: IntoIterator<Item = &'a Identity>,
+    R: RngCore + CryptoRng,
+{
+    round1_with_options(
+        self_identity,
+        min_signers,
+        participants,
+        Round1Options::default(),
+        csrng,
+    )
+}
+
+/// Same as [`round1`], but fails with [`Error::TooManyParticipants`] if the group has more than
+/// `max_participants` participants. [`MAX_PARTICIPANTS`](crate::participant::MAX_PARTICIPANTS) is a
+/// sensible default for this limit.
+pub fn round1_with_max_participants<'a, I, R>(
+    self_identity: &Identity,
+    min_signers: u16,
+    participants: I,
+    max_participants: usize,
+    csrng: R,
+) -> Result<(Vec<u8>, PublicPackage), Error>
+where
+    I: IntoIterator<Item = &'a Identity>,
+    R: RngCore + CryptoRng,
+{
+    round1_with_options(
+        self_identity,
+        min_signers,
+        participants,
+        Round1Options {
+            max_participants: Some(max_participants),
+            ..Round1Options::default()
+        },
+        csrng,
+    )
+}
+
+/// Same as [`round1`], but binds the packages to the ceremony identified by `ceremony_id`.
+///
+/// The ceremony identifier is stored in the public package and mixed into the checksums of
+/// round 1 and round 2, so that packages from a ceremony cannot be used in a different ceremony,
+/// even if the two ceremonies have the same participants. All the participants of a ceremony must
+/// use the same identifier, and [`round2`](crate::dkg::round2::round2) and
+/// [`round3`](crate::dkg::round3::round3) reject packages with an identifier different from the
+/// one of the local participant.
+pub fn round1_with_ceremony_id<'a, I, R>(
+    self_identity: &Identity,
+    min_signers: u16,
+    participants: I,
+    ceremony_id: CeremonyId,
+    csrng: R,
+) -> Result<(Vec<u8>, PublicPackage), Error>
+where
+    I: IntoIterator<Item = &'a Identity>,
+    R: RngCore + CryptoRng,
+{
+    round1_with_options(
+        self_identity,
+        min_signers,
+        participants,
+        Round1Options {
+            ceremony_id,
+            ..Round1Options::default()
+        },
+        csrng,
+    )
+}
+
+/// Options for [`round1_with_options`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Round1Options {
+    /// If set, round 1 fails with [`Error::TooManyParticipants`] if the group has more
+    /// participants. See [`round1_with_max_participants`].
+    pub max_participants: Option<usize>,
+    /// The ceremony the packages are bound to. See [`round1_with_ceremony_id`].
+    pub ceremony_id: CeremonyId,
+}
+
+impl Default for Round1Options {
+    fn default() -> Self {
+        Self {
+            max_participants: None,
+            ceremony_id: DEFAULT_CEREMONY_ID,
+        }
+    }
+}
+
+/// Same as [`round1`], but with the given `options`. This allows combining the participant limit
+/// of [`round1_with_max_participants`] with the ceremony identifier of
+/// [`round1_with_ceremony_id`].
+pub fn round1_with_options<'a, I, R>(
+    self_identity: &Identity,
+    min_signers: u16,
+    participants: I,
+    options: Round1Options,
     mut csrng: R,
 ) -> Result<(Vec<u8>, PublicPackage), Error>
 where
@@ -299,6 +525,12 @@ where
         ));
     }
 
+    if let Some(max_participants) = options.max_participants {
+        if participants.len() > max_participants {
+            return Err(Error::TooManyParticipants(max_participants));
+        }
+    }
+
     let max_signers = u16::try_from(participants.len())
         .map_err(|_| Error::InvalidInput("too many participants".to_string()))?;
 
@@ -319,6 +551,7 @@ where
     let public_package = PublicPackage::new(
         self_identity.clone(),
         min_signers,
+        options.ceremony_id,
         &participants,
         public_package,
         group_secret_key_shard,
@@ -332,6 +565,7 @@ where
 mod tests {
     use super::*;
     use crate::participant::Secret;
+    use crate::participant::MAX_PARTICIPANTS;
     use rand::thread_rng;
 
     #[test]
@@ -465,11 +699,48 @@ mod tests {
         let (_, public_package) = super::round1(identity, min_signers, &participants, &mut rng)
             .expect("dkg round 1 failed");
 
-        let expected_checksum = input_checksum(min_signers, &participants);
+        let expected_checksum = input_checksum(min_signers, &DEFAULT_CEREMONY_ID, &participants);
 
         assert_eq!(expected_checksum, public_package.checksum());
     }
 
+    #[test]
+    fn test_round1_verify_packages() {
+        let mut rng = thread_rng();
+
+        let min_signers = 2;
+
+        let participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let mut packages = participants
+            .iter()
+            .map(|identity| {
+                super::round1(identity, min_signers, &participants, &mut rng)
+                    .expect("dkg round 1 failed")
+                    .1
+            })
+            .collect::<Vec<_>>();
+
+        verify_packages(&packages, min_signers).expect("package verification failed");
+
+        // Replace the package of the second participant with one generated with a different
+        // threshold, which has one commitment coefficient too many
+        packages[1] = super::round1(&participants[1], min_signers + 1, &participants, &mut rng)
+            .expect("dkg round 1 failed")
+            .1;
+
+        match verify_packages(&packages, min_signers) {
+            Err(Error::InvalidInput(message)) => {
+                assert!(message.contains(&participants[1].to_string()))
+            }
+            _ => panic!("package with a wrong number of coefficients should have been rejected"),
+        }
+    }
+
     #[test]
     fn test_round1_package_serialization() {
         let mut rng = thread_rng();
@@ -495,6 +766,90 @@ mod tests {
         assert_eq!(public_package, deserialized);
     }
 
+    #[test]
+    fn round1_max_participants() {
+        let participants = (0..MAX_PARTICIPANTS + 1)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+
+        round1_with_max_participants(
+            &participants[0],
+            2,
+            &participants[..MAX_PARTICIPANTS],
+            MAX_PARTICIPANTS,
+            thread_rng(),
+        )
+        .expect("round 1 failed");
+
+        // The limit is opt-in
+        super::round1(&participants[0], 2, &participants, thread_rng()).expect("round 1 failed");
+
+        match round1_with_max_participants(
+            &participants[0],
+            2,
+            &participants,
+            MAX_PARTICIPANTS,
+            thread_rng(),
+        ) {
+            Err(Error::TooManyParticipants(MAX_PARTICIPANTS)) => (),
+            _ => panic!("dkg round1 should have failed with TooManyParticipants"),
+        }
+    }
+
+    #[test]
+    fn round1_custom_max_participants() {
+        let participants = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+
+        round1_with_max_participants(&participants[0], 2, &participants, 3, thread_rng())
+            .expect("round 1 failed");
+
+        match round1_with_max_participants(&participants[0], 2, &participants, 2, thread_rng()) {
+            Err(Error::TooManyParticipants(2)) => (),
+            _ => panic!("dkg round1 should have failed with TooManyParticipants"),
+        }
+    }
+
+    #[test]
+    fn round1_options() {
+        let participants = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let ceremony_id = [0x42; CEREMONY_ID_LEN];
+
+        let (_, public_package) = round1_with_options(
+            &participants[0],
+            2,
+            &participants,
+            Round1Options {
+                max_participants: Some(3),
+                ceremony_id,
+            },
+            thread_rng(),
+        )
+        .expect("round 1 failed");
+        assert_eq!(public_package.ceremony_id(), &ceremony_id);
+
+        match round1_with_options(
+            &participants[0],
+            2,
+            &participants,
+            Round1Options {
+                max_participants: Some(2),
+                ceremony_id,
+            },
+            thread_rng(),
+        ) {
+            Err(Error::TooManyParticipants(2)) => (),
+            _ => panic!("dkg round1 should have failed with TooManyParticipants"),
+        }
+    }
+
     #[test]
     fn round1() {
         let secret = Secret::random(thread_rng());
@@ -516,4 +871,98 @@ mod tests {
             .group_secret_key_shard(&secret)
             .expect("group secret key shard decryption failed");
     }
+
+    #[test]
+    fn test_round1_ceremony_id() {
+        let mut rng = thread_rng();
+
+        let min_signers = 2;
+        let participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let (_, default_package) =
+            super::round1(&participants[0], min_signers, &participants, &mut rng)
+                .expect("dkg round 1 failed");
+        let (_, package) = round1_with_ceremony_id(
+            &participants[0],
+            min_signers,
+            &participants,
+            [7u8; CEREMONY_ID_LEN],
+            &mut rng,
+        )
+        .expect("dkg round 1 failed");
+
+        assert_eq!(default_package.ceremony_id(), &DEFAULT_CEREMONY_ID);
+        assert_eq!(package.ceremony_id(), &[7u8; CEREMONY_ID_LEN]);
+        assert_ne!(default_package.checksum(), package.checksum());
+        assert_eq!(
+            package.checksum(),
+            input_checksum(min_signers, &[7u8; CEREMONY_ID_LEN], &participants)
+        );
+
+        let deserialized = PublicPackage::deserialize_from(&package.serialize()[..])
+            .expect("package deserialization failed");
+        assert_eq!(deserialized, package);
+    }
+
+    #[test]
+    fn public_package_layout_without_ceremony_id() {
+        let mut rng = thread_rng();
+
+        let participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+        let (_, package) = super::round1(&participants[0], 2, &participants, &mut rng)
+            .expect("dkg round 1 failed");
+
+        // Packages without a ceremony identifier must keep the layout that was used before
+        // ceremony identifiers were introduced
+        let mut old_layout = Vec::new();
+        old_layout.extend_from_slice(&package.identity().serialize());
+        let frost_package = package
+            .frost_package()
+            .serialize()
+            .expect("frost package serialization failed");
+        old_layout.extend_from_slice(&(frost_package.len() as u32).to_le_bytes());
+        old_layout.extend_from_slice(&frost_package);
+        old_layout.extend_from_slice(package.group_secret_key_shard_encrypted());
+        old_layout.extend_from_slice(&package.checksum().to_le_bytes());
+
+        assert_eq!(package.serialize(), old_layout);
+
+        let deserialized = PublicPackage::deserialize_from(&old_layout[..])
+            .expect("package deserialization failed");
+        assert_eq!(deserialized, package);
+        assert_eq!(deserialized.ceremony_id(), &DEFAULT_CEREMONY_ID);
+    }
+
+    #[test]
+    fn public_package_with_serialized_default_ceremony_id() {
+        let mut rng = thread_rng();
+
+        let participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+        let (_, package) = round1_with_ceremony_id(
+            &participants[0],
+            2,
+            &participants,
+            [7u8; CEREMONY_ID_LEN],
+            &mut rng,
+        )
+        .expect("dkg round 1 failed");
+
+        // Replace the ceremony identifier with the default one, which is not canonical
+        let mut serialization = package.serialize();
+        let checksum_offset = serialization.len() - CHECKSUM_LEN;
+        serialization[checksum_offset - CEREMONY_ID_LEN..checksum_offset]
+            .copy_from_slice(&DEFAULT_CEREMONY_ID);
+
+        PublicPackage::deserialize_from(&serialization[..])
+            .expect_err("serialized default ceremony identifier should have been rejected");
+    }
 }
@@ -16,6 +16,8 @@ use crate::frost::keys::VerifiableSecretSharingCommitment;
 use crate::frost::Field;
 use crate::frost::Identifier;
 use crate::frost::JubjubScalarField;
+use crate::identifiers::IdentifierMap;
+use crate::identifiers::MappingError;
 use crate::multienc;
 use crate::participant;
 use crate::participant::Identity;
@@ -29,6 +31,7 @@ use rand_core::CryptoRng;
 use rand_core::RngCore;
 use std::borrow::Borrow;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::hash::Hasher;
 use std::io;
 use std::mem;
@@ -127,17 +130,25 @@ impl SerializableSecretPackage {
     }
 }
 
-pub(super) fn get_secret_package_signers(pkg: &SecretPackage) -> (u16, u16) {
+/// Returns the `(min_signers, max_signers)` threshold parameters of a round 2 secret package, as
+/// obtained from [`import_secret_package`].
+#[must_use]
+pub fn secret_package_params(pkg: &SecretPackage) -> (u16, u16) {
     let serializable = <&SerializableSecretPackage>::from(pkg);
     (serializable.min_signers, serializable.max_signers)
 }
 
+/// Serializes a round 2 secret package and encrypts it for `identity`, so that it can be persisted
+/// at rest until [`round3`](crate::dkg::round3::round3) and later restored with
+/// [`import_secret_package`].
+///
+/// Returns an error if `identity` is not the identity that owns the secret package.
 pub fn export_secret_package<R: RngCore + CryptoRng>(
     pkg: &SecretPackage,
     identity: &Identity,
     csrng: R,
 ) -> io::Result<Vec<u8>> {
-    let serializable = SerializableSecretPackage::from(pkg.clone());
+    let serializable = <&SerializableSecretPackage>::from(pkg);
     if serializable.identifier != identity.to_frost_identifier() {
         return Err(io::Error::other("identity mismatch"));
     }
@@ -149,11 +160,13 @@ pub fn export_secret_package<R: RngCore + CryptoRng>(
     Ok(multienc::encrypt(&serialized, [identity], csrng))
 }
 
+/// Decrypts and deserializes a round 2 secret package previously produced by
+/// [`export_secret_package`] (or by [`round2`]).
 pub fn import_secret_package(
     exported: &[u8],
     secret: &participant::Secret,
 ) -> io::Result<SecretPackage> {
-    let serialized = multienc::decrypt(secret, &exported).map_err(io::Error::other)?;
+    let serialized = multienc::decrypt(secret, &exported).map_err(io::Error::from)?;
     SerializableSecretPackage::deserialize_from(&serialized[..]).map(|pkg| pkg.into())
 }
 
@@ -366,37 +379,43 @@ where
         )));
     }
 
+    let ceremony_id =
+        round1::own_ceremony_id(round1_public_packages.iter().copied(), &self_identity)?;
+    if round1_public_packages
+        .iter()
+        .any(|pkg| *pkg.ceremony_id() != ceremony_id)
+    {
+        return Err(Error::InvalidInput(
+            "round 1 public packages belong to a different ceremony".to_string(),
+        ));
+    }
+
     let expected_round1_checksum = round1::input_checksum(
         min_signers,
+        &ceremony_id,
         round1_public_packages.iter().map(|pkg| pkg.identity()),
     );
 
-    let mut identities = BTreeMap::new();
+    let mut identities = IdentifierMap::new();
     let mut round1_frost_packages: BTreeMap<Identifier, Round1Package> = BTreeMap::new();
     for public_package in round1_public_packages.clone() {
         if public_package.checksum() != expected_round1_checksum {
             return Err(Error::ChecksumError(ChecksumError::DkgPublicPackageError));
         }
 
-        let identity = public_package.identity();
-        let frost_identifier = identity.to_frost_identifier();
-        let frost_package = public_package.frost_package().clone();
-
-        if round1_frost_packages
-            .insert(frost_identifier, frost_package)
-            .is_some()
-        {
-            return Err(Error::InvalidInput(format!(
-                "multiple public packages provided for identity {}",
-                public_package.identity()
-            )));
-        }
-
-        identities.insert(frost_identifier, identity);
-        round1_frost_packages.insert(
-            public_package.identity().to_frost_identifier(),
-            public_package.frost_package().clone(),
-        );
+        let frost_identifier =
+            identities
+                .insert(public_package.identity())
+                .map_err(|err| match err {
+                    MappingError::Duplicate(identity) => Error::InvalidInput(format!(
+                        "multiple public packages provided for identity {}",
+                        identity
+                    )),
+                    MappingError::Collision(identities) => {
+                        Error::IdentifierCollision { identities }
+                    }
+                })?;
+        round1_frost_packages.insert(frost_identifier, public_package.frost_package().clone());
     }
 
     // Sanity check
@@ -422,7 +441,7 @@ where
     // Convert the Identifier->Package map to an Identity->PublicPackage map
     let mut round2_public_packages = Vec::new();
     for (identifier, package) in round2_packages {
-        let identity = *identities
+        let identity = identities
             .get(&identifier)
             .expect("round2 generated package for unknown identifier");
 
@@ -442,6 +461,71 @@ where
     ))
 }
 
+/// Verifies that `packages`, produced by `sender` using [`round2()`], contain exactly one package
+/// for each of the other `participants`, and none for `sender` itself.
+///
+/// This can be used by a sender to validate its own output before broadcasting it.
+pub fn verify_sender_output_complete<'a, P, I>(
+    packages: P,
+    participants: &[I],
+    sender: &Identity,
+) -> Result<(), Error>
+where
+    P: IntoIterator<Item = &'a PublicPackage>,
+    I: Borrow<Identity>,
+{
+    let participants = participants
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<BTreeSet<_>>();
+    if !participants.contains(sender) {
+        return Err(Error::InvalidInput(format!(
+            "sender {} is not a participant",
+            sender
+        )));
+    }
+
+    let mut recipients = BTreeSet::new();
+    for package in packages {
+        if package.sender_identity() != sender {
+            return Err(Error::InvalidInput(format!(
+                "package from unexpected sender {}",
+                package.sender_identity()
+            )));
+        }
+        let recipient = package.recipient_identity();
+        if recipient == sender {
+            return Err(Error::InvalidInput(
+                "package addressed to the sender itself".to_string(),
+            ));
+        }
+        if !participants.contains(recipient) {
+            return Err(Error::InvalidInput(format!(
+                "package addressed to unknown recipient {}",
+                recipient
+            )));
+        }
+        if !recipients.insert(recipient) {
+            return Err(Error::InvalidInput(format!(
+                "multiple packages addressed to recipient {}",
+                recipient
+            )));
+        }
+    }
+
+    if let Some(missing) = participants
+        .into_iter()
+        .find(|participant| *participant != sender && !recipients.contains(participant))
+    {
+        return Err(Error::InvalidInput(format!(
+            "missing package for recipient {}",
+            missing
+        )));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -711,4 +795,54 @@ mod tests {
             _ => panic!("dkg round2 should have failed with InvalidInput"),
         }
     }
+
+    #[test]
+    fn verify_sender_output_complete_missing_recipient() {
+        let secret = participant::Secret::random(thread_rng());
+        let identities = [
+            secret.to_identity(),
+            participant::Secret::random(thread_rng()).to_identity(),
+            participant::Secret::random(thread_rng()).to_identity(),
+        ];
+
+        let round1_packages = identities
+            .iter()
+            .map(|id| round1::round1(id, 2, &identities, thread_rng()).expect("dkg round 1 failed"))
+            .collect::<Vec<_>>();
+
+        let (_, round2_public_packages) = super::round2(
+            &secret,
+            &round1_packages[0].0,
+            round1_packages.iter().map(|(_, package)| package),
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        verify_sender_output_complete(
+            round2_public_packages.packages(),
+            &identities,
+            &identities[0],
+        )
+        .expect("complete output was rejected");
+
+        let result = verify_sender_output_complete(
+            round2_public_packages.packages_for(&identities[1]),
+            &identities,
+            &identities[0],
+        );
+
+        match result {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("incomplete output should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn secret_package_params_match_round1() {
+        let (secret, round1_secret_pkg, round1_packages) = create_round1_packages();
+        let (secret_pkg, _) = create_round2_packages(secret, round1_secret_pkg, round1_packages);
+
+        // `create_round1_packages` runs round 1 with 5-of-10 parameters
+        assert_eq!(secret_package_params(&secret_pkg), (5, 10));
+    }
 }
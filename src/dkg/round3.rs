@@ -2,16 +2,22 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::checksum::Checksum;
 use crate::checksum::ChecksumError;
 use crate::dkg::error::Error;
 use crate::dkg::group_key::GroupSecretKey;
 use crate::dkg::group_key::GroupSecretKeyShard;
+use crate::dkg::group_key::GROUP_SECRET_KEY_LEN;
 use crate::dkg::round1;
 use crate::dkg::round2;
 use crate::dkg::round2::import_secret_package;
 use crate::frost::keys::dkg::part3;
+use crate::frost::keys::dkg::round1::Package as Round1Package;
 use crate::frost::keys::KeyPackage;
 use crate::frost::keys::PublicKeyPackage as FrostPublicKeyPackage;
+use crate::frost::Identifier;
+use crate::identifiers::IdentifierMap;
+use crate::identifiers::MappingError;
 use crate::participant::Identity;
 use crate::participant::Secret;
 use crate::serde::read_u16;
@@ -20,10 +26,16 @@ use crate::serde::read_variable_length_bytes;
 use crate::serde::write_u16;
 use crate::serde::write_variable_length;
 use crate::serde::write_variable_length_bytes;
+#[cfg(feature = "parallel")]
+use rayon::iter::IntoParallelRefIterator;
+#[cfg(feature = "parallel")]
+use rayon::iter::ParallelIterator;
 use reddsa::frost::redjubjub::VerifyingKey;
 use std::borrow::Borrow;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::io;
+use zeroize::Zeroizing;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct PublicKeyPackage {
@@ -105,12 +117,274 @@ impl PublicKeyPackage {
     }
 }
 
+/// Checks that all the participants of the DKG ceremony sent a round 2 package to `identity`.
+///
+/// This can be used before calling [`round3()`] to find out which participants, if any, failed to
+/// contribute, so that they can be excluded or asked to retry. Returns the identities of the
+/// participants (as listed in `round1_public_packages`) that did not send a round 2 package to
+/// `identity`.
+pub fn check_round2_completeness<'a, P, Q>(
+    round1_public_packages: P,
+    round2_public_packages: Q,
+    identity: &Identity,
+) -> Result<(), Vec<Identity>>
+where
+    P: IntoIterator<Item = &'a round1::PublicPackage>,
+    Q: IntoIterator<Item = &'a round2::CombinedPublicPackage>,
+{
+    let senders = round2_public_packages
+        .into_iter()
+        .flat_map(|combo| combo.packages_for(identity))
+        .map(round2::PublicPackage::sender_identity)
+        .collect::<BTreeSet<_>>();
+
+    let mut missing = round1_public_packages
+        .into_iter()
+        .map(round1::PublicPackage::identity)
+        .filter(|participant| *participant != identity && !senders.contains(participant))
+        .cloned()
+        .collect::<Vec<_>>();
+    missing.sort_unstable();
+    missing.dedup();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing)
+    }
+}
+
+/// Verifies the checksum of each of the `round1_public_packages` and converts them to the map
+/// expected by [`part3`].
+///
+/// Returns an error if more than one package was provided for the same sender, even if the
+/// duplicates have a consistent checksum. Duplicate senders are detected before any checksum is
+/// verified, because a repeated sender also makes the expected checksum wrong, and reporting a
+/// checksum error in that case would hide the actual problem.
+///
+/// Identifiers are assigned serially, so that duplicates are always reported in the same way; the
+/// checksums are then verified according to `processing`.
+fn build_round1_frost_packages(
+    round1_public_packages: &[&round1::PublicPackage],
+    expected_round1_checksum: Checksum,
+    processing: Processing,
+) -> Result<BTreeMap<Identifier, Round1Package>, Error> {
+    let mut round1_frost_packages = BTreeMap::new();
+    let mut round1_identifiers = IdentifierMap::new();
+
+    for public_package in round1_public_packages {
+        let frost_identifier = round1_identifiers
+            .insert(public_package.identity())
+            .map_err(|err| match err {
+                MappingError::Duplicate(identity) => Error::InvalidInput(format!(
+                    "multiple round 1 public packages provided for identity {}",
+                    identity
+                )),
+                MappingError::Collision(identities) => Error::IdentifierCollision { identities },
+            })?;
+        round1_frost_packages.insert(frost_identifier, public_package.frost_package().clone());
+    }
+
+    if processing
+        .map(round1_public_packages, |public_package| {
+            public_package.checksum() == expected_round1_checksum
+        })
+        .contains(&false)
+    {
+        return Err(Error::ChecksumError(ChecksumError::DkgPublicPackageError));
+    }
+
+    // Duplicate identities and identifier collisions have been rejected above
+    debug_assert_eq!(round1_public_packages.len(), round1_frost_packages.len());
+
+    Ok(round1_frost_packages)
+}
+
+/// Output of [`build_round1_frost_packages_streaming`]. The group secret key is wiped on drop.
+#[allow(missing_debug_implementations)]
+pub struct Round1FrostPackages {
+    frost_packages: BTreeMap<Identifier, Round1Package>,
+    identities: Vec<Identity>,
+    group_secret_key: Zeroizing<GroupSecretKey>,
+}
+
+impl Round1FrostPackages {
+    #[must_use]
+    pub fn frost_packages(&self) -> &BTreeMap<Identifier, Round1Package> {
+        &self.frost_packages
+    }
+
+    #[must_use]
+    pub fn identities(&self) -> &[Identity] {
+        &self.identities
+    }
+
+    #[must_use]
+    pub fn group_secret_key(&self) -> &GroupSecretKey {
+        &self.group_secret_key
+    }
+}
+
+/// Streaming variant of the round 1 package processing done by [`round3`].
+///
+/// Packages are consumed one at a time: only the FROST package and the identity of each sender
+/// are retained, and the group secret key shards are decrypted with `secret` and combined as they
+/// are encountered, so the full round 1 packages never need to be held in memory at the same time.
+///
+/// Because the expected checksum depends on the identities of all the participants, the checksum
+/// of each package is first compared against the checksum of the first package, and the common
+/// checksum is verified once all the packages have been processed.
+pub fn build_round1_frost_packages_streaming<I, P>(
+    secret: &Secret,
+    round1_public_packages: I,
+    min_signers: u16,
+    max_signers: u16,
+) -> Result<Round1FrostPackages, Error>
+where
+    I: IntoIterator<Item = P>,
+    P: Borrow<round1::PublicPackage>,
+{
+    let mut frost_packages = BTreeMap::new();
+    let mut identifiers = IdentifierMap::new();
+    let mut identities = Vec::new();
+    // Wiped on drop, so that the partially combined key does not outlive an early return
+    let mut group_secret_key = Zeroizing::new([0u8; GROUP_SECRET_KEY_LEN]);
+    let mut common_checksum = None;
+    let mut common_ceremony_id = None;
+
+    for public_package in round1_public_packages {
+        let public_package = public_package.borrow();
+
+        if identities.len() >= max_signers as usize {
+            return Err(Error::InvalidInput(format!(
+                "expected {} round 1 public packages, got more",
+                max_signers
+            )));
+        }
+
+        if *common_checksum.get_or_insert(public_package.checksum()) != public_package.checksum() {
+            return Err(Error::ChecksumError(ChecksumError::DkgPublicPackageError));
+        }
+
+        if *common_ceremony_id.get_or_insert(*public_package.ceremony_id())
+            != *public_package.ceremony_id()
+        {
+            return Err(Error::InvalidInput(
+                "round 1 public packages belong to a different ceremony".to_string(),
+            ));
+        }
+
+        let frost_identifier = identifiers
+            .insert(public_package.identity())
+            .map_err(|err| match err {
+                MappingError::Duplicate(identity) => Error::InvalidInput(format!(
+                    "multiple round 1 public packages provided for identity {}",
+                    identity
+                )),
+                MappingError::Collision(identities) => Error::IdentifierCollision { identities },
+            })?;
+
+        let mut shard = public_package
+            .group_secret_key_shard(secret)
+            .map_err(Error::DecryptionError)?;
+        shard.combine_into(&mut group_secret_key);
+        shard.wipe();
+
+        frost_packages.insert(frost_identifier, public_package.frost_package().clone());
+        identities.push(public_package.identity().clone());
+    }
+
+    if identities.len() != max_signers as usize {
+        return Err(Error::InvalidInput(format!(
+            "expected {} round 1 public packages, got {}",
+            max_signers,
+            identities.len()
+        )));
+    }
+
+    let identity = secret.to_identity();
+    if !identities.contains(&identity) {
+        return Err(Error::InvalidInput(format!(
+            "missing round 1 public package for own identity {}",
+            identity
+        )));
+    }
+
+    let ceremony_id = common_ceremony_id.unwrap_or(round1::DEFAULT_CEREMONY_ID);
+    let expected_checksum = round1::input_checksum(min_signers, &ceremony_id, identities.iter());
+    if common_checksum != Some(expected_checksum) {
+        return Err(Error::ChecksumError(ChecksumError::DkgPublicPackageError));
+    }
+
+    Ok(Round1FrostPackages {
+        frost_packages,
+        identities,
+        group_secret_key,
+    })
+}
+
+/// How the packages received by [`round3`] are validated and decrypted.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Processing {
+    Serial,
+    #[cfg(feature = "parallel")]
+    Parallel,
+}
+
+#[cfg(feature = "parallel")]
+const DEFAULT_PROCESSING: Processing = Processing::Parallel;
+#[cfg(not(feature = "parallel"))]
+const DEFAULT_PROCESSING: Processing = Processing::Serial;
+
+impl Processing {
+    /// Applies `f` to each of the `packages`. The results are returned in the same order as
+    /// `packages`, regardless of how the work was scheduled.
+    fn map<T, U, F>(self, packages: &[T], f: F) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        match self {
+            Self::Serial => packages.iter().map(f).collect(),
+            #[cfg(feature = "parallel")]
+            Self::Parallel => packages.par_iter().map(f).collect(),
+        }
+    }
+}
+
+/// Completes the DKG ceremony, producing the key package of the owner of `secret` and the public
+/// key package of the group.
+///
+/// With the `parallel` feature enabled, the validation and decryption of the round 1 packages and
+/// the validation of the round 2 packages are spread across multiple threads. The output is the
+/// same as with serial processing.
 pub fn round3<'a, P, Q>(
     secret: &Secret,
     round2_secret_package: &[u8],
     round1_public_packages: P,
     round2_public_packages: Q,
 ) -> Result<(KeyPackage, PublicKeyPackage, GroupSecretKey), Error>
+where
+    P: IntoIterator<Item = &'a round1::PublicPackage>,
+    Q: IntoIterator<Item = &'a round2::CombinedPublicPackage>,
+{
+    round3_inner(
+        secret,
+        round2_secret_package,
+        round1_public_packages,
+        round2_public_packages,
+        DEFAULT_PROCESSING,
+    )
+}
+
+fn round3_inner<'a, P, Q>(
+    secret: &Secret,
+    round2_secret_package: &[u8],
+    round1_public_packages: P,
+    round2_public_packages: Q,
+    processing: Processing,
+) -> Result<(KeyPackage, PublicKeyPackage, GroupSecretKey), Error>
 where
     P: IntoIterator<Item = &'a round1::PublicPackage>,
     Q: IntoIterator<Item = &'a round2::CombinedPublicPackage>,
@@ -124,7 +398,7 @@ where
         .flat_map(|combo| combo.packages_for(&identity))
         .collect::<Vec<_>>();
 
-    let (min_signers, max_signers) = round2::get_secret_package_signers(&round2_secret_package);
+    let (min_signers, max_signers) = round2::secret_package_params(&round2_secret_package);
 
     // Ensure that the number of public packages provided matches max_signers
     let expected_round1_packages = max_signers as usize;
@@ -145,83 +419,105 @@ where
         )));
     }
 
+    let ceremony_id = round1::own_ceremony_id(round1_public_packages.iter().copied(), &identity)?;
+    if round1_public_packages
+        .iter()
+        .any(|pkg| *pkg.ceremony_id() != ceremony_id)
+    {
+        return Err(Error::InvalidInput(
+            "round 1 public packages belong to a different ceremony".to_string(),
+        ));
+    }
+
     let expected_round1_checksum = round1::input_checksum(
         min_signers,
+        &ceremony_id,
         round1_public_packages.iter().map(|pkg| pkg.identity()),
     );
 
-    let mut gsk_shards = Vec::new();
-    let mut round1_frost_packages = BTreeMap::new();
-    let mut identities = Vec::new();
-
-    for public_package in round1_public_packages.iter() {
-        if public_package.checksum() != expected_round1_checksum {
-            return Err(Error::ChecksumError(ChecksumError::DkgPublicPackageError));
-        }
-
-        let identity = public_package.identity();
-        let frost_identifier = identity.to_frost_identifier();
-        let frost_package = public_package.frost_package().clone();
-
-        if round1_frost_packages
-            .insert(frost_identifier, frost_package)
-            .is_some()
-        {
-            return Err(Error::InvalidInput(format!(
-                "multiple round 1 public packages provided for identity {}",
-                public_package.identity()
-            )));
-        }
-
-        let gsk_shard = public_package
-            .group_secret_key_shard(secret)
-            .map_err(Error::DecryptionError)?;
-        gsk_shards.push(gsk_shard);
-        identities.push(identity.clone());
-    }
-
-    // Sanity check
-    assert_eq!(round1_public_packages.len(), round1_frost_packages.len());
+    let mut round1_frost_packages = build_round1_frost_packages(
+        &round1_public_packages,
+        expected_round1_checksum,
+        processing,
+    )?;
+
+    let gsk_shards = processing
+        .map(&round1_public_packages, |public_package| {
+            public_package
+                .group_secret_key_shard(secret)
+                .map_err(Error::DecryptionError)
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    let identities = round1_public_packages
+        .iter()
+        .map(|public_package| public_package.identity().clone())
+        .collect::<Vec<_>>();
 
     // The public package for `identity` must be excluded from `frost::keys::dkg::part3`
     // inputs
     round1_frost_packages
         .remove(&identity.to_frost_identifier())
         .ok_or_else(|| {
-            Error::InvalidInput("missing round 1 public package for own identity".to_string())
+            Error::InvalidInput(format!(
+                "missing round 1 public package for own identity {}",
+                identity
+            ))
         })?;
 
     let expected_round2_checksum =
         round2::input_checksum(round1_public_packages.iter().map(Borrow::borrow));
 
-    let mut round2_frost_packages = BTreeMap::new();
-    for public_package in round2_public_packages.iter() {
-        if public_package.checksum() != expected_round2_checksum {
-            return Err(Error::ChecksumError(ChecksumError::DkgPublicPackageError));
-        }
+    processing
+        .map(&round2_public_packages, |public_package| {
+            if public_package.checksum() != expected_round2_checksum {
+                return Err(Error::ChecksumError(ChecksumError::DkgPublicPackageError));
+            }
 
-        if !identity.eq(public_package.recipient_identity()) {
-            return Err(Error::InvalidInput(format!(
-                "round 2 public package does not have the correct recipient identity {:?}",
-                public_package.recipient_identity().serialize()
-            )));
-        }
+            if !identity.eq(public_package.recipient_identity()) {
+                return Err(Error::InvalidInput(format!(
+                    "round 2 public package does not have the correct recipient identity {:?}",
+                    public_package.recipient_identity().serialize()
+                )));
+            }
 
-        let frost_identifier = public_package.sender_identity().to_frost_identifier();
-        let frost_package = public_package.frost_package().clone();
+            Ok(())
+        })
+        .into_iter()
+        .collect::<Result<(), _>>()?;
 
-        if round2_frost_packages
-            .insert(frost_identifier, frost_package)
-            .is_some()
-        {
-            return Err(Error::InvalidInput(format!(
-                "multiple round 2 public packages provided for identity {}",
-                public_package.sender_identity()
-            )));
-        }
+    // Identifiers are assigned serially, so that duplicates are always reported in the same way
+    let mut round2_frost_packages = BTreeMap::new();
+    let mut round2_identifiers = IdentifierMap::new();
+    for public_package in round2_public_packages.iter() {
+        let frost_identifier = round2_identifiers
+            .insert(public_package.sender_identity())
+            .map_err(|err| match err {
+                MappingError::Duplicate(identity) => Error::InvalidInput(format!(
+                    "multiple round 2 public packages provided for identity {}",
+                    identity
+                )),
+                MappingError::Collision(identities) => Error::IdentifierCollision { identities },
+            })?;
+        round2_frost_packages.insert(frost_identifier, public_package.frost_package().clone());
     }
 
-    assert_eq!(round2_public_packages.len(), round2_frost_packages.len());
+    // The round 2 packages must come from exactly the other round 1 participants
+    if round2_frost_packages
+        .keys()
+        .ne(round1_frost_packages.keys())
+    {
+        let unexpected = round2_frost_packages
+            .keys()
+            .filter(|identifier| !round1_frost_packages.contains_key(identifier))
+            .count();
+        return Err(Error::InvalidInput(format!(
+            "expected round 2 public packages from {} round 1 participants, got {} ({} from non-participants)",
+            round1_frost_packages.len(),
+            round2_frost_packages.len(),
+            unexpected
+        )));
+    }
 
     let (key_package, public_key_package) = part3(
         &round2_secret_package,
@@ -242,12 +538,21 @@ where
 
 #[cfg(test)]
 mod tests {
+    use super::build_round1_frost_packages;
+    use super::build_round1_frost_packages_streaming;
+    use super::check_round2_completeness;
     use super::round3;
+    #[cfg(feature = "parallel")]
+    use super::round3_inner;
+    use super::Processing;
     use super::PublicKeyPackage;
+    use crate::checksum::CHECKSUM_LEN;
     use crate::dkg::error::Error;
+    use crate::dkg::group_key::GroupSecretKeyShard;
     use crate::dkg::round1;
     use crate::dkg::round2;
     use crate::participant::Secret;
+    use crate::participant::IDENTITY_LEN;
     use hex_literal::hex;
     use rand::thread_rng;
     use reddsa::frost::redjubjub::keys::split;
@@ -352,12 +657,191 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_round3_missing_own_round1_package() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let secret3 = Secret::random(thread_rng());
+        let identity1 = secret1.to_identity();
+        let identity2 = secret2.to_identity();
+        let identity3 = secret3.to_identity();
+        let identities = [&identity1, &identity2, &identity3];
+
+        let (round1_secret_package_1, package1) =
+            round1::round1(&identity1, 2, identities, thread_rng()).expect("round 1 failed");
+        let (round1_secret_package_2, package2) =
+            round1::round1(&identity2, 2, identities, thread_rng()).expect("round 1 failed");
+        let (round1_secret_package_3, package3) =
+            round1::round1(&identity3, 2, identities, thread_rng()).expect("round 1 failed");
+        let round1_packages = [&package1, &package2, &package3];
+
+        let (encrypted_secret_package, _) = round2::round2(
+            &secret1,
+            &round1_secret_package_1,
+            round1_packages,
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+        let (_, round2_public_packages_2) = round2::round2(
+            &secret2,
+            &round1_secret_package_2,
+            round1_packages,
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+        let (_, round2_public_packages_3) = round2::round2(
+            &secret3,
+            &round1_secret_package_3,
+            round1_packages,
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        // The own package is replaced by a duplicate, so that the package count still matches
+        let result = round3(
+            &secret1,
+            &encrypted_secret_package,
+            [&package2, &package2, &package3],
+            [&round2_public_packages_2, &round2_public_packages_3],
+        );
+
+        match result {
+            Err(Error::InvalidInput(message)) => {
+                assert!(message.contains(&identity1.to_string()))
+            }
+            _ => panic!("dkg round3 should have failed with InvalidInput"),
+        }
+    }
+
+    #[test]
+    fn test_round3_undecryptable_group_secret_key_shard() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let identity1 = secret1.to_identity();
+        let identity2 = secret2.to_identity();
+
+        let (round1_secret_package_1, package1) =
+            round1::round1(&identity1, 2, [&identity1, &identity2], thread_rng())
+                .expect("round 1 failed");
+
+        let (round1_secret_package_2, package2) =
+            round1::round1(&identity2, 2, [&identity1, &identity2], thread_rng())
+                .expect("round 1 failed");
+
+        let (encrypted_secret_package, _) = round2::round2(
+            &secret1,
+            &round1_secret_package_1,
+            [&package1, &package2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        let (_, round2_public_packages) = round2::round2(
+            &secret2,
+            &round1_secret_package_2,
+            [&package1, &package2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        // Tamper with the last byte of the encrypted group secret key shard, which is followed by
+        // the checksum
+        let mut serialized = package2.serialize();
+        let offset = serialized.len() - CHECKSUM_LEN - 1;
+        serialized[offset] ^= 0xff;
+        let tampered_package2 = round1::PublicPackage::deserialize_from(&serialized[..])
+            .expect("deserialization failed");
+
+        let result = round3(
+            &secret1,
+            &encrypted_secret_package,
+            [&package1, &tampered_package2],
+            [&round2_public_packages],
+        );
+
+        match result {
+            Err(Error::DecryptionError(_)) => (),
+            _ => panic!("dkg round3 should have failed with DecryptionError"),
+        }
+    }
+
+    #[test]
+    fn test_round3_mismatched_round2_senders() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let secret3 = Secret::random(thread_rng());
+        let identity1 = secret1.to_identity();
+        let identity2 = secret2.to_identity();
+        let identity3 = secret3.to_identity();
+        let identity4 = Secret::random(thread_rng()).to_identity();
+        let identities = [&identity1, &identity2, &identity3];
+
+        let (round1_secret_package_1, package1) =
+            round1::round1(&identity1, 2, identities, thread_rng()).expect("round 1 failed");
+        let (round1_secret_package_2, package2) =
+            round1::round1(&identity2, 2, identities, thread_rng()).expect("round 1 failed");
+        let (round1_secret_package_3, package3) =
+            round1::round1(&identity3, 2, identities, thread_rng()).expect("round 1 failed");
+        let round1_packages = [&package1, &package2, &package3];
+
+        let (encrypted_secret_package, _) = round2::round2(
+            &secret1,
+            &round1_secret_package_1,
+            round1_packages,
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+        let (_, round2_public_packages_2) = round2::round2(
+            &secret2,
+            &round1_secret_package_2,
+            round1_packages,
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+        let (_, round2_public_packages_3) = round2::round2(
+            &secret3,
+            &round1_secret_package_3,
+            round1_packages,
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        // Attribute the packages of the third participant to someone that did not take part in
+        // round 1. The sender identity comes first in the serialization.
+        let mut serialized = round2_public_packages_3.serialize();
+        serialized[..IDENTITY_LEN].copy_from_slice(&identity4.serialize());
+        let forged_round2_public_packages =
+            round2::CombinedPublicPackage::deserialize_from(&serialized[..])
+                .expect("deserialization failed");
+
+        let result = round3(
+            &secret1,
+            &encrypted_secret_package,
+            round1_packages,
+            [&round2_public_packages_2, &forged_round2_public_packages],
+        );
+
+        match result {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("dkg round3 should have failed with InvalidInput"),
+        }
+
+        round3(
+            &secret1,
+            &encrypted_secret_package,
+            round1_packages,
+            [&round2_public_packages_2, &round2_public_packages_3],
+        )
+        .expect("round 3 failed");
+    }
+
     #[test]
     fn test_round3_invalid_round1_checksum() {
         let secret1 = Secret::random(thread_rng());
         let secret2 = Secret::random(thread_rng());
         let identity1 = secret1.to_identity();
         let identity2 = secret2.to_identity();
+        let identity3 = Secret::random(thread_rng()).to_identity();
 
         let (round1_secret_package_1, package1) =
             round1::round1(&identity1, 2, [&identity1, &identity2], thread_rng())
@@ -383,10 +867,19 @@ mod tests {
         )
         .expect("round 2 failed");
 
+        // A package from the same sender, but computed over a different list of participants
+        let (_, mismatched_package2) = round1::round1(
+            &identity2,
+            2,
+            [&identity1, &identity2, &identity3],
+            thread_rng(),
+        )
+        .expect("round 1 failed");
+
         let result = round3(
             &secret1,
             &encrypted_secret_package,
-            [&package1, &package1],
+            [&package1, &mismatched_package2],
             [&round2_public_packages],
         );
 
@@ -461,4 +954,391 @@ mod tests {
         )
         .expect("round 3 failed");
     }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_round3_matches_serial() {
+        let secrets = (0..5)
+            .map(|_| Secret::random(thread_rng()))
+            .collect::<Vec<_>>();
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let (round1_secret_packages, round1_public_packages): (Vec<_>, Vec<_>) = identities
+            .iter()
+            .map(|identity| {
+                round1::round1(identity, 3, &identities, thread_rng()).expect("round 1 failed")
+            })
+            .unzip();
+
+        let (round2_secret_packages, round2_public_packages): (Vec<_>, Vec<_>) = secrets
+            .iter()
+            .zip(round1_secret_packages.iter())
+            .map(|(secret, round1_secret_package)| {
+                round2::round2(
+                    secret,
+                    round1_secret_package,
+                    &round1_public_packages,
+                    thread_rng(),
+                )
+                .expect("round 2 failed")
+            })
+            .unzip();
+
+        for (i, secret) in secrets.iter().enumerate() {
+            let round2_public_packages = round2_public_packages
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, package)| package)
+                .collect::<Vec<_>>();
+
+            let serial = round3_inner(
+                secret,
+                &round2_secret_packages[i],
+                &round1_public_packages,
+                round2_public_packages.iter().copied(),
+                Processing::Serial,
+            )
+            .expect("serial round 3 failed");
+            let parallel = round3_inner(
+                secret,
+                &round2_secret_packages[i],
+                &round1_public_packages,
+                round2_public_packages.iter().copied(),
+                Processing::Parallel,
+            )
+            .expect("parallel round 3 failed");
+
+            assert_eq!(serial, parallel);
+        }
+    }
+
+    #[test]
+    fn build_round1_frost_packages_duplicate_sender() {
+        let identity1 = Secret::random(thread_rng()).to_identity();
+        let identity2 = Secret::random(thread_rng()).to_identity();
+
+        let (_, package1) = round1::round1(&identity1, 2, [&identity1, &identity2], thread_rng())
+            .expect("round 1 failed");
+        let (_, package2) = round1::round1(&identity2, 2, [&identity1, &identity2], thread_rng())
+            .expect("round 1 failed");
+
+        // All packages share the same checksum, so only the duplicate check can catch this
+        let result = build_round1_frost_packages(
+            &[&package1, &package2, &package1],
+            package1.checksum(),
+            Processing::Serial,
+        );
+
+        match result {
+            Err(Error::InvalidInput(message)) => {
+                assert!(message.contains(&identity1.to_string()))
+            }
+            _ => panic!("duplicate round 1 package should have been rejected"),
+        }
+
+        build_round1_frost_packages(
+            &[&package1, &package2],
+            package1.checksum(),
+            Processing::Serial,
+        )
+        .expect("building round 1 packages failed");
+    }
+
+    #[test]
+    fn test_round3_duplicate_round1_packages() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let identity1 = secret1.to_identity();
+        let identity2 = secret2.to_identity();
+
+        let (round1_secret_package_1, package1) =
+            round1::round1(&identity1, 2, [&identity1, &identity2], thread_rng())
+                .expect("round 1 failed");
+
+        let (round1_secret_package_2, package2) =
+            round1::round1(&identity2, 2, [&identity1, &identity2], thread_rng())
+                .expect("round 1 failed");
+
+        let (encrypted_secret_package, _) = round2::round2(
+            &secret1,
+            &round1_secret_package_1,
+            [&package1, &package2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        let (_, round2_public_packages) = round2::round2(
+            &secret2,
+            &round1_secret_package_2,
+            [&package1, &package2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        // The package count matches, but one of the senders is missing and the other is repeated
+        let result = round3(
+            &secret1,
+            &encrypted_secret_package,
+            [&package1, &package1],
+            [&round2_public_packages],
+        );
+
+        match result {
+            Err(Error::InvalidInput(message)) => {
+                assert!(message.contains(&identity1.to_string()))
+            }
+            _ => panic!("duplicate round 1 package should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn check_round2_completeness_skipped_participant() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let secret3 = Secret::random(thread_rng());
+        let identity1 = secret1.to_identity();
+        let identity2 = secret2.to_identity();
+        let identity3 = secret3.to_identity();
+        let identities = [&identity1, &identity2, &identity3];
+
+        let (_, package1) =
+            round1::round1(&identity1, 2, identities, thread_rng()).expect("round 1 failed");
+        let (round1_secret_package_2, package2) =
+            round1::round1(&identity2, 2, identities, thread_rng()).expect("round 1 failed");
+        let (round1_secret_package_3, package3) =
+            round1::round1(&identity3, 2, identities, thread_rng()).expect("round 1 failed");
+
+        let (_, round2_public_packages_2) = round2::round2(
+            &secret2,
+            &round1_secret_package_2,
+            [&package1, &package2, &package3],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+        let (_, round2_public_packages_3) = round2::round2(
+            &secret3,
+            &round1_secret_package_3,
+            [&package1, &package2, &package3],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        check_round2_completeness(
+            [&package1, &package2, &package3],
+            [&round2_public_packages_2, &round2_public_packages_3],
+            &identity1,
+        )
+        .expect("complete round 2 packages were rejected");
+
+        assert_eq!(
+            check_round2_completeness(
+                [&package1, &package2, &package3],
+                [&round2_public_packages_2],
+                &identity1,
+            ),
+            Err(vec![identity3])
+        );
+    }
+
+    #[test]
+    fn build_round1_frost_packages_streaming_matches_collecting() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let secret3 = Secret::random(thread_rng());
+        let identities = [
+            &secret1.to_identity(),
+            &secret2.to_identity(),
+            &secret3.to_identity(),
+        ];
+
+        let (_, package1) =
+            round1::round1(identities[0], 2, identities, thread_rng()).expect("round 1 failed");
+        let (_, package2) =
+            round1::round1(identities[1], 2, identities, thread_rng()).expect("round 1 failed");
+        let (_, package3) =
+            round1::round1(identities[2], 2, identities, thread_rng()).expect("round 1 failed");
+        let packages = [&package1, &package2, &package3];
+
+        let collected =
+            build_round1_frost_packages(&packages, package1.checksum(), Processing::Serial)
+                .expect("building round 1 packages failed");
+        let gsk_shards = packages
+            .iter()
+            .map(|package| {
+                package
+                    .group_secret_key_shard(&secret1)
+                    .expect("decryption failed")
+            })
+            .collect::<Vec<_>>();
+
+        let streamed = build_round1_frost_packages_streaming(&secret1, packages, 2, 3)
+            .expect("building round 1 packages failed");
+
+        assert_eq!(streamed.frost_packages(), &collected);
+        assert_eq!(
+            streamed.group_secret_key(),
+            &GroupSecretKeyShard::combine(&gsk_shards)
+        );
+        assert_eq!(
+            streamed.identities(),
+            [
+                identities[0].clone(),
+                identities[1].clone(),
+                identities[2].clone()
+            ]
+        );
+
+        match build_round1_frost_packages_streaming(&secret1, [&package1, &package2], 2, 3) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("missing round 1 package should have been rejected"),
+        }
+
+        match build_round1_frost_packages_streaming(&secret1, packages, 3, 3) {
+            Err(Error::ChecksumError(_)) => (),
+            _ => panic!("mismatched min_signers should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn ceremony_id_cross_contamination() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let identity1 = secret1.to_identity();
+        let identity2 = secret2.to_identity();
+        let identities = [&identity1, &identity2];
+
+        let ceremony = |ceremony_id| {
+            let (secret_package_1, package1) = round1::round1_with_ceremony_id(
+                &identity1,
+                2,
+                identities,
+                ceremony_id,
+                thread_rng(),
+            )
+            .expect("round 1 failed");
+            let (secret_package_2, package2) = round1::round1_with_ceremony_id(
+                &identity2,
+                2,
+                identities,
+                ceremony_id,
+                thread_rng(),
+            )
+            .expect("round 1 failed");
+            (secret_package_1, package1, secret_package_2, package2)
+        };
+        let (secret_package_a1, package_a1, secret_package_a2, package_a2) = ceremony([1u8; 16]);
+        let (_, package_b1, _, package_b2) = ceremony([2u8; 16]);
+        assert_ne!(package_a1.checksum(), package_b1.checksum());
+
+        // Round 2 rejects a package from a different ceremony
+        let result = round2::round2(
+            &secret1,
+            &secret_package_a1,
+            [&package_a1, &package_b2],
+            thread_rng(),
+        );
+        match result {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("round 1 package from a different ceremony should have been rejected"),
+        }
+
+        let (round2_secret_package_1, _) = round2::round2(
+            &secret1,
+            &secret_package_a1,
+            [&package_a1, &package_a2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+        let (_, round2_public_packages_2) = round2::round2(
+            &secret2,
+            &secret_package_a2,
+            [&package_a1, &package_a2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        // Round 3 rejects a package from a different ceremony
+        let result = round3(
+            &secret1,
+            &round2_secret_package_1,
+            [&package_a1, &package_b2],
+            [&round2_public_packages_2],
+        );
+        match result {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("round 1 package from a different ceremony should have been rejected"),
+        }
+
+        // Even when all the round 1 packages come from the other ceremony
+        let result = round3(
+            &secret1,
+            &round2_secret_package_1,
+            [&package_b1, &package_b2],
+            [&round2_public_packages_2],
+        );
+        assert!(
+            result.is_err(),
+            "round 1 packages from a different ceremony should have been rejected"
+        );
+
+        round3(
+            &secret1,
+            &round2_secret_package_1,
+            [&package_a1, &package_a2],
+            [&round2_public_packages_2],
+        )
+        .expect("round 3 failed");
+    }
+
+    #[test]
+    fn round3_with_reexported_secret_package() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let identity1 = secret1.to_identity();
+        let identity2 = secret2.to_identity();
+
+        let (round1_secret_package_1, package1) =
+            round1::round1(&identity1, 2, [&identity1, &identity2], thread_rng())
+                .expect("round 1 failed");
+
+        let (round1_secret_package_2, package2) =
+            round1::round1(&identity2, 2, [&identity1, &identity2], thread_rng())
+                .expect("round 1 failed");
+
+        let (encrypted_secret_package, _) = round2::round2(
+            &secret1,
+            &round1_secret_package_1,
+            [&package1, &package2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        let (_, round2_public_packages) = round2::round2(
+            &secret2,
+            &round1_secret_package_2,
+            [&package1, &package2],
+            thread_rng(),
+        )
+        .expect("round 2 failed");
+
+        // Simulate a restart: restore the in-memory secret package, then persist it again
+        let secret_package = round2::import_secret_package(&encrypted_secret_package, &secret1)
+            .expect("secret package import failed");
+        let reexported_secret_package =
+            round2::export_secret_package(&secret_package, &identity1, thread_rng())
+                .expect("secret package export failed");
+        assert_ne!(encrypted_secret_package, reexported_secret_package);
+
+        round3(
+            &secret1,
+            &reexported_secret_package,
+            [&package1, &package2],
+            [&round2_public_packages],
+        )
+        .expect("round 3 failed");
+    }
 }
@@ -0,0 +1,419 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Per-participant state machine that drives the three DKG rounds.
+
+use crate::dkg::error::Error;
+use crate::dkg::group_key::GroupSecretKey;
+use crate::dkg::round1;
+use crate::dkg::round2;
+use crate::dkg::round3;
+use crate::dkg::round3::PublicKeyPackage;
+use crate::frost::keys::KeyPackage;
+use crate::participant::Identity;
+use crate::participant::Secret;
+use crate::serde::read_u16;
+use crate::serde::read_variable_length;
+use crate::serde::read_variable_length_bytes;
+use crate::serde::write_u16;
+use crate::serde::write_variable_length;
+use crate::serde::write_variable_length_bytes;
+use rand_core::CryptoRng;
+use rand_core::RngCore;
+use std::fmt;
+use std::io;
+
+/// Stage of a [`DkgSession`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SessionState {
+    /// Round 1 packages were generated, and the session is waiting for the round 1 packages of
+    /// all the participants.
+    AwaitingRound1,
+    /// Round 2 packages were generated, and the session is waiting for the round 2 packages of
+    /// all the other participants.
+    AwaitingRound2,
+    /// All the packages were received, and the session can be finalized.
+    ReadyToFinalize,
+}
+
+impl SessionState {
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::AwaitingRound1 => 1,
+            Self::AwaitingRound2 => 2,
+            Self::ReadyToFinalize => 3,
+        }
+    }
+
+    fn from_u8(value: u8) -> io::Result<Self> {
+        match value {
+            1 => Ok(Self::AwaitingRound1),
+            2 => Ok(Self::AwaitingRound2),
+            3 => Ok(Self::ReadyToFinalize),
+            _ => Err(io::Error::other("invalid session state")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    /// The operation is not allowed in the current state of the session.
+    OutOfOrder {
+        expected: SessionState,
+        actual: SessionState,
+    },
+    /// Some participants did not send their packages.
+    MissingParticipants(Vec<Identity>),
+    DkgError(Error),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::OutOfOrder { expected, actual } => write!(
+                f,
+                "operation requires session state {:?}, but session is in state {:?}",
+                expected, actual
+            ),
+            Self::MissingParticipants(identities) => {
+                write!(f, "missing packages from participants: ")?;
+                for (index, identity) in identities.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", identity)?;
+                }
+                Ok(())
+            }
+            Self::DkgError(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<Error> for SessionError {
+    fn from(error: Error) -> Self {
+        Self::DkgError(error)
+    }
+}
+
+/// State of a DKG ceremony from the point of view of a single participant.
+///
+/// The session keeps track of the secret packages between rounds, and of the public packages
+/// needed by later rounds. Secret packages are kept encrypted for the participant (as returned by
+/// [`round1::round1`] and [`round2::round2`]), and the participant [`Secret`] is never stored,
+/// so the session can be persisted with [`DkgSession::serialize`] and resumed with
+/// [`DkgSession::deserialize_from`] after a restart.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DkgSession {
+    state: SessionState,
+    identity: Identity,
+    participants: Vec<Identity>,
+    min_signers: u16,
+    secret_package: Vec<u8>,
+    round1_public_packages: Vec<round1::PublicPackage>,
+    round2_public_packages: Vec<round2::CombinedPublicPackage>,
+}
+
+impl DkgSession {
+    /// Starts a DKG ceremony, running round 1 for `identity`.
+    ///
+    /// Returns the new session, along with the round 1 public package that must be sent to all the
+    /// other `participants`.
+    pub fn begin<R: RngCore + CryptoRng>(
+        identity: &Identity,
+        min_signers: u16,
+        participants: &[Identity],
+        csrng: R,
+    ) -> Result<(Self, round1::PublicPackage), SessionError> {
+        let (secret_package, public_package) =
+            round1::round1(identity, min_signers, participants, csrng)?;
+
+        let mut participants = participants.to_vec();
+        participants.sort_unstable();
+        participants.dedup();
+
+        let session = Self {
+            state: SessionState::AwaitingRound1,
+            identity: identity.clone(),
+            participants,
+            min_signers,
+            secret_package,
+            round1_public_packages: Vec::new(),
+            round2_public_packages: Vec::new(),
+        };
+        Ok((session, public_package))
+    }
+
+    #[must_use]
+    pub fn state(&self) -> SessionState {
+        self.state
+    }
+
+    #[must_use]
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+
+    #[must_use]
+    pub fn participants(&self) -> &[Identity] {
+        &self.participants
+    }
+
+    #[must_use]
+    pub fn min_signers(&self) -> u16 {
+        self.min_signers
+    }
+
+    fn expect_state(&self, expected: SessionState) -> Result<(), SessionError> {
+        if self.state == expected {
+            Ok(())
+        } else {
+            Err(SessionError::OutOfOrder {
+                expected,
+                actual: self.state,
+            })
+        }
+    }
+
+    fn expect_secret(&self, secret: &Secret) -> Result<(), SessionError> {
+        if secret.to_identity() == self.identity {
+            Ok(())
+        } else {
+            Err(
+                Error::InvalidInput("secret does not match the session identity".to_string())
+                    .into(),
+            )
+        }
+    }
+
+    /// Receives the round 1 public packages of all the participants (including the one returned by
+    /// [`DkgSession::begin`]) and runs round 2.
+    ///
+    /// Returns the round 2 public packages that must be sent to the other participants.
+    pub fn receive_round1<R: RngCore + CryptoRng>(
+        &mut self,
+        secret: &Secret,
+        round1_public_packages: &[round1::PublicPackage],
+        csrng: R,
+    ) -> Result<round2::CombinedPublicPackage, SessionError> {
+        self.expect_state(SessionState::AwaitingRound1)?;
+        self.expect_secret(secret)?;
+
+        let missing = self
+            .participants
+            .iter()
+            .filter(|participant| {
+                !round1_public_packages
+                    .iter()
+                    .any(|pkg| pkg.identity() == *participant)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(SessionError::MissingParticipants(missing));
+        }
+
+        let (secret_package, public_package) =
+            round2::round2(secret, &self.secret_package, round1_public_packages, csrng)?;
+
+        self.secret_package = secret_package;
+        self.round1_public_packages = round1_public_packages.to_vec();
+        self.state = SessionState::AwaitingRound2;
+        Ok(public_package)
+    }
+
+    /// Receives the round 2 public packages from all the other participants.
+    pub fn receive_round2(
+        &mut self,
+        round2_public_packages: &[round2::CombinedPublicPackage],
+    ) -> Result<(), SessionError> {
+        self.expect_state(SessionState::AwaitingRound2)?;
+
+        round3::check_round2_completeness(
+            &self.round1_public_packages,
+            round2_public_packages,
+            &self.identity,
+        )
+        .map_err(SessionError::MissingParticipants)?;
+
+        self.round2_public_packages = round2_public_packages.to_vec();
+        self.state = SessionState::ReadyToFinalize;
+        Ok(())
+    }
+
+    /// Runs round 3, producing the key material of the participant.
+    pub fn finalize(
+        &self,
+        secret: &Secret,
+    ) -> Result<(KeyPackage, PublicKeyPackage, GroupSecretKey), SessionError> {
+        self.expect_state(SessionState::ReadyToFinalize)?;
+        self.expect_secret(secret)?;
+
+        round3::round3(
+            secret,
+            &self.secret_package,
+            &self.round1_public_packages,
+            &self.round2_public_packages,
+        )
+        .map_err(SessionError::DkgError)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.serialize_into(&mut buf).expect("serialization failed");
+        buf
+    }
+
+    pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&[self.state.to_u8()])?;
+        self.identity.serialize_into(&mut writer)?;
+        write_variable_length(&mut writer, &self.participants, |writer, identity| {
+            identity.serialize_into(writer)
+        })?;
+        write_u16(&mut writer, self.min_signers)?;
+        write_variable_length_bytes(&mut writer, &self.secret_package)?;
+        write_variable_length(&mut writer, &self.round1_public_packages, |writer, pkg| {
+            pkg.serialize_into(writer)
+        })?;
+        write_variable_length(&mut writer, &self.round2_public_packages, |writer, pkg| {
+            pkg.serialize_into(writer)
+        })?;
+        Ok(())
+    }
+
+    pub fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut state = [0u8; 1];
+        reader.read_exact(&mut state)?;
+        let state = SessionState::from_u8(state[0])?;
+
+        let identity = Identity::deserialize_from(&mut reader)?;
+        let participants =
+            read_variable_length(&mut reader, |reader| Identity::deserialize_from(reader))?;
+        let min_signers = read_u16(&mut reader)?;
+        let secret_package = read_variable_length_bytes(&mut reader)?;
+        let round1_public_packages = read_variable_length(&mut reader, |reader| {
+            round1::PublicPackage::deserialize_from(reader)
+        })?;
+        let round2_public_packages = read_variable_length(&mut reader, |reader| {
+            round2::CombinedPublicPackage::deserialize_from(reader)
+        })?;
+
+        Ok(Self {
+            state,
+            identity,
+            participants,
+            min_signers,
+            secret_package,
+            round1_public_packages,
+            round2_public_packages,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DkgSession;
+    use super::SessionError;
+    use super::SessionState;
+    use crate::participant::Secret;
+    use rand::thread_rng;
+
+    #[test]
+    fn end_to_end() {
+        let secrets = (0..3)
+            .map(|_| Secret::random(thread_rng()))
+            .collect::<Vec<_>>();
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let (mut sessions, round1_packages): (Vec<_>, Vec<_>) = identities
+            .iter()
+            .map(|identity| {
+                DkgSession::begin(identity, 2, &identities, thread_rng()).expect("begin failed")
+            })
+            .unzip();
+
+        // Round 2 packages cannot be received before round 1 is complete
+        match sessions[0].receive_round2(&[]) {
+            Err(SessionError::OutOfOrder { expected, actual }) => {
+                assert_eq!(expected, SessionState::AwaitingRound2);
+                assert_eq!(actual, SessionState::AwaitingRound1);
+            }
+            _ => panic!("out of order call should have been rejected"),
+        }
+
+        // All the participants must send their round 1 packages
+        match sessions[0].receive_round1(&secrets[0], &round1_packages[..2], thread_rng()) {
+            Err(SessionError::MissingParticipants(missing)) => {
+                assert_eq!(missing, [identities[2].clone()])
+            }
+            _ => panic!("missing round 1 package should have been rejected"),
+        }
+
+        let round2_packages = sessions
+            .iter_mut()
+            .zip(secrets.iter())
+            .map(|(session, secret)| {
+                session
+                    .receive_round1(secret, &round1_packages, thread_rng())
+                    .expect("receiving round 1 packages failed")
+            })
+            .collect::<Vec<_>>();
+
+        // Persist and resume all the sessions
+        let mut sessions = sessions
+            .iter()
+            .map(|session| {
+                let resumed = DkgSession::deserialize_from(&session.serialize()[..])
+                    .expect("session deserialization failed");
+                assert_eq!(&resumed, session);
+                resumed
+            })
+            .collect::<Vec<_>>();
+
+        for (index, session) in sessions.iter_mut().enumerate() {
+            let others = round2_packages
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, pkg)| pkg.clone())
+                .collect::<Vec<_>>();
+
+            match session.receive_round2(&others[..1]) {
+                Err(SessionError::MissingParticipants(missing)) => assert_eq!(missing.len(), 1),
+                _ => panic!("missing round 2 package should have been rejected"),
+            }
+            assert_eq!(session.state(), SessionState::AwaitingRound2);
+
+            session
+                .receive_round2(&others)
+                .expect("receiving round 2 packages failed");
+        }
+
+        let results = sessions
+            .iter()
+            .map(|session| {
+                DkgSession::deserialize_from(&session.serialize()[..])
+                    .expect("session deserialization failed")
+            })
+            .zip(secrets.iter())
+            .map(|(session, secret)| session.finalize(secret).expect("finalize failed"))
+            .collect::<Vec<_>>();
+
+        for (_, public_key_package, group_secret_key) in &results[1..] {
+            assert_eq!(public_key_package, &results[0].1);
+            assert_eq!(group_secret_key, &results[0].2);
+        }
+
+        // A session cannot be finalized with the wrong secret
+        match sessions[0].finalize(&secrets[1]) {
+            Err(SessionError::DkgError(_)) => (),
+            _ => panic!("finalizing with the wrong secret should have failed"),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::fmt;
+
+/// Advisory warning about a threshold configuration that is valid, but likely insecure or
+/// impractical.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ThresholdWarning {
+    /// A single participant is able to produce signatures on behalf of the whole group.
+    SingleSigner { max_signers: usize },
+    /// All participants are required to sign, so losing any single key share makes the group
+    /// unable to sign.
+    NoFaultTolerance { max_signers: usize },
+}
+
+impl fmt::Display for ThresholdWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::SingleSigner { max_signers } => write!(
+                f,
+                "threshold of 1 allows any of the {} participants to sign alone",
+                max_signers
+            ),
+            Self::NoFaultTolerance { max_signers } => write!(
+                f,
+                "threshold requires all {} participants to sign, no key share can be lost",
+                max_signers
+            ),
+        }
+    }
+}
+
+/// Checks whether a `min_signers`-of-`max_signers` configuration is likely a misconfiguration.
+///
+/// This check is advisory: the configurations it flags are accepted by the DKG ceremony, and
+/// callers are free to ignore the returned warning.
+pub fn validate_threshold(min_signers: usize, max_signers: usize) -> Result<(), ThresholdWarning> {
+    if max_signers > 1 && min_signers == 1 {
+        Err(ThresholdWarning::SingleSigner { max_signers })
+    } else if max_signers > 1 && min_signers == max_signers {
+        Err(ThresholdWarning::NoFaultTolerance { max_signers })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_threshold;
+    use super::ThresholdWarning;
+
+    #[test]
+    fn insecure_thresholds() {
+        assert_eq!(
+            validate_threshold(1, 3),
+            Err(ThresholdWarning::SingleSigner { max_signers: 3 })
+        );
+        assert_eq!(
+            validate_threshold(3, 3),
+            Err(ThresholdWarning::NoFaultTolerance { max_signers: 3 })
+        );
+    }
+
+    #[test]
+    fn reasonable_thresholds() {
+        assert_eq!(validate_threshold(2, 3), Ok(()));
+        assert_eq!(validate_threshold(3, 5), Ok(()));
+        assert_eq!(validate_threshold(1, 1), Ok(()));
+    }
+}
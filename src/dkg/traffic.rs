@@ -0,0 +1,151 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::checksum::CHECKSUM_LEN;
+use crate::dkg::group_key::GROUP_SECRET_KEY_LEN;
+use crate::frost::keys::dkg::part1;
+use crate::frost::keys::dkg::round2::Package as Round2Package;
+use crate::frost::keys::SigningShare;
+use crate::frost::Identifier;
+use crate::multienc;
+use crate::participant::IDENTITY_LEN;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+
+/// Length of the prefix used by variable-length fields (see `crate::serde`).
+const LENGTH_PREFIX_LEN: usize = 4;
+
+/// Estimated amount of data exchanged during a DKG ceremony, in bytes.
+///
+/// Each package is counted once per sender, regardless of how it is delivered to the recipients
+/// (for example, a broadcast to all participants counts as a single package).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TrafficEstimate {
+    /// Total size of the [`round1::PublicPackage`](crate::dkg::round1::PublicPackage) structs
+    /// produced by all participants.
+    pub round1_bytes: usize,
+    /// Total size of the
+    /// [`round2::CombinedPublicPackage`](crate::dkg::round2::CombinedPublicPackage) structs
+    /// produced by all participants.
+    pub round2_bytes: usize,
+    /// Round 3 is computed locally by each participant and does not exchange any data, so this is
+    /// always 0.
+    pub round3_bytes: usize,
+}
+
+impl TrafficEstimate {
+    #[must_use]
+    pub fn total_bytes(&self) -> usize {
+        self.round1_bytes + self.round2_bytes + self.round3_bytes
+    }
+}
+
+/// Computes the amount of data that a DKG ceremony with `participant_count` participants and a
+/// threshold of `min_signers` will exchange.
+///
+/// The sizes of the FROST packages are obtained by serializing sample packages, so this performs
+/// some elliptic curve operations.
+///
+/// The estimate is for a ceremony started with [`round1`](crate::dkg::round1::round1). With
+/// [`round1_with_ceremony_id`](crate::dkg::round1::round1_with_ceremony_id), each round 1 package
+/// is [`CEREMONY_ID_LEN`](crate::dkg::round1::CEREMONY_ID_LEN) bytes larger.
+///
+/// # Panics
+///
+/// Panics if `min_signers` and `participant_count` are not a valid FROST configuration.
+#[must_use]
+pub fn estimate_traffic(participant_count: u16, min_signers: u16) -> TrafficEstimate {
+    let n = participant_count as usize;
+
+    let identifier = Identifier::try_from(1).expect("invalid identifier");
+    let (_, frost_round1_package) = part1(
+        identifier,
+        participant_count,
+        min_signers,
+        ChaCha20Rng::from_seed([0u8; 32]),
+    )
+    .expect("invalid threshold configuration");
+    let frost_round1_package_len = frost_round1_package
+        .serialize()
+        .expect("serialization failed")
+        .len();
+    let frost_round2_package_len = Round2Package::new(SigningShare::default())
+        .serialize()
+        .expect("serialization failed")
+        .len();
+
+    let round1_package_len = IDENTITY_LEN
+        + LENGTH_PREFIX_LEN
+        + frost_round1_package_len
+        + multienc::metadata_size(n)
+        + GROUP_SECRET_KEY_LEN
+        + CHECKSUM_LEN;
+
+    let round2_package_len =
+        IDENTITY_LEN + LENGTH_PREFIX_LEN + frost_round2_package_len + CHECKSUM_LEN;
+    let round2_combined_package_len =
+        IDENTITY_LEN + LENGTH_PREFIX_LEN + round2_package_len * n.saturating_sub(1);
+
+    TrafficEstimate {
+        round1_bytes: round1_package_len * n,
+        round2_bytes: round2_combined_package_len * n,
+        round3_bytes: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::estimate_traffic;
+    use crate::dkg::round1;
+    use crate::dkg::round2;
+    use crate::participant::Secret;
+    use rand::thread_rng;
+
+    #[test]
+    fn estimate_matches_actual_sizes() {
+        let secrets = (0..3)
+            .map(|_| Secret::random(thread_rng()))
+            .collect::<Vec<_>>();
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let round1_packages = identities
+            .iter()
+            .map(|identity| {
+                round1::round1(identity, 2, &identities, thread_rng()).expect("round 1 failed")
+            })
+            .collect::<Vec<_>>();
+
+        let round2_packages = secrets
+            .iter()
+            .zip(round1_packages.iter())
+            .map(|(secret, (secret_package, _))| {
+                round2::round2(
+                    secret,
+                    secret_package,
+                    round1_packages.iter().map(|(_, package)| package),
+                    thread_rng(),
+                )
+                .expect("round 2 failed")
+                .1
+            })
+            .collect::<Vec<_>>();
+
+        let round1_bytes = round1_packages
+            .iter()
+            .map(|(_, package)| package.serialize().len())
+            .sum::<usize>();
+        let round2_bytes = round2_packages
+            .iter()
+            .map(|package| package.serialize().len())
+            .sum::<usize>();
+
+        let estimate = estimate_traffic(3, 2);
+        assert_eq!(estimate.round1_bytes, round1_bytes);
+        assert_eq!(estimate.round2_bytes, round2_bytes);
+        assert_eq!(estimate.total_bytes(), round1_bytes + round2_bytes);
+    }
+}
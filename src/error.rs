@@ -0,0 +1,55 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::checksum::ChecksumError;
+use crate::frost;
+use crate::frost::Identifier;
+use crate::participant::Identity;
+use alloc::string::String;
+use core::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidInput(String),
+    FrostError(frost::Error),
+    ChecksumError(ChecksumError),
+    IdentifierCollision { identities: [Identity; 2] },
+    MissingSignatureShare(Identifier),
+    DuplicateSignatureShare(Identity),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::InvalidInput(e) => {
+                write!(f, "invalid input: ")?;
+                e.fmt(f)
+            }
+            Self::FrostError(e) => {
+                write!(f, "frost error: ")?;
+                e.fmt(f)
+            }
+            Self::ChecksumError(e) => {
+                write!(f, "checksum error: ")?;
+                e.fmt(f)
+            }
+            Self::IdentifierCollision { identities } => write!(
+                f,
+                "identities {} and {} map to the same identifier",
+                identities[0], identities[1]
+            ),
+            Self::MissingSignatureShare(identifier) => {
+                write!(f, "missing signature share for signer {:?}", identifier)
+            }
+            Self::DuplicateSignatureShare(identity) => write!(
+                f,
+                "multiple signature shares provided for identity {}",
+                identity
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
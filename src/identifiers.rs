@@ -0,0 +1,102 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::frost::Identifier;
+use crate::participant::Identity;
+use alloc::collections::BTreeMap;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) enum MappingError<'a> {
+    /// The same identity was inserted more than once.
+    Duplicate(&'a Identity),
+    /// Two different identities were mapped to the same identifier.
+    Collision([Identity; 2]),
+}
+
+/// Tracks the mapping from [`Identity`] to FROST [`Identifier`], detecting identities that map to
+/// the same identifier instead of silently overwriting them.
+#[derive(Debug)]
+pub(crate) struct IdentifierMap<'a, F = fn(&Identity) -> Identifier> {
+    identities: BTreeMap<Identifier, &'a Identity>,
+    to_identifier: F,
+}
+
+impl<'a> IdentifierMap<'a> {
+    pub(crate) fn new() -> Self {
+        Self::with_mapping(Identity::to_frost_identifier)
+    }
+}
+
+impl<'a, F> IdentifierMap<'a, F>
+where
+    F: Fn(&Identity) -> Identifier,
+{
+    pub(crate) fn with_mapping(to_identifier: F) -> Self {
+        Self {
+            identities: BTreeMap::new(),
+            to_identifier,
+        }
+    }
+
+    pub(crate) fn get(&self, identifier: &Identifier) -> Option<&'a Identity> {
+        self.identities.get(identifier).copied()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.identities.len()
+    }
+
+    /// Records `identity`, returning its identifier.
+    pub(crate) fn insert(
+        &mut self,
+        identity: &'a Identity,
+    ) -> Result<Identifier, MappingError<'a>> {
+        let identifier = (self.to_identifier)(identity);
+        match self.identities.insert(identifier, identity) {
+            None => Ok(identifier),
+            Some(previous) if previous == identity => Err(MappingError::Duplicate(identity)),
+            Some(previous) => Err(MappingError::Collision([
+                previous.clone(),
+                identity.clone(),
+            ])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdentifierMap;
+    use super::MappingError;
+    use crate::frost::Identifier;
+    use crate::participant::Secret;
+    use rand::thread_rng;
+
+    #[test]
+    fn distinct_identities() {
+        let identity1 = Secret::random(thread_rng()).to_identity();
+        let identity2 = Secret::random(thread_rng()).to_identity();
+
+        let mut map = IdentifierMap::new();
+        assert_eq!(map.insert(&identity1), Ok(identity1.to_frost_identifier()));
+        assert_eq!(map.insert(&identity2), Ok(identity2.to_frost_identifier()));
+        assert_eq!(
+            map.insert(&identity1),
+            Err(MappingError::Duplicate(&identity1))
+        );
+    }
+
+    #[test]
+    fn collision() {
+        let identity1 = Secret::random(thread_rng()).to_identity();
+        let identity2 = Secret::random(thread_rng()).to_identity();
+
+        let mut map =
+            IdentifierMap::with_mapping(|_| Identifier::try_from(1).expect("invalid identifier"));
+        map.insert(&identity1).expect("insert failed");
+        assert_eq!(
+            map.insert(&identity2),
+            Err(MappingError::Collision([identity1, identity2]))
+        );
+    }
+}
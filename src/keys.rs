@@ -0,0 +1,692 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Utilities to work with the keys produced by a DKG ceremony.
+
+use crate::dkg::error::Error;
+use crate::dkg::round3::PublicKeyPackage;
+use crate::frost::keys::KeyPackage;
+use crate::frost::keys::PublicKeyPackage as FrostPublicKeyPackage;
+use crate::frost::keys::SigningShare;
+use crate::frost::Field;
+use crate::frost::Group;
+use crate::frost::Identifier;
+use crate::frost::JubjubGroup;
+use crate::frost::JubjubScalarField;
+use crate::multienc;
+use crate::participant::Identity;
+use crate::participant::Secret;
+use rand_core::CryptoRng;
+use rand_core::RngCore;
+use std::io;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+pub const PUBLIC_KEY_PACKAGE_HASH_LEN: usize = 32;
+
+pub type PublicKeyPackageHash = [u8; PUBLIC_KEY_PACKAGE_HASH_LEN];
+
+const PUBLIC_KEY_PACKAGE_HASH_CONTEXT: &str = "ironfish-frost 2024 PublicKeyPackage hash";
+const MEMBERSHIP_TOKEN_CONTEXT: &str = "ironfish-frost 2024 membership token challenge";
+
+pub const MEMBERSHIP_TOKEN_LEN: usize = 96;
+
+type Scalar = <JubjubScalarField as Field>::Scalar;
+type Element = <JubjubGroup as Group>::Element;
+
+/// Computes a canonical hash of `public_key_package`, suitable to identify a group (for example,
+/// in an on-chain commitment).
+///
+/// The hash covers the group verifying key, all the verifying shares, the identities of the group
+/// members, and the threshold. It does not depend on the order in which identities were provided
+/// when constructing the [`PublicKeyPackage`].
+#[must_use]
+pub fn public_key_package_hash(public_key_package: &PublicKeyPackage) -> PublicKeyPackageHash {
+    let frost_public_key_package = public_key_package.frost_public_key_package();
+    let mut hasher = blake3::Hasher::new_derive_key(PUBLIC_KEY_PACKAGE_HASH_CONTEXT);
+
+    hasher.update(&public_key_package.verifying_key().serialize());
+
+    let verifying_shares = frost_public_key_package.verifying_shares();
+    hasher.update(&(verifying_shares.len() as u64).to_le_bytes());
+    for (identifier, verifying_share) in verifying_shares {
+        hasher.update(&identifier.serialize());
+        hasher.update(&verifying_share.serialize());
+    }
+
+    let mut identities = public_key_package.identities().iter().collect::<Vec<_>>();
+    identities.sort_unstable();
+    identities.dedup();
+    hasher.update(&(identities.len() as u64).to_le_bytes());
+    for identity in identities {
+        hasher.update(&identity.serialize());
+    }
+
+    hasher.update(&public_key_package.min_signers().to_le_bytes());
+
+    hasher.finalize().into()
+}
+
+/// Returns the group verifying key of `public_key_package` in the encoding used by Ironfish for
+/// public addresses and view keys: the 32-byte compressed Jubjub point of a RedJubjub public key.
+///
+/// Wallets should use this instead of relying on the FROST serialization of [`PublicKeyPackage`].
+#[must_use]
+pub fn group_verifying_key_bytes(public_key_package: &PublicKeyPackage) -> [u8; 32] {
+    public_key_package.verifying_key().serialize()
+}
+
+/// Combines the public key packages of two disjoint segments of the same group, for example from
+/// sub-ceremonies of a hierarchical setup, into a single [`PublicKeyPackage`].
+///
+/// Both packages must have the same group verifying key and the same threshold, and must not
+/// have any member in common. The resulting package contains the identities and the verifying
+/// shares of both.
+pub fn merge_public_key_packages(
+    a: &PublicKeyPackage,
+    b: &PublicKeyPackage,
+) -> Result<PublicKeyPackage, Error> {
+    if a.verifying_key() != b.verifying_key() {
+        return Err(Error::InvalidInput(
+            "public key packages have different group verifying keys".to_string(),
+        ));
+    }
+    if a.min_signers() != b.min_signers() {
+        return Err(Error::InvalidInput(format!(
+            "public key packages have different thresholds: {} and {}",
+            a.min_signers(),
+            b.min_signers()
+        )));
+    }
+    if let Some(identity) = a.identities().iter().find(|id| b.identities().contains(id)) {
+        return Err(Error::InvalidInput(format!(
+            "identity {} is a member of both public key packages",
+            identity
+        )));
+    }
+
+    let a_shares = a.frost_public_key_package().verifying_shares();
+    let b_shares = b.frost_public_key_package().verifying_shares();
+    if a_shares
+        .keys()
+        .any(|identifier| b_shares.contains_key(identifier))
+    {
+        return Err(Error::InvalidInput(
+            "public key packages have verifying shares for the same identifier".to_string(),
+        ));
+    }
+
+    let mut verifying_shares = a_shares.clone();
+    verifying_shares.extend(
+        b_shares
+            .iter()
+            .map(|(identifier, share)| (*identifier, *share)),
+    );
+
+    Ok(PublicKeyPackage::from_frost(
+        FrostPublicKeyPackage::new(verifying_shares, *a.verifying_key()),
+        a.identities().iter().chain(b.identities()).cloned(),
+        a.min_signers(),
+    ))
+}
+
+/// Returns `true` if `share` is the signing share contained in `key_package`.
+///
+/// This can be used to detect a mix-up when shares and key packages are stored separately.
+#[must_use]
+pub fn share_matches_package(share: &SigningShare, key_package: &KeyPackage) -> bool {
+    // Compare the serialized scalars in constant time, so that the comparison does not leak
+    // information about the shares
+    share
+        .serialize()
+        .ct_eq(&key_package.signing_share().serialize())
+        .into()
+}
+
+/// Returns `true` if the members of the group described by `public_key_package` that are listed in
+/// `available` are enough to produce a signature.
+///
+/// Identities in `available` that are not members of the group are ignored, and so are duplicates.
+#[must_use]
+pub fn can_sign(public_key_package: &PublicKeyPackage, available: &[Identity]) -> bool {
+    let available_members = public_key_package
+        .identities()
+        .iter()
+        .filter(|member| available.contains(member))
+        .count();
+    available_members >= public_key_package.min_signers() as usize
+}
+
+/// Returns `true` if the group described by `public_key_package` can still be recovered with the
+/// members listed in `available`, for example to reshare the key to replace lost participants.
+///
+/// This requires the same quorum as signing, so this is equivalent to [`can_sign`].
+#[must_use]
+pub fn recoverable(public_key_package: &PublicKeyPackage, available: &[Identity]) -> bool {
+    can_sign(public_key_package, available)
+}
+
+/// Overwrites the signing share contained in `key_package` with zeros.
+///
+/// After this call, `key_package` can no longer be used to produce valid signature shares. This
+/// gives explicit control over the lifetime of the secret material, for example to remove it from
+/// memory right after signing.
+pub fn wipe(key_package: &mut KeyPackage) {
+    key_package.zeroize();
+}
+
+/// Encrypts `key_package` so that it can only be restored by the owner of `own_identity`, using
+/// [`restore_self_backup`].
+pub fn self_backup<R: RngCore + CryptoRng>(
+    key_package: &KeyPackage,
+    own_identity: &Identity,
+    csrng: R,
+) -> Vec<u8> {
+    let serialized = key_package.serialize().expect("serialization failed");
+    multienc::encrypt(&serialized, [own_identity], csrng)
+}
+
+/// Decrypts a backup produced by [`self_backup`].
+///
+/// Fails if the backup was not encrypted for `secret`, or if the decrypted [`KeyPackage`] does not
+/// belong to the identity of `secret`.
+pub fn restore_self_backup(secret: &Secret, backup: &[u8]) -> Result<KeyPackage, Error> {
+    let serialized =
+        multienc::decrypt(secret, backup).map_err(|err| Error::DecryptionError(err.into()))?;
+    let key_package = KeyPackage::deserialize(&serialized).map_err(Error::FrostError)?;
+    if *key_package.identifier() != secret.to_identity().to_frost_identifier() {
+        return Err(Error::InvalidInput(
+            "key package does not belong to this identity".to_string(),
+        ));
+    }
+    Ok(key_package)
+}
+
+/// Proof that the holder of a [`KeyPackage`] is a member of a group, bound to a specific audience.
+///
+/// The token is a Schnorr proof of knowledge of the signing share behind the verifying share of
+/// the member in the group's [`PublicKeyPackage`]. It does not reveal the signing share. Tokens
+/// are bound to the [`public_key_package_hash`] of the group and to the audience they were
+/// created for, so they cannot be presented to a different audience or for a different group.
+/// They are not bound to a time or a nonce chosen by the verifier: audiences that need to prevent
+/// replay over time should include a challenge of their own in `audience`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MembershipToken {
+    identifier: Identifier,
+    commitment: [u8; 32],
+    response: [u8; 32],
+}
+
+impl MembershipToken {
+    #[must_use]
+    pub fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+
+    pub fn serialize(&self) -> [u8; MEMBERSHIP_TOKEN_LEN] {
+        let mut bytes = [0u8; MEMBERSHIP_TOKEN_LEN];
+        self.serialize_into(&mut bytes[..])
+            .expect("serialization failed");
+        bytes
+    }
+
+    pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.identifier.serialize())?;
+        writer.write_all(&self.commitment)?;
+        writer.write_all(&self.response)?;
+        Ok(())
+    }
+
+    pub fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut identifier = [0u8; 32];
+        reader.read_exact(&mut identifier)?;
+        let identifier = Identifier::deserialize(&identifier).map_err(io::Error::other)?;
+
+        let mut commitment = [0u8; 32];
+        reader.read_exact(&mut commitment)?;
+
+        let mut response = [0u8; 32];
+        reader.read_exact(&mut response)?;
+
+        Ok(Self {
+            identifier,
+            commitment,
+            response,
+        })
+    }
+}
+
+fn membership_challenge(
+    group_hash: &PublicKeyPackageHash,
+    audience: &[u8],
+    identifier: &Identifier,
+    verifying_share: &[u8; 32],
+    commitment: &[u8; 32],
+) -> Scalar {
+    let mut hasher = blake3::Hasher::new_derive_key(MEMBERSHIP_TOKEN_CONTEXT);
+    hasher.update(group_hash);
+    hasher.update(&(audience.len() as u64).to_le_bytes());
+    hasher.update(audience);
+    hasher.update(&identifier.serialize());
+    hasher.update(verifying_share);
+    hasher.update(commitment);
+
+    let mut wide = [0u8; 64];
+    hasher.finalize_xof().fill(&mut wide);
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// Creates a [`MembershipToken`] proving that the owner of `key_package` is a member of the group
+/// described by `public_key_package`, addressed to `audience`.
+pub fn membership_token<R: RngCore + CryptoRng>(
+    key_package: &KeyPackage,
+    public_key_package: &PublicKeyPackage,
+    audience: &[u8],
+    mut csrng: R,
+) -> MembershipToken {
+    let secret = JubjubScalarField::deserialize(&key_package.signing_share().serialize())
+        .expect("signing share should be a valid scalar");
+
+    let nonce = JubjubScalarField::random(&mut csrng);
+    let commitment = JubjubGroup::serialize(&(JubjubGroup::generator() * nonce));
+
+    let challenge = membership_challenge(
+        &public_key_package_hash(public_key_package),
+        audience,
+        key_package.identifier(),
+        &key_package.verifying_share().serialize(),
+        &commitment,
+    );
+    let response = nonce + challenge * secret;
+
+    MembershipToken {
+        identifier: *key_package.identifier(),
+        commitment,
+        response: JubjubScalarField::serialize(&response),
+    }
+}
+
+/// Verifies a [`MembershipToken`] created by [`membership_token`] for the same
+/// `public_key_package` and `audience`.
+pub fn verify_membership_token(
+    token: &MembershipToken,
+    public_key_package: &PublicKeyPackage,
+    audience: &[u8],
+) -> Result<(), Error> {
+    let verifying_share = public_key_package
+        .frost_public_key_package()
+        .verifying_shares()
+        .get(&token.identifier)
+        .ok_or_else(|| {
+            Error::InvalidInput("token holder is not a member of the group".to_string())
+        })?
+        .serialize();
+
+    let invalid_token = || Error::InvalidInput("invalid membership token".to_string());
+    let commitment: Element =
+        JubjubGroup::deserialize(&token.commitment).map_err(|_| invalid_token())?;
+    let response: Scalar =
+        JubjubScalarField::deserialize(&token.response).map_err(|_| invalid_token())?;
+    let verifying_share_element: Element =
+        JubjubGroup::deserialize(&verifying_share).map_err(|_| invalid_token())?;
+
+    let challenge = membership_challenge(
+        &public_key_package_hash(public_key_package),
+        audience,
+        &token.identifier,
+        &verifying_share,
+        &token.commitment,
+    );
+
+    if JubjubGroup::generator() * response == commitment + verifying_share_element * challenge {
+        Ok(())
+    } else {
+        Err(invalid_token())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frost::frost::keys::IdentifierList;
+    use crate::frost::keys::split;
+    use crate::frost::SigningKey;
+    use crate::nonces::is_degenerate_signing_share;
+    use hex_literal::hex;
+    use rand::thread_rng;
+
+    fn frost_public_key_package(identities: &[Identity]) -> FrostPublicKeyPackage {
+        let mut rng = thread_rng();
+        let identifiers = identities
+            .iter()
+            .map(|identity| identity.to_frost_identifier())
+            .collect::<Vec<_>>();
+        let max_signers = identifiers.len() as u16;
+
+        let (_, frost_public_key_package) = split(
+            &SigningKey::new(&mut rng),
+            max_signers,
+            2,
+            IdentifierList::Custom(&identifiers),
+            &mut rng,
+        )
+        .expect("signing key split failed");
+
+        frost_public_key_package
+    }
+
+    #[test]
+    fn public_key_package_hash_stability() {
+        let identities = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let frost_public_key_package = frost_public_key_package(&identities);
+
+        let public_key_package1 =
+            PublicKeyPackage::from_frost(frost_public_key_package.clone(), identities.clone(), 2);
+        let public_key_package2 = PublicKeyPackage::from_frost(
+            frost_public_key_package,
+            [
+                identities[2].clone(),
+                identities[0].clone(),
+                identities[1].clone(),
+            ],
+            2,
+        );
+
+        assert_eq!(
+            public_key_package_hash(&public_key_package1),
+            public_key_package_hash(&public_key_package2)
+        );
+    }
+
+    #[test]
+    fn public_key_package_hash_variation() {
+        let identities = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let frost_public_key_package = frost_public_key_package(&identities);
+        let public_key_package =
+            PublicKeyPackage::from_frost(frost_public_key_package.clone(), identities.clone(), 2);
+
+        // Swap the verifying shares of the first two participants
+        let mut verifying_shares = frost_public_key_package.verifying_shares().clone();
+        let identifier1 = identities[0].to_frost_identifier();
+        let identifier2 = identities[1].to_frost_identifier();
+        let share1 = verifying_shares[&identifier1];
+        let share2 = verifying_shares[&identifier2];
+        verifying_shares.insert(identifier1, share2);
+        verifying_shares.insert(identifier2, share1);
+        let altered_public_key_package = PublicKeyPackage::from_frost(
+            FrostPublicKeyPackage::new(verifying_shares, *frost_public_key_package.verifying_key()),
+            identities.clone(),
+            2,
+        );
+
+        assert_ne!(
+            public_key_package_hash(&public_key_package),
+            public_key_package_hash(&altered_public_key_package)
+        );
+
+        let other_threshold_public_key_package =
+            PublicKeyPackage::from_frost(frost_public_key_package, identities, 3);
+
+        assert_ne!(
+            public_key_package_hash(&public_key_package),
+            public_key_package_hash(&other_threshold_public_key_package)
+        );
+    }
+
+    #[test]
+    fn group_verifying_key_encoding() {
+        let serialization = hex!(
+            "
+            a600000000c3d2051e02b62709a88950f3a75eb0d03a9510123a72947eb083b5822
+            e874793e8f40f6b0ba381109571a24f9f87421f0393f45cee913ef891bd75eb7ba7
+            a5611858a80305cf631451a7d94604cb32d11285ebd4b6ee797eceaa464b2dfcd09
+            7295cacf90c579265130e9e37225a23dfd51da2c4b8db499cb0e7aa6b03a15cde2d
+            b678e99c94974b2a766f83b134c5c782803f5f5a65bc4a6392f6a81062ad8292e84
+            4f3c00200000072cf6be086f2453ec7ce6f7b76fbb35c4dcf6fac1737dbcc2a2467
+            b3f0c8453574ad36e9dd2b092aa0870930ed6be8d9ba40c146c5b2110fbb03f7e3b
+            60e5d63347f47bd69c418630d0c4d3301f0a910c3d127c9d7064cedf26c0c2f0cea
+            9486a8993eea77744ead60ea210bc43a4c56be4933762dddaba145fb215c5dbaebc
+            a0272586e451ceb90d00fde8fa96f7eba99845066803aef4073ca39f3af9050b9f0
+            bc63deb3652c1455090070a8dd3376128e093726a055bab2e2d2325cb5c978b62eb
+            a97c6b42325cf4fc106321b7c8979fc123dc77a5da91ace3b3245405d680b9bcc13
+            5828ac28415305d74abe2ca084639dd1ab7bb8c69930cf0a55a1151022020200
+        "
+        );
+        let public_key_package =
+            PublicKeyPackage::deserialize_from(&serialization[..]).expect("deserialization failed");
+
+        assert_eq!(
+            group_verifying_key_bytes(&public_key_package),
+            hex!("94974b2a766f83b134c5c782803f5f5a65bc4a6392f6a81062ad8292e844f3c0")
+        );
+    }
+
+    #[test]
+    fn merge_disjoint_public_key_packages() {
+        let identities = (0..4)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+        let group = frost_public_key_package(&identities);
+        let verifying_key = *group.verifying_key();
+
+        let segment = |members: &[Identity]| {
+            let verifying_shares = group
+                .verifying_shares()
+                .iter()
+                .filter(|(identifier, _)| {
+                    members
+                        .iter()
+                        .any(|member| member.to_frost_identifier() == **identifier)
+                })
+                .map(|(identifier, share)| (*identifier, *share))
+                .collect();
+            PublicKeyPackage::from_frost(
+                FrostPublicKeyPackage::new(verifying_shares, verifying_key),
+                members.to_vec(),
+                2,
+            )
+        };
+
+        let merged =
+            merge_public_key_packages(&segment(&identities[..2]), &segment(&identities[2..]))
+                .expect("merging public key packages failed");
+        let expected = PublicKeyPackage::from_frost(group.clone(), identities.clone(), 2);
+        assert_eq!(
+            public_key_package_hash(&merged),
+            public_key_package_hash(&expected)
+        );
+
+        match merge_public_key_packages(&segment(&identities[..3]), &segment(&identities[2..])) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("overlapping public key packages should have been rejected"),
+        }
+
+        let other_group = PublicKeyPackage::from_frost(
+            frost_public_key_package(&identities[2..]),
+            identities[2..].to_vec(),
+            2,
+        );
+        match merge_public_key_packages(&segment(&identities[..2]), &other_group) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("public key packages of different groups should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn wipe_key_package() {
+        let identities = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let identifier = identities[0].to_frost_identifier();
+        let (secret_shares, _) = split(
+            &SigningKey::new(thread_rng()),
+            2,
+            2,
+            IdentifierList::Custom(&[identifier, identities[1].to_frost_identifier()]),
+            thread_rng(),
+        )
+        .expect("signing key split failed");
+        let mut key_package = KeyPackage::try_from(secret_shares[&identifier].clone())
+            .expect("key package creation failed");
+        let verifying_share = *key_package.verifying_share();
+
+        assert!(!is_degenerate_signing_share(key_package.signing_share()));
+
+        wipe(&mut key_package);
+
+        assert_eq!(key_package.signing_share().serialize(), [0u8; 32]);
+        assert_eq!(key_package.verifying_share(), &verifying_share);
+    }
+
+    #[test]
+    fn self_backup_round_trip() {
+        let secrets = [Secret::random(thread_rng()), Secret::random(thread_rng())];
+        let identifiers = secrets
+            .iter()
+            .map(|secret| secret.to_identity().to_frost_identifier())
+            .collect::<Vec<_>>();
+        let (secret_shares, _) = split(
+            &SigningKey::new(thread_rng()),
+            2,
+            2,
+            IdentifierList::Custom(&identifiers),
+            thread_rng(),
+        )
+        .expect("signing key split failed");
+        let key_package = KeyPackage::try_from(secret_shares[&identifiers[0]].clone())
+            .expect("key package creation failed");
+
+        let backup = self_backup(&key_package, &secrets[0].to_identity(), thread_rng());
+
+        let restored = restore_self_backup(&secrets[0], &backup).expect("restore failed");
+        assert_eq!(restored, key_package);
+
+        match restore_self_backup(&secrets[1], &backup) {
+            Err(Error::DecryptionError(_)) => (),
+            _ => panic!("restore with a different secret should have failed"),
+        }
+    }
+
+    #[test]
+    fn share_matches_key_package() {
+        let identities = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let identifiers = identities
+            .iter()
+            .map(|identity| identity.to_frost_identifier())
+            .collect::<Vec<_>>();
+        let (secret_shares, _) = split(
+            &SigningKey::new(thread_rng()),
+            2,
+            2,
+            IdentifierList::Custom(&identifiers),
+            thread_rng(),
+        )
+        .expect("signing key split failed");
+        let key_packages = identifiers
+            .iter()
+            .map(|identifier| {
+                KeyPackage::try_from(secret_shares[identifier].clone())
+                    .expect("key package creation failed")
+            })
+            .collect::<Vec<_>>();
+
+        assert!(share_matches_package(
+            key_packages[0].signing_share(),
+            &key_packages[0]
+        ));
+        assert!(!share_matches_package(
+            key_packages[1].signing_share(),
+            &key_packages[0]
+        ));
+    }
+
+    #[test]
+    fn membership_tokens() {
+        let identities = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let identifiers = identities
+            .iter()
+            .map(|identity| identity.to_frost_identifier())
+            .collect::<Vec<_>>();
+
+        let group = |identities: &[Identity]| {
+            let (secret_shares, frost_public_key_package) = split(
+                &SigningKey::new(thread_rng()),
+                2,
+                2,
+                IdentifierList::Custom(&identifiers),
+                thread_rng(),
+            )
+            .expect("signing key split failed");
+            let key_package = KeyPackage::try_from(secret_shares[&identifiers[0]].clone())
+                .expect("key package creation failed");
+            let public_key_package =
+                PublicKeyPackage::from_frost(frost_public_key_package, identities.to_vec(), 2);
+            (key_package, public_key_package)
+        };
+        let (key_package, public_key_package) = group(&identities);
+        let (other_key_package, _) = group(&identities);
+
+        let token = membership_token(
+            &key_package,
+            &public_key_package,
+            b"some audience",
+            thread_rng(),
+        );
+        verify_membership_token(&token, &public_key_package, b"some audience")
+            .expect("token verification failed");
+
+        let deserialized = MembershipToken::deserialize_from(&token.serialize()[..])
+            .expect("token deserialization failed");
+        assert_eq!(deserialized, token);
+
+        verify_membership_token(&token, &public_key_package, b"other audience")
+            .expect_err("token replayed to a different audience should have been rejected");
+
+        let non_member_token = membership_token(
+            &other_key_package,
+            &public_key_package,
+            b"some audience",
+            thread_rng(),
+        );
+        verify_membership_token(&non_member_token, &public_key_package, b"some audience")
+            .expect_err("token from a non-member should have been rejected");
+    }
+
+    #[test]
+    fn available_signers() {
+        let identities = (0..3)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+        let public_key_package = PublicKeyPackage::from_frost(
+            frost_public_key_package(&identities),
+            identities.clone(),
+            2,
+        );
+        let outsider = Secret::random(thread_rng()).to_identity();
+
+        assert!(can_sign(&public_key_package, &identities));
+        assert!(can_sign(&public_key_package, &identities[1..]));
+        assert!(recoverable(&public_key_package, &identities[1..]));
+
+        let below_threshold = [identities[0].clone(), identities[0].clone(), outsider];
+        assert!(!can_sign(&public_key_package, &below_threshold));
+        assert!(!recoverable(&public_key_package, &below_threshold));
+        assert!(!can_sign(&public_key_package, &[]));
+    }
+}
@@ -9,24 +9,62 @@
 #![warn(unused_crate_dependencies)]
 #![warn(unused_qualifications)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// Only used by benchmarks; silences `unused_crate_dependencies` when building tests.
+#[cfg(test)]
+use criterion as _;
+
+// Only used by tests of the `serde` feature.
+#[cfg(all(test, not(feature = "serde")))]
+use bincode as _;
+#[cfg(all(test, not(feature = "serde")))]
+use serde_json as _;
+
+// Only used to enable the JavaScript random number generator on `wasm32`.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use getrandom as _;
+
+// Only used by the DKG; silences `unused_crate_dependencies` when building without it.
+#[cfg(all(feature = "parallel", not(feature = "dkg")))]
+use rayon as _;
+
 mod serde;
 
 #[cfg(feature = "signing")]
 mod checksum;
+#[cfg(feature = "signing")]
+mod identifiers;
 
 pub mod multienc;
 pub mod participant;
 
 #[cfg(feature = "dkg")]
 pub mod dkg;
+#[cfg(feature = "dkg")]
+pub mod keys;
 
+#[cfg(feature = "signing")]
+pub mod error;
 #[cfg(feature = "signing")]
 pub mod nonces;
+#[cfg(all(feature = "signing", feature = "std"))]
+pub mod session;
 #[cfg(feature = "signing")]
 pub mod signature_share;
 #[cfg(feature = "signing")]
 pub mod signing_commitment;
 
+#[cfg(feature = "audit")]
+pub mod verification;
+
+#[cfg(feature = "test-helpers")]
+pub mod test_vectors;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 pub use reddsa::frost::redjubjub as frost;
 
 #[cfg(feature = "std")]
@@ -52,6 +90,12 @@ mod io {
         }
     }
 
+    impl core::fmt::Display for Error {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("I/O error")
+        }
+    }
+
     pub type Result<T> = core::result::Result<T, Error>;
 
     pub trait Read {
@@ -126,6 +170,14 @@ mod io {
         }
     }
 
+    #[cfg(feature = "alloc")]
+    impl Write for alloc::vec::Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
     impl Write for &mut [u8] {
         fn write(&mut self, buf: &[u8]) -> Result<usize> {
             let n = cmp::min(self.len(), buf.len());
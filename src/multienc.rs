@@ -15,14 +15,72 @@ use chacha20poly1305::ChaCha20Poly1305;
 use chacha20poly1305::KeyInit;
 use chacha20poly1305::Nonce;
 use chacha20poly1305::Tag;
+use core::fmt;
+use poly1305::universal_hash::UniversalHash;
+use poly1305::Poly1305;
+#[cfg(all(feature = "signing", feature = "std"))]
+use rand_chacha::ChaCha20Rng;
 use rand_core::CryptoRng;
 use rand_core::RngCore;
+#[cfg(all(feature = "signing", feature = "std"))]
+use rand_core::SeedableRng;
 use x25519_dalek::PublicKey;
 use x25519_dalek::ReusableSecret;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
 
 pub const HEADER_SIZE: usize = 56;
 pub const KEY_SIZE: usize = 32;
 
+/// Reasons why data produced by [`encrypt`] could not be decrypted.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MultiencError {
+    /// The data ended before the metadata, or the ciphertext that the metadata describes.
+    Truncated,
+    /// None of the encrypted keys allowed decrypting the data with the given secret. This is the
+    /// case when the secret is not one of the recipients, but also when the data was altered: the
+    /// two cannot be told apart, because the encrypted keys do not reveal who they are for.
+    NotARecipient,
+    /// The encryption key of the recipient was found, but a part of the data did not
+    /// authenticate. Only reported by [`decrypt_stream`], where the first chunk identifies the
+    /// encryption key and the following chunks are authenticated independently.
+    AuthenticationFailed,
+    /// The metadata describes sizes that are inconsistent with each other or with the data.
+    MalformedHeader,
+}
+
+impl fmt::Display for MultiencError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => fmt::Display::fmt("encrypted data is truncated", f),
+            Self::NotARecipient => fmt::Display::fmt("ciphertext could not be decrypted", f),
+            Self::AuthenticationFailed => fmt::Display::fmt("ciphertext failed authentication", f),
+            Self::MalformedHeader => fmt::Display::fmt("malformed encryption metadata", f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MultiencError {}
+
+impl From<MultiencError> for io::Error {
+    #[cfg(feature = "std")]
+    fn from(err: MultiencError) -> Self {
+        let kind = match err {
+            MultiencError::Truncated => io::ErrorKind::UnexpectedEof,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, err)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn from(err: MultiencError) -> Self {
+        io::Error::other(err)
+    }
+}
+
 #[inline]
 #[must_use]
 pub const fn metadata_size(num_recipients: usize) -> usize {
@@ -141,14 +199,14 @@ where
 /// This method expects the ciphertext and the metadata to be concatenated in one slice. Use
 /// [`decrypt_in_place`] if you have two separate slices.
 #[cfg(feature = "std")]
-pub fn decrypt(secret: &Secret, data: &[u8]) -> io::Result<Vec<u8>> {
-    let header = Header::deserialize_from(data)?;
+pub fn decrypt(secret: &Secret, data: &[u8]) -> Result<Vec<u8>, MultiencError> {
+    let header = Header::deserialize_from(data).map_err(|_| MultiencError::Truncated)?;
     let metadata_len = metadata_size(header.num_recipients);
     let total_len = metadata_len
         .checked_add(header.data_len)
-        .ok_or_else(|| io::Error::other("overflow when calculating data size"))?;
+        .ok_or(MultiencError::MalformedHeader)?;
     if data.len() < total_len {
-        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        return Err(MultiencError::Truncated);
     }
 
     let (metadata, ciphertext) = data.split_at(metadata_len);
@@ -163,29 +221,44 @@ pub fn decrypt(secret: &Secret, data: &[u8]) -> io::Result<Vec<u8>> {
 /// This method expects the ciphertext and the metadata to be passed as two distinct slices. Use
 /// [`decrypt`] if you have a contiguous slice, like a vector produced by [`encrypt`].
 pub fn decrypt_in_place<R>(
+    secret: &Secret,
+    ciphertext: &mut [u8],
+    metadata: R,
+) -> Result<(), MultiencError>
+where
+    R: io::Read,
+{
+    decrypt_in_place_with_key(secret, ciphertext, metadata).map(|_| ())
+}
+
+/// Same as [`decrypt_in_place`], but also returns the key that the data was encrypted with.
+///
+/// Any failure to read from `metadata` is reported as [`MultiencError::Truncated`].
+fn decrypt_in_place_with_key<R>(
     secret: &Secret,
     ciphertext: &mut [u8],
     mut metadata: R,
-) -> io::Result<()>
+) -> Result<[u8; KEY_SIZE], MultiencError>
 where
     R: io::Read,
 {
+    let truncated = |_| MultiencError::Truncated;
+
     // Read all metadata
     let mut agreement_key = [0u8; 32];
-    metadata.read_exact(&mut agreement_key)?;
+    metadata.read_exact(&mut agreement_key).map_err(truncated)?;
     let agreement_key = PublicKey::from(agreement_key);
 
     let mut tag = [0u8; 16];
-    metadata.read_exact(&mut tag)?;
+    metadata.read_exact(&mut tag).map_err(truncated)?;
     let tag = tag.into();
 
-    let encrypted_keys_len = read_usize(&mut metadata)?;
-    let ciphertext_len = read_usize(&mut metadata)?;
+    let encrypted_keys_len = read_usize(&mut metadata).map_err(truncated)?;
+    let ciphertext_len = read_usize(&mut metadata).map_err(truncated)?;
 
+    // The ciphertext size recorded in the metadata must match the actual ciphertext
     if ciphertext.len() != ciphertext_len {
-        return Err(io::Error::other(
-            "ciphertext size does not match size recorded in the metadata",
-        ));
+        return Err(MultiencError::MalformedHeader);
     }
 
     // Reconstruct the shared secret
@@ -199,7 +272,9 @@ where
     // until the data is recovered.
     for _ in 0..encrypted_keys_len {
         let mut encryption_key = [0u8; KEY_SIZE];
-        metadata.read_exact(&mut encryption_key)?;
+        metadata
+            .read_exact(&mut encryption_key)
+            .map_err(truncated)?;
 
         // Decrypt the encryption key with X25519 + ChaCha20. This will always succeed, even if
         // the encryption key was not for this participant (in which case, it will result in
@@ -211,7 +286,7 @@ where
         // for this participant (or if the encryption key was tampered).
         let cipher = ChaCha20Poly1305::new((&encryption_key).into());
         match cipher.decrypt_in_place_detached(&nonce, &[], ciphertext, &tag) {
-            Ok(()) => return Ok(()),
+            Ok(()) => return Ok(encryption_key),
             Err(_) => {
                 // `decrypt_in_place_detached` garbled the `ciphertext` and replaced it with random
                 // data. We need to restore it to its original state. Because chacha20 is a stream
@@ -222,7 +297,529 @@ where
         }
     }
 
-    Err(io::Error::other("ciphertext could not be decrypted"))
+    Err(MultiencError::NotARecipient)
+}
+
+/// Returns the number of recipients that data produced by [`encrypt`] was encrypted for, without
+/// decrypting it.
+///
+/// Only the metadata is parsed. Returns [`MultiencError::Truncated`] if `data` is too short to
+/// contain the metadata and the ciphertext it describes, and [`MultiencError::MalformedHeader`] if
+/// the sizes in the metadata overflow.
+#[cfg(feature = "std")]
+pub fn recipient_count(data: &[u8]) -> Result<usize, MultiencError> {
+    let header = Header::deserialize_from(data).map_err(|_| MultiencError::Truncated)?;
+    header.total_size(data.len())?;
+    Ok(header.num_recipients)
+}
+
+/// Returns `true` if `secret` is one of the recipients of data produced by [`encrypt`].
+///
+/// The encrypted keys do not reveal who they are for, so this needs the secret of the recipient,
+/// not just its identity. The check is cheaper than [`decrypt`] though: the data is authenticated
+/// with each encrypted key, but it is neither copied nor decrypted.
+///
+/// Returns `false` if the data was altered, for the same reasons described in
+/// [`MultiencError::NotARecipient`].
+#[cfg(feature = "std")]
+pub fn is_recipient(secret: &Secret, data: &[u8]) -> Result<bool, MultiencError> {
+    match find_content_key(secret, data) {
+        Ok(_) => Ok(true),
+        Err(MultiencError::NotARecipient) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Finds the key that the ciphertext in `data` was encrypted with, using one participant secret.
+///
+/// Each encrypted key is tried by checking the authentication tag of the ciphertext, like
+/// ChaCha20Poly1305 does before decrypting, so the ciphertext is never copied nor decrypted.
+#[cfg(feature = "std")]
+fn find_content_key(secret: &Secret, data: &[u8]) -> Result<[u8; KEY_SIZE], MultiencError> {
+    let header = Header::deserialize_from(data).map_err(|_| MultiencError::Truncated)?;
+    let total_len = header.total_size(data.len())?;
+    if data.len() != total_len {
+        return Err(MultiencError::MalformedHeader);
+    }
+
+    let (metadata, ciphertext) = data.split_at(total_len - header.data_len);
+    let nonce = Nonce::default();
+    let shared_secret = secret
+        .decryption_key()
+        .diffie_hellman(&header.agreement_key)
+        .to_bytes();
+
+    for encrypted_key in metadata[HEADER_SIZE..].chunks_exact(KEY_SIZE) {
+        let mut key = [0u8; KEY_SIZE];
+        key.copy_from_slice(encrypted_key);
+        let mut cipher = ChaCha20::new((&shared_secret).into(), &nonce);
+        cipher.apply_keystream(&mut key);
+
+        // Same as the authentication step of ChaCha20Poly1305, with no associated data: the
+        // Poly1305 key is the first block of the ChaCha20 keystream
+        let mut mac_key = poly1305::Key::default();
+        ChaCha20::new((&key).into(), &nonce).apply_keystream(&mut *mac_key);
+        let mut mac = Poly1305::new(&mac_key);
+        mac.update_padded(ciphertext);
+        let mut lengths = poly1305::Block::default();
+        lengths[8..].copy_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+        mac.update(&[lengths]);
+
+        if mac.verify(&header.tag).is_ok() {
+            return Ok(key);
+        }
+    }
+
+    Err(MultiencError::NotARecipient)
+}
+
+/// Key that the data of a ciphertext produced by [`encrypt`] is encrypted with.
+///
+/// Holding this key gives access to the data, and allows granting access to new recipients with
+/// [`add_recipient`]. It can be obtained by any of the recipients through [`content_key`].
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct ContentKey([u8; KEY_SIZE]);
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for ContentKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for ContentKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// With the `zeroize` feature, a [`ContentKey`] wipes itself when dropped, including the copies
+/// produced by [`Clone`].
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for ContentKey {}
+
+/// Recovers the [`ContentKey`] of data produced by [`encrypt`] using one participant secret.
+///
+/// This fails under the same conditions as [`decrypt`], so the returned key is guaranteed to
+/// decrypt `data`. Unlike [`decrypt`], `data` is only authenticated: it is neither copied nor
+/// decrypted, so this is cheap even for large payloads.
+#[cfg(feature = "std")]
+pub fn content_key(secret: &Secret, data: &[u8]) -> Result<ContentKey, MultiencError> {
+    find_content_key(secret, data).map(ContentKey)
+}
+
+/// Grants `new_recipient` access to data produced by [`encrypt`] or [`encrypt_in_place`], without
+/// re-encrypting the data.
+///
+/// `metadata` is the metadata of the encrypted data, and `content_key` is the key recovered by one
+/// of the existing recipients with [`content_key`]. Because the encrypted keys of all the
+/// recipients are derived from a single ephemeral agreement key, which is not retained, the keys
+/// are encrypted again under a new agreement key for all the `recipients` plus `new_recipient`.
+/// `recipients` should therefore list the existing recipients that should retain access.
+///
+/// Returns the new metadata. The ciphertext is unchanged and can be used as-is with the new
+/// metadata. Returns [`MultiencError::Truncated`] if `metadata` is too short to contain a header.
+#[cfg(feature = "std")]
+pub fn add_recipient<'a, I, R>(
+    metadata: &[u8],
+    content_key: &ContentKey,
+    recipients: I,
+    new_recipient: &'a Identity,
+    csrng: R,
+) -> Result<Vec<u8>, MultiencError>
+where
+    I: IntoIterator<Item = &'a Identity>,
+    R: RngCore + CryptoRng,
+{
+    let header = Header::deserialize_from(metadata).map_err(|_| MultiencError::Truncated)?;
+    let recipients = recipients
+        .into_iter()
+        .chain([new_recipient])
+        .collect::<Vec<_>>();
+
+    let agreement_secret = ReusableSecret::random_from_rng(csrng);
+    let new_header = Header {
+        agreement_key: PublicKey::from(&agreement_secret),
+        num_recipients: recipients.len(),
+        ..header
+    };
+
+    let mut new_metadata = Vec::with_capacity(metadata_size(recipients.len()));
+    new_header
+        .serialize_into(&mut new_metadata)
+        .expect("serialization failed");
+    for id in recipients {
+        let recipient_key = id.encryption_key();
+        let shared_secret = agreement_secret.diffie_hellman(recipient_key).to_bytes();
+        let mut cipher = ChaCha20::new((&shared_secret).into(), &Nonce::default());
+        let mut encrypted_key = content_key.0;
+
+        cipher.apply_keystream(&mut encrypted_key);
+        new_metadata.extend_from_slice(&encrypted_key);
+    }
+
+    Ok(new_metadata)
+}
+
+pub const SHARD_MANIFEST_SIZE: usize = 40;
+
+/// Metadata that ties together the shards produced by [`encrypt_sharded`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ShardManifest {
+    /// Random identifier shared by all the shards of the same encryption.
+    pub id: [u8; 32],
+    /// Position of this shard, starting from 0.
+    pub index: u32,
+    /// Total number of shards.
+    pub count: u32,
+}
+
+#[cfg(feature = "std")]
+impl ShardManifest {
+    fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.id)?;
+        writer.write_all(&self.index.to_le_bytes())?;
+        writer.write_all(&self.count.to_le_bytes())
+    }
+
+    fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut id = [0u8; 32];
+        reader.read_exact(&mut id)?;
+        let mut index = [0u8; 4];
+        reader.read_exact(&mut index)?;
+        let mut count = [0u8; 4];
+        reader.read_exact(&mut count)?;
+        Ok(Self {
+            id,
+            index: u32::from_le_bytes(index),
+            count: u32::from_le_bytes(count),
+        })
+    }
+}
+
+/// Encrypts `data` for multiple participants, splitting the recipients across multiple shards of
+/// at most `shard_size` recipients each.
+///
+/// Each shard is a self-contained ciphertext that can be decrypted by the recipients it addresses
+/// using [`decrypt_shard`]. This keeps the size of each shard bounded, at the cost of repeating
+/// the encrypted data in every shard. Each shard embeds an authenticated [`ShardManifest`] that
+/// ties it to the other shards of the same encryption.
+///
+/// # Panics
+///
+/// Panics if `shard_size` is 0.
+#[must_use]
+#[cfg(feature = "std")]
+pub fn encrypt_sharded<'a, I, R>(
+    data: &[u8],
+    recipients: I,
+    shard_size: usize,
+    mut csrng: R,
+) -> Vec<Vec<u8>>
+where
+    I: IntoIterator<Item = &'a Identity>,
+    R: RngCore + CryptoRng,
+{
+    assert!(shard_size > 0, "shard_size must be greater than 0");
+
+    let recipients = recipients.into_iter().collect::<Vec<_>>();
+    let chunks = recipients.chunks(shard_size);
+    let count = u32::try_from(chunks.len()).expect("too many shards");
+
+    let mut id = [0u8; 32];
+    csrng.fill_bytes(&mut id);
+
+    chunks
+        .zip(0..)
+        .map(|(chunk, index)| {
+            let manifest = ShardManifest { id, index, count };
+            let mut plaintext = Vec::with_capacity(SHARD_MANIFEST_SIZE + data.len());
+            manifest
+                .serialize_into(&mut plaintext)
+                .expect("serialization failed");
+            plaintext.extend_from_slice(data);
+            encrypt(&plaintext, chunk.iter().copied(), &mut csrng)
+        })
+        .collect()
+}
+
+/// Decrypts a shard produced by [`encrypt_sharded`] using one participant secret, returning the
+/// manifest of the shard along with the decrypted data.
+///
+/// Fails under the same conditions as [`decrypt`], and with [`MultiencError::Truncated`] if the
+/// decrypted data is too short to contain a [`ShardManifest`].
+#[cfg(feature = "std")]
+pub fn decrypt_shard(
+    secret: &Secret,
+    shard: &[u8],
+) -> Result<(ShardManifest, Vec<u8>), MultiencError> {
+    let plaintext = decrypt(secret, shard)?;
+    if plaintext.len() < SHARD_MANIFEST_SIZE {
+        return Err(MultiencError::Truncated);
+    }
+    let (manifest, data) = plaintext.split_at(SHARD_MANIFEST_SIZE);
+    let manifest =
+        ShardManifest::deserialize_from(manifest).map_err(|_| MultiencError::Truncated)?;
+    Ok((manifest, data.to_vec()))
+}
+
+/// Size of the plaintext chunks processed by [`encrypt_stream`] and [`decrypt_stream`].
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+const STREAM_TAG_SIZE: usize = 16;
+const STREAM_CHUNK_MORE: u8 = 0;
+const STREAM_CHUNK_LAST: u8 = 1;
+
+/// Nonce of the chunk at position `index` in a stream. The position and the last-chunk flag are
+/// both part of the nonce, so that chunks cannot be reordered, and the stream cannot be truncated
+/// without detection.
+#[cfg(feature = "std")]
+fn stream_nonce(index: u64, flag: u8) -> Nonce {
+    let mut nonce = Nonce::default();
+    nonce[..8].copy_from_slice(&index.to_le_bytes());
+    nonce[11] = flag;
+    nonce
+}
+
+/// Reads from `reader` until `buf` is full or the end of the input is reached. Returns the number
+/// of bytes read.
+#[cfg(feature = "std")]
+fn read_chunk<R: io::Read>(mut reader: R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypts everything read from `reader` once for multiple participants, writing the result to
+/// `writer`.
+///
+/// Unlike [`encrypt`], the data is processed in chunks of [`STREAM_CHUNK_SIZE`] bytes, each with
+/// its own authentication tag, so memory usage does not depend on the size of the data. The
+/// encrypted keys for all the recipients are written once, before the first chunk.
+///
+/// The output can be decrypted by each participant using [`decrypt_stream`]. It is not compatible
+/// with [`decrypt`].
+#[cfg(feature = "std")]
+pub fn encrypt_stream<'a, I, Rd, W, R>(
+    recipients: I,
+    mut reader: Rd,
+    mut writer: W,
+    mut csrng: R,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = &'a Identity>,
+    I::IntoIter: ExactSizeIterator,
+    Rd: io::Read,
+    W: io::Write,
+    R: RngCore + CryptoRng,
+{
+    let recipients = recipients.into_iter();
+
+    let encryption_key = ChaCha20Poly1305::generate_key(&mut csrng);
+    let agreement_secret = ReusableSecret::random_from_rng(csrng);
+    let agreement_key = PublicKey::from(&agreement_secret);
+
+    writer.write_all(agreement_key.as_bytes())?;
+    write_usize(&mut writer, recipients.len())?;
+    for id in recipients {
+        let recipient_key = id.encryption_key();
+        let shared_secret = agreement_secret.diffie_hellman(recipient_key).to_bytes();
+        let mut cipher = ChaCha20::new((&shared_secret).into(), &Nonce::default());
+        let mut encrypted_key = encryption_key;
+
+        cipher.apply_keystream(&mut encrypted_key);
+        writer.write_all(&encrypted_key)?;
+    }
+
+    // A chunk shorter than `STREAM_CHUNK_SIZE` marks the end of the input. If the size of the
+    // input is a multiple of `STREAM_CHUNK_SIZE`, the last chunk is empty.
+    let cipher = ChaCha20Poly1305::new(&encryption_key);
+    let mut chunk = vec![0u8; STREAM_CHUNK_SIZE];
+    for index in 0u64.. {
+        let len = read_chunk(&mut reader, &mut chunk)?;
+        let flag = if len < STREAM_CHUNK_SIZE {
+            STREAM_CHUNK_LAST
+        } else {
+            STREAM_CHUNK_MORE
+        };
+
+        let data = &mut chunk[..len];
+        let tag = cipher
+            .encrypt_in_place_detached(&stream_nonce(index, flag), &[flag], data)
+            .expect("encryption failed");
+
+        writer.write_all(&[flag])?;
+        write_usize(&mut writer, len)?;
+        writer.write_all(data)?;
+        writer.write_all(&tag)?;
+
+        if flag == STREAM_CHUNK_LAST {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the next chunk of a stream produced by [`encrypt_stream`] into `chunk`. Returns the
+/// chunk flag and the authentication tag.
+#[cfg(feature = "std")]
+fn read_stream_chunk<R: io::Read>(mut reader: R, chunk: &mut Vec<u8>) -> io::Result<(u8, Tag)> {
+    let mut flag = [0u8; 1];
+    reader.read_exact(&mut flag)?;
+    let len = read_usize(&mut reader)?;
+    if len > STREAM_CHUNK_SIZE {
+        return Err(io::Error::other(
+            "chunk size exceeds the maximum chunk size",
+        ));
+    }
+
+    chunk.resize(len, 0);
+    reader.read_exact(chunk)?;
+    let mut tag = [0u8; STREAM_TAG_SIZE];
+    reader.read_exact(&mut tag)?;
+
+    Ok((flag[0], tag.into()))
+}
+
+/// Decrypts a stream produced by [`encrypt_stream`] using one participant secret, writing the
+/// decrypted data to `writer`.
+///
+/// Each chunk is authenticated before being written, but the data is written as it is decrypted:
+/// if an error is returned, the data written so far must be discarded.
+///
+/// Because the data is read from `reader` and written to `writer`, errors are reported as
+/// [`std::io::Error`]. When the data cannot be decrypted, the inner error is a [`MultiencError`],
+/// which can be retrieved with [`std::io::Error::get_ref`].
+#[cfg(feature = "std")]
+pub fn decrypt_stream<Rd, W>(secret: &Secret, mut reader: Rd, mut writer: W) -> io::Result<()>
+where
+    Rd: io::Read,
+    W: io::Write,
+{
+    let mut agreement_key = [0u8; 32];
+    reader.read_exact(&mut agreement_key)?;
+    let agreement_key = PublicKey::from(agreement_key);
+    let num_recipients = read_usize(&mut reader)?;
+
+    let shared_secret = secret
+        .decryption_key()
+        .diffie_hellman(&agreement_key)
+        .to_bytes();
+    let mut encryption_keys = Vec::new();
+    for _ in 0..num_recipients {
+        let mut encryption_key = [0u8; KEY_SIZE];
+        reader.read_exact(&mut encryption_key)?;
+        let mut cipher = ChaCha20::new((&shared_secret).into(), &Nonce::default());
+        cipher.apply_keystream(&mut encryption_key);
+        encryption_keys.push(encryption_key);
+    }
+
+    // Find the encryption key for this participant by attempting to decrypt the first chunk with
+    // each of the keys
+    let mut ciphertext = Vec::new();
+    let mut chunk = Vec::new();
+    let (mut flag, mut tag) = read_stream_chunk(&mut reader, &mut ciphertext)?;
+    let cipher = encryption_keys
+        .iter()
+        .map(|encryption_key| ChaCha20Poly1305::new(encryption_key.into()))
+        .find(|cipher| {
+            chunk.clone_from(&ciphertext);
+            cipher
+                .decrypt_in_place_detached(&stream_nonce(0, flag), &[flag], &mut chunk, &tag)
+                .is_ok()
+        })
+        .ok_or(MultiencError::NotARecipient)?;
+
+    for index in 1u64.. {
+        writer.write_all(&chunk)?;
+        if flag == STREAM_CHUNK_LAST {
+            break;
+        }
+
+        (flag, tag) = read_stream_chunk(&mut reader, &mut chunk)?;
+        cipher
+            .decrypt_in_place_detached(&stream_nonce(index, flag), &[flag], &mut chunk, &tag)
+            .map_err(|_| MultiencError::AuthenticationFailed)?;
+    }
+
+    Ok(())
+}
+
+/// A known-answer test vector for [`encrypt`], produced by [`test_vectors`].
+#[cfg(all(feature = "signing", feature = "std"))]
+#[allow(missing_debug_implementations)]
+pub struct TestVector {
+    /// Secrets of the recipients, in the order in which they were passed to [`encrypt`].
+    pub secrets: Vec<Secret>,
+    pub plaintext: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+#[cfg(all(feature = "signing", feature = "std"))]
+impl TestVector {
+    #[must_use]
+    pub fn recipients(&self) -> Vec<Identity> {
+        self.secrets.iter().map(Secret::to_identity).collect()
+    }
+}
+
+/// Returns a set of test vectors generated from fixed seeds.
+///
+/// The output is deterministic: the same vectors are returned every time, as long as the
+/// encryption format does not change. Downstream crates can compare the ciphertexts against values
+/// recorded earlier to detect format changes, and check them with [`verify_vector`].
+#[must_use]
+#[cfg(all(feature = "signing", feature = "std"))]
+pub fn test_vectors() -> Vec<TestVector> {
+    let cases: [(u8, Vec<u8>); 3] = [
+        (1, Vec::new()),
+        (2, b"hello".to_vec()),
+        (3, (0..64).collect()),
+    ];
+
+    cases
+        .into_iter()
+        .zip(0u8..)
+        .map(|((num_recipients, plaintext), index)| {
+            let secrets = (0..num_recipients)
+                .map(|i| Secret::random(ChaCha20Rng::from_seed([i; 32])))
+                .collect::<Vec<_>>();
+            let recipients = secrets.iter().map(Secret::to_identity).collect::<Vec<_>>();
+            let ciphertext = encrypt(
+                &plaintext,
+                &recipients,
+                ChaCha20Rng::from_seed([0x80 | index; 32]),
+            );
+            TestVector {
+                secrets,
+                plaintext,
+                ciphertext,
+            }
+        })
+        .collect()
+}
+
+/// Checks that the ciphertext of `vector` can be decrypted to its plaintext by every recipient.
+#[cfg(all(feature = "signing", feature = "std"))]
+pub fn verify_vector(vector: &TestVector) -> io::Result<()> {
+    for secret in &vector.secrets {
+        if decrypt(secret, &vector.ciphertext)? != vector.plaintext {
+            return Err(io::Error::other(
+                "decrypted data does not match the expected plaintext",
+            ));
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -261,6 +858,22 @@ impl Header {
             data_len,
         })
     }
+
+    /// Returns the size of the metadata and the ciphertext described by this header, or an error
+    /// if it does not fit in `available` bytes.
+    #[cfg(feature = "std")]
+    fn total_size(&self, available: usize) -> Result<usize, MultiencError> {
+        let total_len = self
+            .num_recipients
+            .checked_mul(KEY_SIZE)
+            .and_then(|keys_len| keys_len.checked_add(HEADER_SIZE))
+            .and_then(|metadata_len| metadata_len.checked_add(self.data_len))
+            .ok_or(MultiencError::MalformedHeader)?;
+        if available < total_len {
+            return Err(MultiencError::Truncated);
+        }
+        Ok(total_len)
+    }
 }
 
 #[cfg(test)]
@@ -331,6 +944,395 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
+    mod recipients {
+        use crate::multienc::add_recipient;
+        use crate::multienc::content_key;
+        use crate::multienc::decrypt;
+        use crate::multienc::encrypt;
+        use crate::multienc::metadata_size;
+        use crate::participant::Secret;
+        use rand::thread_rng;
+
+        #[test]
+        fn grant_access() {
+            let plaintext = b"hello";
+
+            let secret_a = Secret::random(thread_rng());
+            let secret_b = Secret::random(thread_rng());
+            let secret_c = Secret::random(thread_rng());
+            let id_a = secret_a.to_identity();
+            let id_b = secret_b.to_identity();
+            let id_c = secret_c.to_identity();
+
+            let blob = encrypt(plaintext, [&id_a, &id_b], thread_rng());
+            assert!(decrypt(&secret_c, &blob).is_err());
+
+            let (metadata, ciphertext) = blob.split_at(metadata_size(2));
+            let key = content_key(&secret_b, &blob).expect("content key recovery failed");
+            let new_metadata = add_recipient(metadata, &key, [&id_a, &id_b], &id_c, thread_rng())
+                .expect("adding recipient failed");
+            assert_eq!(new_metadata.len(), metadata_size(3));
+
+            let new_blob = [&new_metadata[..], ciphertext].concat();
+            assert_eq!(&new_blob[new_metadata.len()..], ciphertext);
+
+            assert_eq!(decrypt(&secret_a, &new_blob).unwrap(), plaintext);
+            assert_eq!(decrypt(&secret_b, &new_blob).unwrap(), plaintext);
+            assert_eq!(decrypt(&secret_c, &new_blob).unwrap(), plaintext);
+
+            assert!(content_key(&secret_c, &blob).is_err());
+        }
+
+        #[test]
+        #[cfg(feature = "zeroize")]
+        fn zeroize_content_key() {
+            use crate::multienc::KEY_SIZE;
+            use zeroize::Zeroize;
+
+            let secret = Secret::random(thread_rng());
+            let blob = encrypt(b"hello", [&secret.to_identity()], thread_rng());
+
+            let mut key = content_key(&secret, &blob).expect("content key recovery failed");
+            assert_ne!(key.0, [0u8; KEY_SIZE]);
+            key.zeroize();
+            assert_eq!(key.0, [0u8; KEY_SIZE]);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod header {
+        use crate::multienc::encrypt;
+        use crate::multienc::is_recipient;
+        use crate::multienc::recipient_count;
+        use crate::multienc::MultiencError;
+        use crate::multienc::HEADER_SIZE;
+        use crate::participant::Secret;
+        use rand::thread_rng;
+
+        #[test]
+        fn count_recipients() {
+            let identities = (0..3)
+                .map(|_| Secret::random(thread_rng()).to_identity())
+                .collect::<Vec<_>>();
+
+            let blob = encrypt(b"hello", &identities, thread_rng());
+            assert_eq!(recipient_count(&blob), Ok(3));
+
+            let blob = encrypt(b"", &identities[..1], thread_rng());
+            assert_eq!(recipient_count(&blob), Ok(1));
+        }
+
+        #[test]
+        fn truncated_header() {
+            let identities = (0..3)
+                .map(|_| Secret::random(thread_rng()).to_identity())
+                .collect::<Vec<_>>();
+            let blob = encrypt(b"hello", &identities, thread_rng());
+
+            for len in [0, 1, HEADER_SIZE - 1, HEADER_SIZE, blob.len() - 1] {
+                assert_eq!(recipient_count(&blob[..len]), Err(MultiencError::Truncated));
+            }
+
+            // A header claiming an absurd number of recipients
+            let mut tampered = blob.clone();
+            tampered[48..52].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert!(recipient_count(&tampered).is_err());
+        }
+
+        #[test]
+        fn check_recipient() {
+            let secrets = (0..3)
+                .map(|_| Secret::random(thread_rng()))
+                .collect::<Vec<_>>();
+            let identities = secrets
+                .iter()
+                .map(|secret| secret.to_identity())
+                .collect::<Vec<_>>();
+            let blob = encrypt(b"hello", &identities[..2], thread_rng());
+
+            assert_eq!(is_recipient(&secrets[0], &blob), Ok(true));
+            assert_eq!(is_recipient(&secrets[1], &blob), Ok(true));
+            assert_eq!(is_recipient(&secrets[2], &blob), Ok(false));
+
+            let blob = encrypt(b"", &identities[..1], thread_rng());
+            assert_eq!(is_recipient(&secrets[0], &blob), Ok(true));
+            assert_eq!(is_recipient(&secrets[1], &blob), Ok(false));
+        }
+
+        #[test]
+        fn check_recipient_malformed() {
+            let secret = Secret::random(thread_rng());
+            let blob = encrypt(b"hello", [&secret.to_identity()], thread_rng());
+
+            for len in [0, HEADER_SIZE - 1, HEADER_SIZE, blob.len() - 1] {
+                assert_eq!(
+                    is_recipient(&secret, &blob[..len]),
+                    Err(MultiencError::Truncated)
+                );
+            }
+
+            let mut trailing = blob.clone();
+            trailing.push(0);
+            assert_eq!(
+                is_recipient(&secret, &trailing),
+                Err(MultiencError::MalformedHeader)
+            );
+
+            let mut tampered = blob.clone();
+            *tampered.last_mut().unwrap() ^= 0xff;
+            assert_eq!(is_recipient(&secret, &tampered), Ok(false));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod sharded {
+        use crate::multienc::decrypt_shard;
+        use crate::multienc::encrypt_sharded;
+        use crate::participant::Secret;
+        use rand::thread_rng;
+
+        #[test]
+        fn round_trip() {
+            let plaintext = b"hello";
+
+            let secrets = (0..5)
+                .map(|_| Secret::random(thread_rng()))
+                .collect::<Vec<_>>();
+            let identities = secrets
+                .iter()
+                .map(|secret| secret.to_identity())
+                .collect::<Vec<_>>();
+
+            let shards = encrypt_sharded(plaintext, &identities, 2, thread_rng());
+            assert_eq!(shards.len(), 3);
+
+            let mut manifest_ids = Vec::new();
+            for (index, secret) in secrets.iter().enumerate() {
+                let assigned_shard = index / 2;
+                for (shard_index, shard) in shards.iter().enumerate() {
+                    let result = decrypt_shard(secret, shard);
+                    if shard_index == assigned_shard {
+                        let (manifest, data) = result.expect("decryption failed");
+                        assert_eq!(data, plaintext);
+                        assert_eq!(manifest.index as usize, assigned_shard);
+                        assert_eq!(manifest.count, 3);
+                        manifest_ids.push(manifest.id);
+                    } else {
+                        assert!(result.is_err());
+                    }
+                }
+            }
+
+            assert!(manifest_ids.iter().all(|id| *id == manifest_ids[0]));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod stream {
+        use crate::multienc::decrypt_stream;
+        use crate::multienc::encrypt_stream;
+        use crate::multienc::STREAM_CHUNK_SIZE;
+        use crate::participant::Secret;
+        use rand::thread_rng;
+        use rand::RngCore;
+
+        #[test]
+        fn round_trip() {
+            let mut plaintext = vec![0u8; 10 * 1024 * 1024];
+            thread_rng().fill_bytes(&mut plaintext);
+
+            let secrets = (0..5)
+                .map(|_| Secret::random(thread_rng()))
+                .collect::<Vec<_>>();
+            let identities = secrets
+                .iter()
+                .map(|secret| secret.to_identity())
+                .collect::<Vec<_>>();
+
+            let mut ciphertext = Vec::new();
+            encrypt_stream(&identities, &plaintext[..], &mut ciphertext, thread_rng())
+                .expect("encryption failed");
+
+            for secret in secrets.iter() {
+                let mut decrypted = Vec::new();
+                decrypt_stream(secret, &ciphertext[..], &mut decrypted).expect("decryption failed");
+                assert_eq!(decrypted, plaintext);
+            }
+
+            let other_secret = Secret::random(thread_rng());
+            assert!(decrypt_stream(&other_secret, &ciphertext[..], &mut Vec::new()).is_err());
+        }
+
+        #[test]
+        fn tampering() {
+            let plaintext = vec![0x42u8; 2 * STREAM_CHUNK_SIZE];
+
+            let secret = Secret::random(thread_rng());
+            let identity = secret.to_identity();
+
+            let mut ciphertext = Vec::new();
+            encrypt_stream([&identity], &plaintext[..], &mut ciphertext, thread_rng())
+                .expect("encryption failed");
+
+            let mut decrypted = Vec::new();
+            decrypt_stream(&secret, &ciphertext[..], &mut decrypted).expect("decryption failed");
+            assert_eq!(decrypted, plaintext);
+
+            // Altering the last byte of the ciphertext should be detected
+            let mut tampered = ciphertext.clone();
+            *tampered.last_mut().unwrap() ^= 0xff;
+            assert!(decrypt_stream(&secret, &tampered[..], &mut Vec::new()).is_err());
+
+            // Dropping the last (empty) chunk should be detected
+            let truncated = &ciphertext[..ciphertext.len() - (1 + 4 + 16)];
+            assert!(decrypt_stream(&secret, truncated, &mut Vec::new()).is_err());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod errors {
+        use crate::multienc::decrypt;
+        use crate::multienc::decrypt_stream;
+        use crate::multienc::encrypt;
+        use crate::multienc::encrypt_stream;
+        use crate::multienc::MultiencError;
+        use crate::multienc::HEADER_SIZE;
+        use crate::multienc::STREAM_CHUNK_SIZE;
+        use crate::participant::Secret;
+        use rand::thread_rng;
+        use std::io;
+
+        #[test]
+        fn truncated() {
+            let secret = Secret::random(thread_rng());
+            let blob = encrypt(b"hello", [&secret.to_identity()], thread_rng());
+
+            assert_eq!(
+                decrypt(&secret, &blob[..HEADER_SIZE - 1]),
+                Err(MultiencError::Truncated)
+            );
+            assert_eq!(
+                decrypt(&secret, &blob[..blob.len() - 1]),
+                Err(MultiencError::Truncated)
+            );
+        }
+
+        #[test]
+        fn not_a_recipient() {
+            let secret = Secret::random(thread_rng());
+            let blob = encrypt(b"hello", [&secret.to_identity()], thread_rng());
+
+            let other_secret = Secret::random(thread_rng());
+            assert_eq!(
+                decrypt(&other_secret, &blob),
+                Err(MultiencError::NotARecipient)
+            );
+        }
+
+        #[test]
+        fn authentication_failed() {
+            let plaintext = vec![0x42u8; 2 * STREAM_CHUNK_SIZE];
+            let secret = Secret::random(thread_rng());
+
+            let mut ciphertext = Vec::new();
+            encrypt_stream(
+                [&secret.to_identity()],
+                &plaintext[..],
+                &mut ciphertext,
+                thread_rng(),
+            )
+            .expect("encryption failed");
+
+            // Alter the tag of the last chunk: the first chunk identifies the encryption key, so
+            // the failure can be attributed to tampering
+            *ciphertext.last_mut().unwrap() ^= 0xff;
+            let err = decrypt_stream(&secret, &ciphertext[..], &mut Vec::new())
+                .expect_err("tampering was not detected");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(
+                err.get_ref()
+                    .and_then(|inner| inner.downcast_ref::<MultiencError>()),
+                Some(&MultiencError::AuthenticationFailed)
+            );
+        }
+
+        #[test]
+        fn malformed_header() {
+            let secret = Secret::random(thread_rng());
+            let mut blob = encrypt(b"hello", [&secret.to_identity()], thread_rng());
+
+            // Decrease the data length recorded in the header, so that it no longer matches the
+            // size of the ciphertext
+            blob[HEADER_SIZE - 4] -= 1;
+            assert_eq!(decrypt(&secret, &blob), Err(MultiencError::MalformedHeader));
+        }
+
+        #[test]
+        fn io_error_conversion() {
+            let err = io::Error::from(MultiencError::Truncated);
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+            let err = io::Error::from(MultiencError::NotARecipient);
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), MultiencError::NotARecipient.to_string());
+        }
+    }
+
+    #[cfg(all(feature = "signing", feature = "std"))]
+    mod vectors {
+        use crate::multienc::test_vectors;
+        use crate::multienc::verify_vector;
+        use hex_literal::hex;
+
+        #[test]
+        fn deserialization_regression() {
+            let expected: [&[u8]; 3] = [
+                &hex!(
+                    "
+                    15f669547d0890570b1219f46ba321badc532da70e8839c6780a50e33d44a41bbed
+                    b3b1e6227fb3bd8978ed81a2d7223010000000000000059247e05fa869669643399
+                    412727b8fcd4a46732cc3124685edc683d97e7bde8
+                "
+                ),
+                &hex!(
+                    "
+                    fbc29f1a413c1edbc9a03a505c9ab4eb8fcf4607e447304a6b84c1508cb42d6be24
+                    6c66972aa24784b4c85af12f5d06702000000050000004d0b1ff2e2f266a84287ec
+                    f3c818d3154d1b69ffdf19ba6663746514529dedf84a3a1b6235017e23411b99260
+                    2fb24ce779a588bcfd054cfac0b714480b8e363b5f2aaa233
+                "
+                ),
+                &hex!(
+                    "
+                    49be0af83648604013c4f038a49aabebf7a77daf006e12e23cb0870918e4071815c
+                    d4b90d1656c9fef36b6f151ec458b03000000400000001ccbf5e3acfa039978d0dd
+                    f6556452a7e387289563c3627036295afed1c56052934580d795be9c3ff2de2be02
+                    824f045127ab99865aeb310ac66426b4bd1bf583449f680df0e13ed3619d314c7ca
+                    e5fec4d11d1d34266d3c6f1643687154f1516d103a07735e16c8a232969c4caf351
+                    eff0ffb3d0e70f43ac3bdb8ed6100b52579d6dc6134376af5a6727d7ba430bb2336
+                    98df643178052d6bb9603486fa76c4
+                "
+                ),
+            ];
+
+            let vectors = test_vectors();
+            assert_eq!(vectors.len(), expected.len());
+            for (vector, expected) in vectors.iter().zip(expected) {
+                assert_eq!(vector.ciphertext, expected);
+                verify_vector(vector).expect("test vector verification failed");
+            }
+        }
+
+        #[test]
+        fn tampered_vector() {
+            let mut vectors = test_vectors();
+            let vector = &mut vectors[1];
+            *vector.ciphertext.last_mut().unwrap() ^= 0xff;
+            assert!(verify_vector(vector).is_err());
+        }
+    }
+
     mod in_place {
         use crate::multienc::decrypt_in_place;
         use crate::multienc::encrypt_in_place;
@@ -2,20 +2,48 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::error::Error;
 use crate::frost::keys::SigningShare;
+use crate::frost::round1::NonceCommitment;
+use crate::frost::round1::SigningCommitments;
 use crate::frost::round1::SigningNonces;
+use crate::frost::Group;
+use crate::frost::JubjubGroup;
 use crate::participant::Identity;
 use crate::participant::IdentitySerialization;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
 use rand_chacha::ChaCha20Rng;
 use rand_core::SeedableRng;
-use std::borrow::Borrow;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "std")]
+use std::sync::PoisonError;
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
 
 type ParticipantCount = u32;
 
+#[cfg(feature = "std")]
+const SESSION_KEY_CONTEXT: &str = "ironfish-frost 2024 signing session key";
+
+/// Default deployment salt used by [`deterministic_signing_nonces`].
+///
+/// The empty salt is not mixed into the nonce derivation, so nonces derived with it match the
+/// ones derived by previous versions of this crate.
+pub const DEFAULT_NONCES_SALT: &[u8] = b"";
+
 fn nonces_seed<I>(
     secret: &SigningShare,
     transaction_hash: &[u8],
     signing_participants: &[I],
+    salt: &[u8],
 ) -> <ChaCha20Rng as SeedableRng>::Seed
 where
     I: Borrow<Identity>,
@@ -41,6 +69,11 @@ where
         hasher.update(&id);
     });
 
+    if !salt.is_empty() {
+        hasher.update(&(salt.len() as u64).to_le_bytes());
+        hasher.update(salt);
+    }
+
     hasher.finalize().into()
 }
 
@@ -49,6 +82,15 @@ where
 /// The nonces generated are *deterministic*: given the same `secret`, `transaction_hash`, and list
 /// of `signing_participants`, the nonces returned are the same. The order of
 /// `signing_participants` can be changed without influencing the output.
+///
+/// These are the nonces used by
+/// [`SigningCommitment::try_from_secrets`](crate::signing_commitment::SigningCommitment::try_from_secrets),
+/// so custom signing flows can reproduce them.
+///
+/// Unlike the commitment constructors, this function does not reject degenerate signing shares,
+/// even with the `reject-weak-keys` feature: callers that do not go through a
+/// [`SigningCommitment`](crate::signing_commitment::SigningCommitment) constructor first should
+/// check `secret` with [`is_degenerate_signing_share`].
 pub fn deterministic_signing_nonces<I>(
     secret: &SigningShare,
     transaction_hash: &[u8],
@@ -57,17 +99,339 @@ pub fn deterministic_signing_nonces<I>(
 where
     I: Borrow<Identity>,
 {
-    let seed = nonces_seed(secret, transaction_hash, signing_participants);
+    deterministic_signing_nonces_with_salt(
+        secret,
+        transaction_hash,
+        signing_participants,
+        DEFAULT_NONCES_SALT,
+    )
+}
+
+/// Returns the [`SigningCommitments`] of the nonces produced by [`deterministic_signing_nonces`].
+///
+/// Like the nonces, the commitments are deterministic: the same inputs always yield the same
+/// commitments, regardless of the order of `signing_participants`. This allows precomputing the
+/// commitments of a signer without holding on to the nonces.
+///
+/// With the `reject-weak-keys` feature, returns an error if `secret_share` is degenerate (see
+/// [`is_degenerate_signing_share`]).
+pub fn commitments_for<I>(
+    secret_share: &SigningShare,
+    transaction_hash: &[u8],
+    signing_participants: &[I],
+) -> Result<SigningCommitments, Error>
+where
+    I: Borrow<Identity>,
+{
+    let nonces = checked_signing_nonces(
+        secret_share,
+        transaction_hash,
+        signing_participants,
+        DEFAULT_NONCES_SALT,
+    )?;
+    Ok(*nonces.commitments())
+}
+
+/// Generate [`SigningNonces`] for a signer participant, mixing a deployment-specific `salt` into
+/// the derivation.
+///
+/// Deployments using different salts derive unrelated nonces from the same inputs, and therefore
+/// cannot interoperate. Using [`DEFAULT_NONCES_SALT`] is equivalent to calling
+/// [`deterministic_signing_nonces`]. Like [`deterministic_signing_nonces`], this function does not
+/// reject degenerate signing shares.
+pub fn deterministic_signing_nonces_with_salt<I>(
+    secret: &SigningShare,
+    transaction_hash: &[u8],
+    signing_participants: &[I],
+    salt: &[u8],
+) -> SigningNonces
+where
+    I: Borrow<Identity>,
+{
+    let seed = nonces_seed(secret, transaction_hash, signing_participants, salt);
     let mut csrng = ChaCha20Rng::from_seed(seed);
     SigningNonces::new(secret, &mut csrng)
 }
 
-#[cfg(test)]
+/// Same as [`deterministic_signing_nonces_with_salt`], but also returns the time it took to derive
+/// the nonces.
+///
+/// This is meant for instrumentation: integrators can assert a performance budget on it, for
+/// example to catch a deployment built without optimizations.
+#[cfg(feature = "std")]
+pub fn derive_timed<I>(
+    secret: &SigningShare,
+    transaction_hash: &[u8],
+    signing_participants: &[I],
+    salt: &[u8],
+) -> (SigningNonces, Duration)
+where
+    I: Borrow<Identity>,
+{
+    let start = Instant::now();
+    let nonces = deterministic_signing_nonces_with_salt(
+        secret,
+        transaction_hash,
+        signing_participants,
+        salt,
+    );
+    (nonces, start.elapsed())
+}
+
+/// Error returned by [`SessionRegistry::begin`] when a session with the same nonce derivation
+/// inputs is already in progress.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NonceReuse;
+
+impl fmt::Display for NonceReuse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a signing session with the same nonces is already in progress"
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NonceReuse {}
+
+/// Keeps track of the signing sessions in progress, to prevent running concurrent sessions that
+/// would use the same nonces.
+///
+/// Because nonces are derived deterministically from the signing share, the transaction hash and
+/// the signing participants (see [`deterministic_signing_nonces`]), two concurrent sessions with
+/// the same inputs would reuse the same nonces. Sessions are identified by a digest of these
+/// inputs, so the registry does not hold any secret material.
+#[cfg(feature = "std")]
+#[derive(Default, Debug)]
+pub struct SessionRegistry {
+    active: Mutex<HashSet<[u8; 32]>>,
+}
+
+#[cfg(feature = "std")]
+impl SessionRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new session, failing if another session with the same inputs is still in
+    /// progress. The session ends when the returned [`SessionGuard`] is dropped.
+    ///
+    /// `salt` must be the salt that the nonces of the session are derived with (see
+    /// [`deterministic_signing_nonces_with_salt`]), or [`DEFAULT_NONCES_SALT`] for nonces derived
+    /// with [`deterministic_signing_nonces`]. Sessions with different salts use different nonces,
+    /// so they do not conflict.
+    pub fn begin<I>(
+        &self,
+        secret: &SigningShare,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+        salt: &[u8],
+    ) -> Result<SessionGuard<'_>, NonceReuse>
+    where
+        I: Borrow<Identity>,
+    {
+        // The seed must not be stored, as it would reveal the nonces
+        let seed = nonces_seed(secret, transaction_hash, signing_participants, salt);
+        let key = blake3::derive_key(SESSION_KEY_CONTEXT, &seed);
+        let mut active = self.active.lock().unwrap_or_else(PoisonError::into_inner);
+        if !active.insert(key) {
+            return Err(NonceReuse);
+        }
+        Ok(SessionGuard {
+            registry: self,
+            key,
+        })
+    }
+
+    /// Returns the number of sessions in progress.
+    #[must_use]
+    pub fn active_sessions(&self) -> usize {
+        self.active
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
+
+/// A signing session in progress, registered in a [`SessionRegistry`]. The session ends when this
+/// guard is dropped.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+#[must_use]
+pub struct SessionGuard<'a> {
+    registry: &'a SessionRegistry,
+    key: [u8; 32],
+}
+
+#[cfg(feature = "std")]
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        self.registry
+            .active
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.key);
+    }
+}
+
+/// A holder of a signing share that can produce nonce commitments without exposing the share
+/// itself.
+///
+/// This allows generating a [`SigningCommitment`](crate::signing_commitment::SigningCommitment)
+/// from a share held, for example, by a hardware security module. Implementations must derive the
+/// nonces in the same way as [`deterministic_signing_nonces`], otherwise the commitments won't
+/// match the nonces used at signing time. Because the share never leaves the provider,
+/// implementations are also responsible for refusing degenerate shares (see
+/// [`is_degenerate_signing_share`]).
+pub trait ShareProvider {
+    type Error;
+
+    fn signing_commitments(
+        &self,
+        transaction_hash: &[u8],
+        signing_participants: &[&Identity],
+    ) -> Result<SigningCommitments, Self::Error>;
+}
+
+impl ShareProvider for SigningShare {
+    type Error = Error;
+
+    fn signing_commitments(
+        &self,
+        transaction_hash: &[u8],
+        signing_participants: &[&Identity],
+    ) -> Result<SigningCommitments, Self::Error> {
+        let nonces = checked_signing_nonces(
+            self,
+            transaction_hash,
+            signing_participants,
+            DEFAULT_NONCES_SALT,
+        )?;
+        Ok(*nonces.commitments())
+    }
+}
+
+/// Same as [`deterministic_signing_nonces_with_salt`], but fails if `secret` is a degenerate share
+/// (see [`is_degenerate_signing_share`]) and the `reject-weak-keys` feature is enabled.
+///
+/// All the constructors of
+/// [`SigningCommitment`](crate::signing_commitment::SigningCommitment) that take a
+/// [`SigningShare`] derive their nonces through this function.
+pub(crate) fn checked_signing_nonces<I>(
+    secret: &SigningShare,
+    transaction_hash: &[u8],
+    signing_participants: &[I],
+    salt: &[u8],
+) -> Result<SigningNonces, Error>
+where
+    I: Borrow<Identity>,
+{
+    if cfg!(feature = "reject-weak-keys") && is_degenerate_signing_share(secret) {
+        return Err(Error::InvalidInput(
+            "refusing to use a degenerate signing share".to_string(),
+        ));
+    }
+    Ok(deterministic_signing_nonces_with_salt(
+        secret,
+        transaction_hash,
+        signing_participants,
+        salt,
+    ))
+}
+
+/// Returns `true` if `secret` is a degenerate signing share that must never be used for signing.
+///
+/// A signing share is considered degenerate if it is zero (which is what
+/// [`SigningShare::default`] returns) or one. Such shares can only be the result of a bug, like a
+/// zeroed or uninitialized key reaching the signing code.
+#[must_use]
+pub fn is_degenerate_signing_share(secret: &SigningShare) -> bool {
+    let serialization = secret.serialize();
+    let (first, rest) = serialization
+        .split_first()
+        .expect("signing share serialization should not be empty");
+    rest.iter().all(|&byte| byte == 0) && (*first == 0 || *first == 1)
+}
+
+/// Reconstructs [`SigningCommitments`] from the serialized hiding and binding nonce commitments.
+///
+/// This is meant for transports where the two nonce commitments are transmitted separately. Both
+/// commitments must be valid points, must not be the identity element, and must be distinct.
+pub fn commitments_from_parts(
+    hiding: &[u8; 32],
+    binding: &[u8; 32],
+) -> Result<SigningCommitments, Error> {
+    if hiding == binding {
+        return Err(Error::InvalidInput(
+            "hiding and binding commitments must be distinct".to_string(),
+        ));
+    }
+
+    let identity_element = JubjubGroup::serialize(&JubjubGroup::identity());
+    if *hiding == identity_element || *binding == identity_element {
+        return Err(Error::InvalidInput(
+            "nonce commitments must not be the identity element".to_string(),
+        ));
+    }
+
+    let hiding = NonceCommitment::deserialize(*hiding).map_err(Error::FrostError)?;
+    let binding = NonceCommitment::deserialize(*binding).map_err(Error::FrostError)?;
+
+    Ok(SigningCommitments::new(hiding, binding))
+}
+
+/// Verifies that the nonce commitments `hiding` and `binding` were derived from the given inputs
+/// using [`deterministic_signing_nonces_with_salt`], with `salt` set to `aux`.
+///
+/// This allows a participant to prove, by revealing the inputs, that their nonces were derived
+/// correctly. Use [`DEFAULT_NONCES_SALT`] as `aux` for nonces derived with
+/// [`deterministic_signing_nonces`].
+pub fn verify_commitment_from_inputs<I>(
+    hiding: &NonceCommitment,
+    binding: &NonceCommitment,
+    secret: &SigningShare,
+    transaction_hash: &[u8],
+    signing_participants: &[I],
+    aux: &[u8],
+) -> Result<(), Error>
+where
+    I: Borrow<Identity>,
+{
+    let nonces =
+        deterministic_signing_nonces_with_salt(secret, transaction_hash, signing_participants, aux);
+    let expected = nonces.commitments();
+    if expected.hiding() == hiding && expected.binding() == binding {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(
+            "nonce commitments were not derived from the given inputs".to_string(),
+        ))
+    }
+}
+
+/// Returns a fixed signing share, which is not degenerate.
+#[cfg(all(test, feature = "std"))]
+pub(crate) fn test_signing_share() -> SigningShare {
+    SigningShare::deserialize(*b"some signing share.............\0").unwrap()
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
+    use super::commitments_for;
+    use super::commitments_from_parts;
+    use super::derive_timed;
     use super::deterministic_signing_nonces;
+    use super::deterministic_signing_nonces_with_salt;
+    use super::is_degenerate_signing_share;
+    use super::verify_commitment_from_inputs;
+    use super::SessionRegistry;
+    use super::DEFAULT_NONCES_SALT;
     use crate::nonces::SigningShare;
     use crate::participant::Secret;
     use rand::thread_rng;
+    use std::time::Duration;
 
     macro_rules! assert_nonces_eq {
         ( $left:expr , $right:expr ) => {
@@ -103,6 +467,107 @@ mod tests {
         };
     }
 
+    #[test]
+    fn degenerate_signing_shares() {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+
+        assert!(is_degenerate_signing_share(&SigningShare::default()));
+        assert!(is_degenerate_signing_share(
+            &SigningShare::deserialize([0u8; 32]).unwrap()
+        ));
+        assert!(is_degenerate_signing_share(
+            &SigningShare::deserialize(one).unwrap()
+        ));
+        assert!(!is_degenerate_signing_share(
+            &SigningShare::deserialize(*b"some signing share.............\0").unwrap()
+        ));
+    }
+
+    #[test]
+    fn commitments_from_parts_round_trip() {
+        let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+        let transaction_hash = b"some hash";
+        let signing_participants = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+
+        let nonces = deterministic_signing_nonces(&secret, transaction_hash, &signing_participants);
+        let commitments = nonces.commitments();
+
+        let reconstructed = commitments_from_parts(
+            &commitments.hiding().serialize(),
+            &commitments.binding().serialize(),
+        )
+        .expect("reconstructing commitments failed");
+
+        assert_eq!(&reconstructed, commitments);
+    }
+
+    #[test]
+    fn commitments_for_determinism() {
+        let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+        let transaction_hash = b"some hash";
+        let signing_participants = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let reordered_participants = [
+            signing_participants[1].clone(),
+            signing_participants[0].clone(),
+        ];
+
+        let commitments1 = commitments_for(&secret, transaction_hash, &signing_participants)
+            .expect("computing commitments failed");
+        let commitments2 = commitments_for(&secret, transaction_hash, &reordered_participants)
+            .expect("computing commitments failed");
+
+        assert_eq!(
+            commitments1.hiding().serialize(),
+            commitments2.hiding().serialize()
+        );
+        assert_eq!(
+            commitments1.binding().serialize(),
+            commitments2.binding().serialize()
+        );
+
+        let nonces = deterministic_signing_nonces(&secret, transaction_hash, &signing_participants);
+        assert_eq!(&commitments1, nonces.commitments());
+
+        let other_commitments = commitments_for(&secret, b"other hash", &signing_participants)
+            .expect("computing commitments failed");
+        assert_ne!(commitments1, other_commitments);
+    }
+
+    #[test]
+    #[cfg(feature = "reject-weak-keys")]
+    fn commitments_for_rejects_degenerate_shares() {
+        let signing_participants = [Secret::random(thread_rng()).to_identity()];
+        commitments_for(
+            &SigningShare::default(),
+            b"some hash",
+            &signing_participants,
+        )
+        .expect_err("degenerate signing share should have been rejected");
+    }
+
+    #[test]
+    fn commitments_from_parts_invalid_points() {
+        let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+        let signing_participants = [Secret::random(thread_rng()).to_identity()];
+        let nonces = deterministic_signing_nonces(&secret, b"some hash", &signing_participants);
+        let hiding = nonces.commitments().hiding().serialize();
+
+        let mut identity_element = [0u8; 32];
+        identity_element[0] = 1;
+
+        assert!(commitments_from_parts(&hiding, &hiding).is_err());
+        assert!(commitments_from_parts(&hiding, &identity_element).is_err());
+        assert!(commitments_from_parts(&identity_element, &hiding).is_err());
+        assert!(commitments_from_parts(&hiding, &[0xff; 32]).is_err());
+    }
+
     #[test]
     fn same_input() {
         let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
@@ -120,6 +585,28 @@ mod tests {
         assert_nonces_eq!(nonces1, nonces2);
     }
 
+    #[test]
+    fn timed_derivation() {
+        let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+        let transaction_hash = b"some hash";
+        let p1 = Secret::random(thread_rng()).to_identity();
+        let p2 = Secret::random(thread_rng()).to_identity();
+        let signing_participants = [p1, p2];
+
+        let (nonces, elapsed) = derive_timed(
+            &secret,
+            transaction_hash,
+            &signing_participants,
+            DEFAULT_NONCES_SALT,
+        );
+
+        assert!(elapsed > Duration::ZERO);
+        assert_nonces_eq!(
+            nonces,
+            deterministic_signing_nonces(&secret, transaction_hash, &signing_participants)
+        );
+    }
+
     #[test]
     fn different_participants_order() {
         let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
@@ -210,4 +697,153 @@ mod tests {
 
         assert_nonces_ne!(nonces1, nonces2);
     }
+
+    #[test]
+    fn different_salts() {
+        let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+        let transaction_hash = b"some hash";
+        let p1 = Secret::random(thread_rng()).to_identity();
+        let p2 = Secret::random(thread_rng()).to_identity();
+        let signing_participants = [p1, p2];
+
+        let nonces = |salt: &[u8]| {
+            deterministic_signing_nonces_with_salt(
+                &secret,
+                transaction_hash,
+                &signing_participants,
+                salt,
+            )
+        };
+
+        assert_nonces_eq!(nonces(b"salt 1"), nonces(b"salt 1"));
+        assert_nonces_ne!(nonces(b"salt 1"), nonces(b"salt 2"));
+        assert_nonces_ne!(nonces(b"salt 1"), nonces(DEFAULT_NONCES_SALT));
+        assert_nonces_eq!(
+            nonces(DEFAULT_NONCES_SALT),
+            deterministic_signing_nonces(&secret, transaction_hash, &signing_participants)
+        );
+    }
+
+    #[test]
+    fn session_registry_rejects_concurrent_sessions() {
+        let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+        let p1 = Secret::random(thread_rng()).to_identity();
+        let p2 = Secret::random(thread_rng()).to_identity();
+        let signing_participants = [p1, p2];
+
+        let registry = SessionRegistry::new();
+
+        let guard = registry
+            .begin(
+                &secret,
+                b"some hash",
+                &signing_participants,
+                DEFAULT_NONCES_SALT,
+            )
+            .expect("first session should have started");
+        registry
+            .begin(
+                &secret,
+                b"some hash",
+                &signing_participants,
+                DEFAULT_NONCES_SALT,
+            )
+            .expect_err("concurrent session should have been rejected");
+        let other_guard = registry
+            .begin(
+                &secret,
+                b"some other hash",
+                &signing_participants,
+                DEFAULT_NONCES_SALT,
+            )
+            .expect("session with different inputs should have started");
+        let salted_guard = registry
+            .begin(&secret, b"some hash", &signing_participants, b"salt")
+            .expect("session with a different salt should have started");
+        registry
+            .begin(&secret, b"some hash", &signing_participants, b"salt")
+            .expect_err("concurrent salted session should have been rejected");
+        assert_eq!(registry.active_sessions(), 3);
+
+        drop(guard);
+        drop(other_guard);
+        drop(salted_guard);
+        assert_eq!(registry.active_sessions(), 0);
+
+        registry
+            .begin(
+                &secret,
+                b"some hash",
+                &signing_participants,
+                DEFAULT_NONCES_SALT,
+            )
+            .expect("session should have started after the first one ended");
+    }
+
+    #[test]
+    fn verify_commitment_from_inputs_detects_altered_inputs() {
+        let secret = SigningShare::deserialize(*b"some signing share.............\0").unwrap();
+        let other_secret =
+            SigningShare::deserialize(*b"some other signing share.......\0").unwrap();
+        let p1 = Secret::random(thread_rng()).to_identity();
+        let p2 = Secret::random(thread_rng()).to_identity();
+        let signing_participants = [p1.clone(), p2];
+
+        let nonces = deterministic_signing_nonces(&secret, b"some hash", &signing_participants);
+        let hiding = nonces.commitments().hiding();
+        let binding = nonces.commitments().binding();
+
+        verify_commitment_from_inputs(
+            hiding,
+            binding,
+            &secret,
+            b"some hash",
+            &signing_participants,
+            DEFAULT_NONCES_SALT,
+        )
+        .expect("verification with correct inputs failed");
+
+        let altered_inputs: [(&SigningShare, &[u8], &[_], &[u8]); 4] = [
+            (
+                &other_secret,
+                b"some hash",
+                &signing_participants,
+                DEFAULT_NONCES_SALT,
+            ),
+            (
+                &secret,
+                b"some other hash",
+                &signing_participants,
+                DEFAULT_NONCES_SALT,
+            ),
+            (
+                &secret,
+                b"some hash",
+                &signing_participants[..1],
+                DEFAULT_NONCES_SALT,
+            ),
+            (&secret, b"some hash", &signing_participants, b"some salt"),
+        ];
+        for (secret, transaction_hash, signing_participants, aux) in altered_inputs {
+            verify_commitment_from_inputs(
+                hiding,
+                binding,
+                secret,
+                transaction_hash,
+                signing_participants,
+                aux,
+            )
+            .expect_err("verification with altered inputs should have failed");
+        }
+
+        verify_commitment_from_inputs(
+            binding,
+            hiding,
+            &secret,
+            b"some hash",
+            &signing_participants,
+            DEFAULT_NONCES_SALT,
+        )
+        .expect_err("verification with swapped commitments should have failed");
+    }
 }
@@ -2,8 +2,15 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+#[cfg(feature = "signing")]
+use crate::checksum::Checksum;
 use crate::frost;
 use crate::io;
+#[cfg(feature = "signing")]
+use alloc::string::String;
+#[cfg(feature = "signing")]
+use alloc::vec::Vec;
+use core::borrow::Borrow;
 use core::cell::OnceCell;
 use core::cmp;
 use core::hash::Hash;
@@ -14,8 +21,14 @@ use ed25519_dalek::Verifier;
 use ed25519_dalek::VerifyingKey;
 use rand_core::CryptoRng;
 use rand_core::RngCore;
+use subtle::Choice;
+use subtle::ConstantTimeEq;
 use x25519_dalek::PublicKey;
 use x25519_dalek::StaticSecret;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
 
 const VERSION_LEN: usize = 1;
 const VERSION: [u8; VERSION_LEN] = [0x72];
@@ -34,10 +47,172 @@ const SIGNATURE_LEN: usize = Signature::BYTE_SIZE;
 pub const IDENTITY_LEN: usize =
     VERSION_LEN + VERIFICATION_KEY_LEN + ENCRYPTION_KEY_LEN + SIGNATURE_LEN;
 
+/// Recommended maximum number of participants allowed in a group.
+///
+/// FROST itself allows up to `u16::MAX` participants, but groups of that size are impractical:
+/// every participant needs to exchange packages with every other participant.
+///
+/// This limit is opt-in, so that groups that were created with more participants keep working:
+/// pass it to [`round1_with_max_participants`](crate::dkg::round1::round1_with_max_participants)
+/// or [`round1_with_options`](crate::dkg::round1::round1_with_options) to enforce it. It is only
+/// enforced when a group is created: signing commitments are always generated for a subset of an
+/// existing group, so they are not checked against it.
+pub const MAX_PARTICIPANTS: usize = 255;
+
+#[cfg(feature = "signing")]
+const COORDINATOR_ELECTION_CONTEXT: &str = "ironfish-frost 2024 coordinator election";
+#[cfg(feature = "signing")]
+const SESSION_SAS_CONTEXT: &str = "ironfish-frost 2024 session SAS";
+#[cfg(all(feature = "signing", feature = "std"))]
+const ACCENT_COLOR_CONTEXT: &str = "ironfish-frost 2024 Identity accent color";
+
+/// Number of words in a short authentication string. See [`session_sas`].
+pub const SAS_WORD_COUNT: usize = 6;
+
 pub type Signature = ed25519_dalek::Signature;
 pub type SignatureError = ed25519_dalek::SignatureError;
 pub type IdentitySerialization = [u8; IDENTITY_LEN];
 
+/// Returns the 1-based position of `identity` in the sorted, deduplicated list of
+/// `participants`, or `None` if `identity` is not a participant.
+///
+/// The result does not depend on the order of `participants`, so all parties agree on the index
+/// of each participant. This is meant for display purposes only.
+#[must_use]
+pub fn index_in<I>(identity: &Identity, participants: &[I]) -> Option<usize>
+where
+    I: Borrow<Identity>,
+{
+    let mut found = false;
+    let mut smaller = 0;
+    for (i, participant) in participants.iter().enumerate() {
+        let participant = participant.borrow();
+        if participant == identity {
+            found = true;
+        } else if participant < identity
+            && !participants[..i]
+                .iter()
+                .any(|previous| previous.borrow() == participant)
+        {
+            smaller += 1;
+        }
+    }
+    found.then_some(smaller + 1)
+}
+
+/// Deterministically picks a coordinator among `participants` for the session identified by
+/// `session_checksum`.
+///
+/// All parties that agree on the set of participants and on the session checksum elect the same
+/// coordinator, regardless of the order of `participants`. Different sessions are likely to elect
+/// different coordinators.
+///
+/// # Panics
+///
+/// Panics if `participants` is empty.
+#[cfg(feature = "signing")]
+#[must_use]
+pub fn elect_coordinator<I>(participants: &[I], session_checksum: Checksum) -> Identity
+where
+    I: Borrow<Identity>,
+{
+    let mut participants = participants
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<Vec<&Identity>>();
+    participants.sort_unstable();
+    participants.dedup();
+    assert!(!participants.is_empty(), "no participants to elect from");
+
+    let mut hasher = blake3::Hasher::new_derive_key(COORDINATOR_ELECTION_CONTEXT);
+    hasher.update(&session_checksum.to_le_bytes());
+    hasher.update(&(participants.len() as u64).to_le_bytes());
+    for participant in &participants {
+        hasher.update(&participant.serialize());
+    }
+    let hash = hasher.finalize();
+    let hash = u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap());
+
+    participants[(hash % participants.len() as u64) as usize].clone()
+}
+
+/// Returns a short authentication string (SAS) for a session, made of [`SAS_WORD_COUNT`] words
+/// separated by spaces.
+///
+/// The SAS is derived from `session_checksum` and from all the `participants` (in any order), so
+/// participants can read it aloud over an out-of-band channel to confirm that they all joined the
+/// same session, with the same participants. Each word encodes 8 bits, so the SAS carries 48
+/// bits: this is enough to detect a man-in-the-middle in an interactive comparison, but the SAS
+/// must not be used as a long-term identifier.
+#[cfg(feature = "signing")]
+#[must_use]
+pub fn session_sas<I>(participants: &[I], session_checksum: Checksum) -> String
+where
+    I: Borrow<Identity>,
+{
+    let mut participants = participants
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<Vec<&Identity>>();
+    participants.sort_unstable();
+    participants.dedup();
+
+    let mut hasher = blake3::Hasher::new_derive_key(SESSION_SAS_CONTEXT);
+    hasher.update(&session_checksum.to_le_bytes());
+    hasher.update(&(participants.len() as u64).to_le_bytes());
+    for participant in &participants {
+        hasher.update(&participant.serialize());
+    }
+    let hash = hasher.finalize();
+
+    hash.as_bytes()[..SAS_WORD_COUNT]
+        .iter()
+        .map(|&byte| SAS_WORDS[byte as usize])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Verifies multiple signatures, each produced by a possibly different identity, at once.
+///
+/// When all the signatures are valid, this is faster than calling [`Identity::verify_data`] on
+/// each item. Otherwise, returns the indices of the items that failed verification.
+///
+/// Batch verification uses the cofactored verification equation, which may accept some
+/// non-canonical signatures that [`Identity::verify_data`] rejects. Such signatures cannot be
+/// produced by honest signers.
+#[cfg(feature = "std")]
+pub fn verify_batch(items: &[(&Identity, &[u8], &Signature)]) -> Result<(), Vec<usize>> {
+    let messages = items.iter().map(|(_, data, _)| *data).collect::<Vec<_>>();
+    let signatures = items
+        .iter()
+        .map(|(_, _, signature)| **signature)
+        .collect::<Vec<_>>();
+    let verifying_keys = items
+        .iter()
+        .map(|(identity, _, _)| *identity.verification_key())
+        .collect::<Vec<_>>();
+
+    if ed25519_dalek::verify_batch(&messages, &signatures, &verifying_keys).is_ok() {
+        return Ok(());
+    }
+
+    // Fall back to verifying each item individually to find out which ones are invalid
+    let failed = items
+        .iter()
+        .enumerate()
+        .filter(|(_, (identity, data, signature))| identity.verify_data(data, signature).is_err())
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    if failed.is_empty() {
+        // The batch was rejected, but no individual signature is invalid: this can only happen
+        // with non-canonical signatures. Reject the whole batch to be safe.
+        Err((0..items.len()).collect())
+    } else {
+        Err(failed)
+    }
+}
+
 /// Returns the portion of identifier data that is signed by [`Secret::signing_key`]
 fn authenticated_data(
     verification_key: &VerifyingKey,
@@ -58,6 +233,61 @@ fn authenticated_data(
     data
 }
 
+/// Finds pairs of distinct identities that share one of their embedded public keys (the
+/// verification key or the encryption key), but not the other.
+///
+/// Honest participants generate both keys at random, so a partial collision almost certainly
+/// indicates a configuration mistake, such as an identity entry that was copied and only partially
+/// edited. Returns the indices of each colliding pair, with the lower index first.
+#[cfg(feature = "std")]
+pub fn find_partial_collisions(identities: &[Identity]) -> Vec<(usize, usize)> {
+    let mut collisions = Vec::new();
+    for (i, first) in identities.iter().enumerate() {
+        for (j, second) in identities.iter().enumerate().skip(i + 1) {
+            let same_verification_key = first.verification_key() == second.verification_key();
+            let same_encryption_key =
+                first.encryption_key().as_bytes() == second.encryption_key().as_bytes();
+            if same_verification_key != same_encryption_key {
+                collisions.push((i, j));
+            }
+        }
+    }
+    collisions
+}
+
+/// Word list used by [`session_sas`]. Words are distinct, and easy to tell apart when read aloud.
+#[cfg(feature = "signing")]
+const SAS_WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "adobe", "agent", "album", "alley", "amber", "anchor", "angle",
+    "apple", "apron", "arena", "armor", "arrow", "atlas", "attic", "audio", "autumn", "bacon",
+    "badge", "bagel", "baker", "bamboo", "banana", "banjo", "barn", "basil", "basket", "beach",
+    "beacon", "bean", "beaver", "bell", "berry", "bicycle", "bison", "blade", "blanket", "bloom",
+    "bonus", "boot", "bottle", "boulder", "bowl", "brain", "branch", "bread", "brick", "bridge",
+    "broom", "bubble", "bucket", "buffalo", "cabin", "cactus", "camel", "camera", "candle",
+    "canoe", "canyon", "carbon", "carpet", "carrot", "castle", "cattle", "cedar", "cello",
+    "cement", "chalk", "cherry", "chess", "chimney", "cider", "circle", "citrus", "clock", "cloud",
+    "clover", "cobalt", "cocoa", "coconut", "comet", "copper", "coral", "cotton", "cougar", "crab",
+    "crane", "crayon", "cricket", "crystal", "cupcake", "daisy", "dancer", "delta", "desert",
+    "diamond", "dinner", "dolphin", "domino", "donkey", "dragon", "drum", "eagle", "echo",
+    "eclipse", "elbow", "elephant", "ember", "engine", "falcon", "feather", "fennel", "ferry",
+    "fiddle", "finch", "flame", "flute", "forest", "fossil", "fountain", "fox", "galaxy", "garden",
+    "garlic", "gecko", "ginger", "giraffe", "glacier", "globe", "goblet", "gopher", "granite",
+    "grape", "guitar", "hammer", "harbor", "harvest", "hazel", "helmet", "heron", "hickory",
+    "honey", "horizon", "hornet", "husky", "igloo", "iguana", "island", "ivory", "jacket",
+    "jaguar", "jasmine", "jelly", "jigsaw", "jungle", "kayak", "kernel", "kettle", "kiwi", "koala",
+    "ladder", "lagoon", "lantern", "laser", "lemon", "leopard", "lettuce", "lily", "lion",
+    "lizard", "lobster", "locket", "lotus", "magnet", "mango", "maple", "marble", "meadow",
+    "melon", "meteor", "mint", "mirror", "mitten", "monkey", "moose", "mosaic", "muffin", "nectar",
+    "needle", "noodle", "nutmeg", "oasis", "ocean", "octopus", "olive", "onion", "orange",
+    "orchid", "otter", "oyster", "paddle", "panda", "panther", "parrot", "peach", "peanut",
+    "pebble", "pelican", "pepper", "piano", "pickle", "pigeon", "pillow", "pine", "pirate",
+    "planet", "plum", "pocket", "pony", "potato", "prism", "pumpkin", "puzzle", "quartz", "quill",
+    "rabbit", "raccoon", "radish", "rainbow", "raven", "ribbon", "river", "robin", "rocket",
+    "saddle", "salmon", "sandal", "scarf", "shadow", "shark", "shell", "silver", "sparrow",
+    "spider", "sponge", "squash", "statue", "stone", "sugar", "summit", "sunset", "swan", "tiger",
+    "tomato",
+];
+
 /// Secret keys of a participant.
 #[derive(Clone)]
 #[allow(missing_debug_implementations)]
@@ -128,13 +358,17 @@ impl Secret {
             return Err(io::Error::other("unsupported serialization version number"));
         }
 
-        let mut signing_key = [0u8; SIGNING_KEY_LEN];
-        reader.read_exact(&mut signing_key)?;
-        let signing_key = SigningKey::from_bytes(&signing_key);
+        let mut signing_key_bytes = [0u8; SIGNING_KEY_LEN];
+        reader.read_exact(&mut signing_key_bytes)?;
+        let signing_key = SigningKey::from_bytes(&signing_key_bytes);
+        #[cfg(feature = "zeroize")]
+        signing_key_bytes.zeroize();
 
-        let mut decryption_key = [0u8; DECRYPTION_KEY_LEN];
-        reader.read_exact(&mut decryption_key)?;
-        let decryption_key = StaticSecret::from(decryption_key);
+        let mut decryption_key_bytes = [0u8; DECRYPTION_KEY_LEN];
+        reader.read_exact(&mut decryption_key_bytes)?;
+        let decryption_key = StaticSecret::from(decryption_key_bytes);
+        #[cfg(feature = "zeroize")]
+        decryption_key_bytes.zeroize();
 
         Ok(Self {
             signing_key,
@@ -149,7 +383,7 @@ impl Secret {
 }
 
 /// Public identity of a participant.
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 pub struct Identity {
     verification_key: VerifyingKey,
     encryption_key: PublicKey,
@@ -210,6 +444,41 @@ impl Identity {
             .expect("deriving an identifier with FROST-RedJubJub should never fail")
     }
 
+    /// Returns an RGB color derived deterministically from this identity, meant to help users
+    /// tell participants apart in user interfaces.
+    ///
+    /// The hue is derived from a hash of the identity and covers the whole color wheel, while the
+    /// saturation and lightness are kept in a narrow range, so that all colors are equally
+    /// readable and distinct identities are likely to get visibly different colors. Like the
+    /// [`session_sas`], colors are not unique and must not be used to authenticate participants.
+    ///
+    /// Only available with the `std` feature, as the conversion relies on floating point
+    /// functions that are not part of `core`.
+    #[cfg(all(feature = "signing", feature = "std"))]
+    #[must_use]
+    pub fn accent_color(&self) -> (u8, u8, u8) {
+        let hash = blake3::derive_key(ACCENT_COLOR_CONTEXT, &self.serialize());
+        let hue = u16::from_le_bytes([hash[0], hash[1]]) as f32 * 360.0 / 65536.0;
+        let saturation = 0.55 + hash[2] as f32 / 255.0 * 0.2;
+        let lightness = 0.45 + hash[3] as f32 / 255.0 * 0.1;
+
+        // Standard HSL to RGB conversion
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let sector = hue / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        let channel = |value: f32| ((value + m) * 255.0).round() as u8;
+        (channel(r), channel(g), channel(b))
+    }
+
     #[must_use]
     pub fn serialize(&self) -> IdentitySerialization {
         let mut s = [0u8; IDENTITY_LEN];
@@ -269,14 +538,68 @@ impl PartialOrd<Self> for Identity {
     }
 }
 
+/// Equality of identities is evaluated in constant time. Ordering (used to sort participants) is
+/// not.
+impl ConstantTimeEq for Identity {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.serialize()[..].ct_eq(&other.serialize()[..])
+    }
+}
+
+impl PartialEq for Identity {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Identity {}
+
 // Need to implement `Hash` manually because `Signature` does not implement it
-#[allow(clippy::derived_hash_with_manual_eq)]
 impl Hash for Identity {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.serialize().hash(state);
     }
 }
 
+/// Equality of secrets is evaluated in constant time.
+impl ConstantTimeEq for Secret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // Compare the keys in place, to avoid leaving copies of them around
+        self.signing_key
+            .as_bytes()
+            .ct_eq(other.signing_key.as_bytes())
+            & self
+                .decryption_key
+                .as_bytes()
+                .ct_eq(other.decryption_key.as_bytes())
+    }
+}
+
+impl PartialEq for Secret {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Secret {}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for Secret {
+    fn zeroize(&mut self) {
+        // Replacing the signing key drops the old one, which wipes it
+        self.signing_key = SigningKey::from_bytes(&[0u8; SIGNING_KEY_LEN]);
+        self.decryption_key.zeroize();
+        self.identity = OnceCell::new();
+    }
+}
+
+/// With the `zeroize` feature, the signing key and the decryption key of a [`Secret`] wipe
+/// themselves when dropped, so no explicit `Drop` implementation is needed.
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Secret {}
+
 impl From<Secret> for Identity {
     #[inline]
     fn from(secret: Secret) -> Identity {
@@ -306,6 +629,12 @@ impl<'a> From<&'a Identity> for frost::Identifier {
 }
 
 impl core::fmt::Display for Identity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl core::fmt::LowerHex for Identity {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for byte in self.serialize() {
             write!(f, "{:02x}", byte)?;
@@ -314,10 +643,137 @@ impl core::fmt::Display for Identity {
     }
 }
 
+impl core::fmt::UpperHex for Identity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for byte in self.serialize() {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when parsing an [`Identity`] from a hex string fails.
+#[derive(Debug)]
+pub enum ParseIdentityError {
+    /// The string does not have the length of a hex-encoded identity.
+    InvalidLength { expected: usize, actual: usize },
+    /// The string contains a character that is not a hex digit.
+    InvalidCharacter { index: usize, character: char },
+    /// The string is valid hex, but does not encode a valid identity.
+    InvalidIdentity(io::Error),
+}
+
+impl core::fmt::Display for ParseIdentityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidLength { expected, actual } => write!(
+                f,
+                "invalid identity length: expected {} hex characters, got {}",
+                expected, actual
+            ),
+            Self::InvalidCharacter { index, character } => write!(
+                f,
+                "invalid character {:?} at index {} in identity",
+                character, index
+            ),
+            Self::InvalidIdentity(_) => write!(f, "invalid identity"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseIdentityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidIdentity(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Parses an identity from its hex encoding (either lowercase or uppercase), as produced by the
+/// `Display`, `LowerHex`, and `UpperHex` implementations. The identity is validated like
+/// [`Identity::deserialize_from`] does.
+impl core::str::FromStr for Identity {
+    type Err = ParseIdentityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != IDENTITY_LEN * 2 {
+            return Err(ParseIdentityError::InvalidLength {
+                expected: IDENTITY_LEN * 2,
+                actual: s.len(),
+            });
+        }
+
+        let mut bytes = [0u8; IDENTITY_LEN];
+        let mut digits = s.char_indices().map(|(index, character)| {
+            character
+                .to_digit(16)
+                .map(|digit| digit as u8)
+                .ok_or(ParseIdentityError::InvalidCharacter { index, character })
+        });
+        for byte in bytes.iter_mut() {
+            // The length check above ensures that there are enough characters: multi-byte
+            // characters, which could make the string shorter, are rejected before it runs out
+            let high = digits.next().expect("string too short")?;
+            let low = digits.next().expect("string too short")?;
+            *byte = (high << 4) | low;
+        }
+
+        Self::deserialize_from(&bytes[..]).map_err(ParseIdentityError::InvalidIdentity)
+    }
+}
+
+/// Serializes the secret keys as a byte string. Note that the serialized form contains the
+/// secret keys in plain text.
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Secret {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.serialize())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Secret {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::serde::deserialize_bytes_with(deserializer, |reader| Self::deserialize_from(reader))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Identity {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.serialize())
+    }
+}
+
+/// Deserializes an identity from a byte string, verifying its signature like
+/// [`Identity::deserialize_from`] does.
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Identity {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::serde::deserialize_bytes_with(deserializer, |reader| Self::deserialize_from(reader))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "signing")]
+    use super::elect_coordinator;
+    #[cfg(feature = "std")]
+    use super::find_partial_collisions;
+    #[cfg(feature = "std")]
+    use super::index_in;
+    #[cfg(all(feature = "signing", feature = "std"))]
+    use super::session_sas;
+    #[cfg(feature = "std")]
+    use super::verify_batch;
     use super::Identity;
+    #[cfg(feature = "std")]
+    use super::ParseIdentityError;
     use super::Secret;
+    #[cfg(feature = "std")]
+    use super::IDENTITY_LEN;
     use ed25519_dalek::Signature;
     use hex_literal::hex;
     use rand::thread_rng;
@@ -388,6 +844,43 @@ mod tests {
         assert_eq!(serialization, deserialized.serialize());
     }
 
+    #[test]
+    #[cfg(all(feature = "signing", feature = "std"))]
+    fn accent_color() {
+        let id1 = Identity::deserialize_from(
+            &hex!(
+                "
+                723c692fa94b563faa41cf99b5759f8d0dc2606c90df30e8ae1813b1068d5cddd60
+                0c88978733e6399d65a1b5c5c025030a3505737c4207075e11fce168ba97246a545
+                92e5217ea54b1bc68efcdf15e8bf227d098d84d403994cebe774a119cfcbcec2eee
+                a73d09306e395019d9b891e8c9d6acaa1751cacde66f4010f748c9e0d
+            "
+            )[..],
+        )
+        .expect("deserialization failed");
+        let id2 = Secret::deserialize_from(
+            &hex!(
+                "
+                72adb5f2526cba20e38cb7e44466503d41823e2da95c68faa1904ada33d8660edeb
+                29e91c501aca3bb7791fe37ae562ff50fcf88bb3af9a64671ace8f6095bb169
+            "
+            )[..],
+        )
+        .expect("deserialization failed")
+        .to_identity();
+
+        assert_eq!(id1.accent_color(), id1.clone().accent_color());
+        assert_eq!(id2.accent_color(), id2.clone().accent_color());
+
+        let (r1, g1, b1) = id1.accent_color();
+        let (r2, g2, b2) = id2.accent_color();
+        let distance = [(r1, r2), (g1, g2), (b1, b2)]
+            .iter()
+            .map(|&(a, b)| (a as i32 - b as i32).pow(2))
+            .sum::<i32>();
+        assert!(distance >= 100 * 100, "colors are too close");
+    }
+
     #[test]
     fn identity_integrity_check() {
         let secret = Secret::random(thread_rng());
@@ -449,4 +942,312 @@ mod tests {
         id.verify_data(data, &fake_signature)
             .expect_err("verification failed");
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn index_in_is_order_independent() {
+        let mut rng = thread_rng();
+        let mut participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+        let outsider = Secret::random(&mut rng).to_identity();
+
+        let indexes = participants
+            .iter()
+            .map(|identity| index_in(identity, &participants))
+            .collect::<Vec<_>>();
+        let mut sorted_indexes = indexes.clone();
+        sorted_indexes.sort();
+        assert_eq!(sorted_indexes, [Some(1), Some(2), Some(3)]);
+
+        participants.reverse();
+        let with_duplicate = [
+            participants[0].clone(),
+            participants[1].clone(),
+            participants[0].clone(),
+            participants[2].clone(),
+        ];
+        participants.reverse();
+
+        for (identity, index) in participants.iter().zip(indexes) {
+            assert_eq!(index_in(identity, &with_duplicate), index);
+        }
+        assert_eq!(index_in(&outsider, &participants), None);
+    }
+
+    #[test]
+    #[cfg(feature = "signing")]
+    fn elect_coordinator_is_deterministic() {
+        let mut rng = thread_rng();
+        let mut participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let coordinator = elect_coordinator(&participants, 1234);
+        assert!(participants.contains(&coordinator));
+        assert_eq!(elect_coordinator(&participants, 1234), coordinator);
+
+        participants.reverse();
+        assert_eq!(elect_coordinator(&participants, 1234), coordinator);
+
+        assert!(
+            (0..64).any(|checksum| elect_coordinator(&participants, checksum) != coordinator),
+            "election never changes with the session checksum"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn verify_batch_reports_failing_items() {
+        let secrets = [
+            Secret::random(thread_rng()),
+            Secret::random(thread_rng()),
+            Secret::random(thread_rng()),
+        ];
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+        let signatures = secrets
+            .iter()
+            .map(|secret| secret.sign(b"some data"))
+            .collect::<Vec<_>>();
+
+        let items = identities
+            .iter()
+            .zip(signatures.iter())
+            .map(|(identity, signature)| (identity, &b"some data"[..], signature))
+            .collect::<Vec<_>>();
+        assert_eq!(verify_batch(&items), Ok(()));
+        assert_eq!(verify_batch(&[]), Ok(()));
+
+        let items = [
+            (&identities[0], &b"some data"[..], &signatures[0]),
+            (&identities[1], &b"other data"[..], &signatures[1]),
+            (&identities[2], &b"some data"[..], &signatures[2]),
+            (&identities[0], &b"some data"[..], &signatures[2]),
+        ];
+        assert_eq!(verify_batch(&items), Err(vec![1, 3]));
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::super::Identity;
+        use super::super::Secret;
+        use super::super::Signature;
+        use rand::thread_rng;
+
+        #[test]
+        fn serde_round_trip() {
+            let secret = Secret::random(thread_rng());
+            let identity = secret.to_identity();
+            let signature = secret.sign(b"some data");
+
+            let json = serde_json::to_string(&secret).expect("json serialization failed");
+            let deserialized: Secret =
+                serde_json::from_str(&json).expect("json deserialization failed");
+            assert_eq!(deserialized.serialize(), secret.serialize());
+
+            let json = serde_json::to_string(&identity).expect("json serialization failed");
+            let deserialized: Identity =
+                serde_json::from_str(&json).expect("json deserialization failed");
+            assert_eq!(deserialized, identity);
+
+            let json = serde_json::to_string(&signature).expect("json serialization failed");
+            let deserialized: Signature =
+                serde_json::from_str(&json).expect("json deserialization failed");
+            assert_eq!(deserialized, signature);
+
+            let bytes = bincode::serialize(&secret).expect("bincode serialization failed");
+            let deserialized: Secret =
+                bincode::deserialize(&bytes).expect("bincode deserialization failed");
+            assert_eq!(deserialized.serialize(), secret.serialize());
+
+            let bytes = bincode::serialize(&identity).expect("bincode serialization failed");
+            let deserialized: Identity =
+                bincode::deserialize(&bytes).expect("bincode deserialization failed");
+            assert_eq!(deserialized, identity);
+
+            let bytes = bincode::serialize(&signature).expect("bincode serialization failed");
+            let deserialized: Signature =
+                bincode::deserialize(&bytes).expect("bincode deserialization failed");
+            assert_eq!(deserialized, signature);
+        }
+
+        #[test]
+        fn serde_rejects_tampered_identity() {
+            let mut serialized = Secret::random(thread_rng()).to_identity().serialize();
+            let last = serialized.len() - 1;
+            serialized[last] ^= 0xff;
+
+            let json =
+                serde_json::to_string(&serialized.to_vec()).expect("json serialization failed");
+            serde_json::from_str::<Identity>(&json)
+                .expect_err("deserialization of a tampered identity should have failed");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn constant_time_equality() {
+        use subtle::ConstantTimeEq;
+
+        let secret = Secret::random(thread_rng());
+        let other_secret = Secret::random(thread_rng());
+        let secret_copy = Secret::deserialize_from(&secret.serialize()[..])
+            .expect("secret deserialization failed");
+
+        assert!(bool::from(secret.ct_eq(&secret_copy)));
+        assert!(!bool::from(secret.ct_eq(&other_secret)));
+        assert!(secret == secret_copy);
+        assert!(secret != other_secret);
+
+        let identity = secret.to_identity();
+        let other_identity = other_secret.to_identity();
+        let identity_copy = Identity::deserialize_from(&identity.serialize()[..])
+            .expect("identity deserialization failed");
+
+        assert!(bool::from(identity.ct_eq(&identity_copy)));
+        assert!(!bool::from(identity.ct_eq(&other_identity)));
+        assert_eq!(identity, identity_copy);
+        assert_ne!(identity, other_identity);
+
+        // Sorting and deduplication rely on `Ord` and `PartialEq` agreeing
+        let mut identities = vec![
+            other_identity.clone(),
+            identity.clone(),
+            identity_copy,
+            other_identity.clone(),
+        ];
+        identities.sort_unstable();
+        identities.dedup();
+        assert_eq!(identities.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn partial_collisions() {
+        let secret1 = Secret::random(thread_rng());
+        let secret2 = Secret::random(thread_rng());
+        let secret3 = Secret::random(thread_rng());
+
+        // Same signing key as `secret1`, but the decryption key of `secret2`
+        let mut serialized = secret1.serialize();
+        let decryption_key_offset = serialized.len() - 32;
+        serialized[decryption_key_offset..]
+            .copy_from_slice(&secret2.serialize()[decryption_key_offset..]);
+        let mixed_secret =
+            Secret::deserialize_from(&serialized[..]).expect("secret deserialization failed");
+
+        let identities = [
+            secret1.to_identity(),
+            secret2.to_identity(),
+            secret3.to_identity(),
+            mixed_secret.to_identity(),
+            secret3.to_identity(),
+        ];
+
+        // Full duplicates (2, 4) are not partial collisions
+        assert_eq!(find_partial_collisions(&identities), [(0, 3), (1, 3)]);
+        assert!(find_partial_collisions(&identities[..3]).is_empty());
+    }
+
+    #[cfg(feature = "zeroize")]
+    mod zeroize {
+        use super::super::Secret;
+        use rand::thread_rng;
+        use zeroize::Zeroize;
+
+        #[test]
+        fn zeroize() {
+            let mut secret = Secret::random(thread_rng());
+            let copy = secret.clone();
+            secret.zeroize();
+
+            assert_eq!(secret.signing_key().as_bytes(), &[0u8; 32]);
+            assert_eq!(secret.decryption_key().as_bytes(), &[0u8; 32]);
+            assert_ne!(copy.signing_key().as_bytes(), &[0u8; 32]);
+            assert_ne!(copy.decryption_key().as_bytes(), &[0u8; 32]);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hex_round_trip() {
+        let identity = Secret::random(thread_rng()).to_identity();
+
+        let lower = identity.to_string();
+        assert_eq!(lower, format!("{:x}", identity));
+        assert_eq!(lower.len(), IDENTITY_LEN * 2);
+        assert_eq!(lower.parse::<Identity>().expect("parsing failed"), identity);
+
+        let upper = format!("{:X}", identity);
+        assert_eq!(upper, lower.to_uppercase());
+        assert_eq!(upper.parse::<Identity>().expect("parsing failed"), identity);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hex_parse_errors() {
+        let identity = Secret::random(thread_rng()).to_identity().to_string();
+
+        match identity[1..].parse::<Identity>() {
+            Err(ParseIdentityError::InvalidLength { expected, actual }) => {
+                assert_eq!(expected, IDENTITY_LEN * 2);
+                assert_eq!(actual, IDENTITY_LEN * 2 - 1);
+            }
+            _ => panic!("string with invalid length should have been rejected"),
+        }
+
+        let invalid_character = format!("{}g", &identity[1..]);
+        match invalid_character.parse::<Identity>() {
+            Err(ParseIdentityError::InvalidCharacter { index, character }) => {
+                assert_eq!(index, IDENTITY_LEN * 2 - 1);
+                assert_eq!(character, 'g');
+            }
+            _ => panic!("string with invalid character should have been rejected"),
+        }
+
+        let multi_byte_character = format!("{}\u{e9}", &identity[2..]);
+        match multi_byte_character.parse::<Identity>() {
+            Err(ParseIdentityError::InvalidCharacter { character, .. }) => {
+                assert_eq!(character, '\u{e9}')
+            }
+            _ => panic!("string with multi-byte character should have been rejected"),
+        }
+
+        // Valid hex, but invalid signature
+        let tampered = format!("{}00", &identity[..identity.len() - 2]);
+        let tampered = if tampered == identity {
+            format!("{}01", &identity[..identity.len() - 2])
+        } else {
+            tampered
+        };
+        match tampered.parse::<Identity>() {
+            Err(ParseIdentityError::InvalidIdentity(_)) => (),
+            _ => panic!("invalid identity should have been rejected"),
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "signing", feature = "std"))]
+    fn session_sas_consistency() {
+        let identities = [
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let reversed = identities.iter().rev().collect::<Vec<_>>();
+
+        let sas = session_sas(&identities, 1234);
+        assert_eq!(sas.split(' ').count(), super::SAS_WORD_COUNT);
+        assert_eq!(sas, session_sas(&reversed, 1234));
+
+        assert_ne!(sas, session_sas(&identities[..2], 1234));
+        assert_ne!(sas, session_sas(&identities, 1235));
+    }
 }
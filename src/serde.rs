@@ -96,6 +96,56 @@ pub(crate) fn read_variable_length_bytes<R: io::Read>(mut reader: R) -> io::Resu
     Ok(bytes)
 }
 
+#[cfg(feature = "serde")]
+struct BytesVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: ::serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: ::serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: ::serde::de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Deserializes a byte string from `deserializer` and decodes it with `f`, failing if `f` does
+/// not consume all the bytes.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_bytes_with<'de, D, T, F>(deserializer: D, f: F) -> Result<T, D::Error>
+where
+    D: ::serde::Deserializer<'de>,
+    F: FnOnce(&mut &[u8]) -> io::Result<T>,
+{
+    use ::serde::de::Error;
+
+    let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+    let mut reader = &bytes[..];
+    let value = f(&mut reader).map_err(D::Error::custom)?;
+    if !reader.is_empty() {
+        return Err(D::Error::custom("trailing bytes after serialized value"));
+    }
+    Ok(value)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,481 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Compact descriptors to share the parameters of a signing session, for example through a QR
+//! code.
+
+use crate::checksum::Checksum;
+use crate::checksum::CHECKSUM_LEN;
+#[cfg(feature = "dkg")]
+use crate::dkg::round3::PublicKeyPackage;
+use crate::error::Error;
+#[cfg(feature = "dkg")]
+use crate::frost::SigningPackage;
+#[cfg(feature = "dkg")]
+use crate::keys::group_verifying_key_bytes;
+use crate::participant::Identity;
+use crate::signing_commitment::input_checksum;
+use crate::signing_commitment::recover_participants;
+use std::borrow::Borrow;
+use std::io;
+
+const SIGNERS_IDENTITIES: u8 = 0;
+const SIGNERS_ROSTER_CHECKSUM: u8 = 1;
+
+#[cfg(feature = "dkg")]
+pub const GROUP_ADDRESS_LEN: usize = 32;
+
+/// The signers of a session, as described by a [`Descriptor`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Signers {
+    /// The full identities of the signers, sorted and without duplicates.
+    Identities(Vec<Identity>),
+    /// The [`input_checksum`] of the transaction hash and the signers. The signers can be
+    /// recovered from a roster shared ahead of time, see [`Descriptor::signers`].
+    RosterChecksum(Checksum),
+}
+
+/// Parameters of a signing session: the transaction hash, the signers, and the threshold.
+///
+/// The serialization uses single-byte length prefixes to keep descriptors small enough to fit in a
+/// QR code. When all the parties already share a roster of the possible signers, the signers can be
+/// replaced by a checksum (see [`Descriptor::with_roster_checksum`]), so that the size of the
+/// descriptor does not depend on the number of signers.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Descriptor {
+    transaction_hash: Vec<u8>,
+    min_signers: u16,
+    signers: Signers,
+}
+
+impl Descriptor {
+    /// Creates a descriptor that lists the full identities of `signers`.
+    pub fn new<I>(transaction_hash: &[u8], signers: &[I], min_signers: u16) -> Result<Self, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        let mut signers = signers
+            .iter()
+            .map(|signer| signer.borrow().clone())
+            .collect::<Vec<_>>();
+        signers.sort_unstable();
+        signers.dedup();
+
+        if signers.len() > u8::MAX as usize {
+            return Err(Error::InvalidInput(format!(
+                "too many signers: at most {} are allowed",
+                u8::MAX
+            )));
+        }
+
+        Self::build(transaction_hash, min_signers, Signers::Identities(signers))
+    }
+
+    /// Creates a descriptor that replaces `signers` with their checksum. Recipients of the
+    /// descriptor need a roster that contains `signers` to recover them.
+    pub fn with_roster_checksum<I>(
+        transaction_hash: &[u8],
+        signers: &[I],
+        min_signers: u16,
+    ) -> Result<Self, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        let checksum = input_checksum(transaction_hash, signers);
+        Self::build(
+            transaction_hash,
+            min_signers,
+            Signers::RosterChecksum(checksum),
+        )
+    }
+
+    fn build(transaction_hash: &[u8], min_signers: u16, signers: Signers) -> Result<Self, Error> {
+        if transaction_hash.len() > u8::MAX as usize {
+            return Err(Error::InvalidInput(format!(
+                "transaction hash too long: at most {} bytes are allowed",
+                u8::MAX
+            )));
+        }
+
+        Ok(Self {
+            transaction_hash: transaction_hash.to_vec(),
+            min_signers,
+            signers,
+        })
+    }
+
+    #[must_use]
+    pub fn transaction_hash(&self) -> &[u8] {
+        &self.transaction_hash
+    }
+
+    #[must_use]
+    pub fn min_signers(&self) -> u16 {
+        self.min_signers
+    }
+
+    #[must_use]
+    pub fn raw_signers(&self) -> &Signers {
+        &self.signers
+    }
+
+    /// Returns the sorted identities of the signers of the session.
+    ///
+    /// If the descriptor only contains the checksum of the signers, they are recovered from
+    /// `roster` using [`recover_participants`]. Returns `None` if no subset of `roster` matches the
+    /// checksum (or if `roster` is too large to be searched).
+    #[must_use]
+    pub fn signers<I>(&self, roster: &[I]) -> Option<Vec<Identity>>
+    where
+        I: Borrow<Identity>,
+    {
+        match &self.signers {
+            Signers::Identities(identities) => Some(identities.clone()),
+            Signers::RosterChecksum(checksum) => recover_participants(
+                *checksum,
+                &self.transaction_hash,
+                roster,
+                self.min_signers as usize,
+                roster.len(),
+            ),
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.serialize_into(&mut bytes)
+            .expect("serialization failed");
+        bytes
+    }
+
+    pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        let kind = match self.signers {
+            Signers::Identities(_) => SIGNERS_IDENTITIES,
+            Signers::RosterChecksum(_) => SIGNERS_ROSTER_CHECKSUM,
+        };
+        writer.write_all(&[kind])?;
+        writer.write_all(&self.min_signers.to_le_bytes())?;
+        writer.write_all(&[self.transaction_hash.len() as u8])?;
+        writer.write_all(&self.transaction_hash)?;
+
+        match &self.signers {
+            Signers::Identities(identities) => {
+                writer.write_all(&[identities.len() as u8])?;
+                for identity in identities {
+                    identity.serialize_into(&mut writer)?;
+                }
+            }
+            Signers::RosterChecksum(checksum) => writer.write_all(&checksum.to_le_bytes())?,
+        }
+
+        Ok(())
+    }
+
+    pub fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut kind = [0u8; 1];
+        reader.read_exact(&mut kind)?;
+
+        let mut min_signers = [0u8; 2];
+        reader.read_exact(&mut min_signers)?;
+        let min_signers = u16::from_le_bytes(min_signers);
+
+        let mut transaction_hash_len = [0u8; 1];
+        reader.read_exact(&mut transaction_hash_len)?;
+        let mut transaction_hash = vec![0u8; transaction_hash_len[0] as usize];
+        reader.read_exact(&mut transaction_hash)?;
+
+        let signers = match kind[0] {
+            SIGNERS_IDENTITIES => {
+                let mut count = [0u8; 1];
+                reader.read_exact(&mut count)?;
+                let identities = (0..count[0])
+                    .map(|_| Identity::deserialize_from(&mut reader))
+                    .collect::<io::Result<Vec<_>>>()?;
+                if !identities.windows(2).all(|ids| ids[0] < ids[1]) {
+                    return Err(io::Error::other(
+                        "signers must be sorted and must not contain duplicates",
+                    ));
+                }
+                Signers::Identities(identities)
+            }
+            SIGNERS_ROSTER_CHECKSUM => {
+                let mut checksum = [0u8; CHECKSUM_LEN];
+                reader.read_exact(&mut checksum)?;
+                Signers::RosterChecksum(u64::from_le_bytes(checksum))
+            }
+            _ => return Err(io::Error::other("unknown signers encoding")),
+        };
+
+        Ok(Self {
+            transaction_hash,
+            min_signers,
+            signers,
+        })
+    }
+}
+
+/// The parameters of a signing request that a wallet shows to the user before approving it: the
+/// transaction hash, the number of participants and the threshold, and the group address.
+///
+/// The group address is the group verifying key, encoded as returned by
+/// [`group_verifying_key_bytes`]. The serialization is canonical: two payloads with the same fields
+/// always serialize to the same bytes.
+#[cfg(feature = "dkg")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ApprovalPayload {
+    transaction_hash: Vec<u8>,
+    participants: u16,
+    min_signers: u16,
+    group_address: [u8; GROUP_ADDRESS_LEN],
+}
+
+#[cfg(feature = "dkg")]
+impl ApprovalPayload {
+    /// Assembles the payload for `signing_package`, which must be a signing request for the group
+    /// described by `public_key_package`.
+    pub fn new(
+        signing_package: &SigningPackage,
+        public_key_package: &PublicKeyPackage,
+    ) -> Result<Self, Error> {
+        let transaction_hash = signing_package.message();
+        if transaction_hash.len() > u8::MAX as usize {
+            return Err(Error::InvalidInput(format!(
+                "transaction hash too long: at most {} bytes are allowed",
+                u8::MAX
+            )));
+        }
+
+        let verifying_shares = public_key_package
+            .frost_public_key_package()
+            .verifying_shares();
+        let signing_commitments = signing_package.signing_commitments();
+        if let Some(identifier) = signing_commitments
+            .keys()
+            .find(|identifier| !verifying_shares.contains_key(identifier))
+        {
+            return Err(Error::InvalidInput(format!(
+                "participant {:?} is not a member of the group",
+                identifier
+            )));
+        }
+
+        let participants = u16::try_from(signing_commitments.len())
+            .map_err(|_| Error::InvalidInput("too many participants".to_string()))?;
+        let min_signers = public_key_package.min_signers();
+        if participants < min_signers {
+            return Err(Error::InvalidInput(format!(
+                "not enough participants: {} participants, {} required",
+                participants, min_signers
+            )));
+        }
+
+        Ok(Self {
+            transaction_hash: transaction_hash.to_vec(),
+            participants,
+            min_signers,
+            group_address: group_verifying_key_bytes(public_key_package),
+        })
+    }
+
+    #[must_use]
+    pub fn transaction_hash(&self) -> &[u8] {
+        &self.transaction_hash
+    }
+
+    /// Returns the number of participants taking part in the signing session.
+    #[must_use]
+    pub fn participants(&self) -> u16 {
+        self.participants
+    }
+
+    #[must_use]
+    pub fn min_signers(&self) -> u16 {
+        self.min_signers
+    }
+
+    #[must_use]
+    pub fn group_address(&self) -> &[u8; GROUP_ADDRESS_LEN] {
+        &self.group_address
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.serialize_into(&mut bytes)
+            .expect("serialization failed");
+        bytes
+    }
+
+    pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.group_address)?;
+        writer.write_all(&self.participants.to_le_bytes())?;
+        writer.write_all(&self.min_signers.to_le_bytes())?;
+        writer.write_all(&[self.transaction_hash.len() as u8])?;
+        writer.write_all(&self.transaction_hash)?;
+        Ok(())
+    }
+
+    pub fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut group_address = [0u8; GROUP_ADDRESS_LEN];
+        reader.read_exact(&mut group_address)?;
+
+        let mut participants = [0u8; 2];
+        reader.read_exact(&mut participants)?;
+        let participants = u16::from_le_bytes(participants);
+
+        let mut min_signers = [0u8; 2];
+        reader.read_exact(&mut min_signers)?;
+        let min_signers = u16::from_le_bytes(min_signers);
+
+        if participants < min_signers {
+            return Err(io::Error::other(
+                "the number of participants must not be lower than the threshold",
+            ));
+        }
+
+        let mut transaction_hash_len = [0u8; 1];
+        reader.read_exact(&mut transaction_hash_len)?;
+        let mut transaction_hash = vec![0u8; transaction_hash_len[0] as usize];
+        reader.read_exact(&mut transaction_hash)?;
+
+        Ok(Self {
+            transaction_hash,
+            participants,
+            min_signers,
+            group_address,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "dkg")]
+    use crate::frost::frost::keys::IdentifierList;
+    #[cfg(feature = "dkg")]
+    use crate::frost::keys::split;
+    #[cfg(feature = "dkg")]
+    use crate::frost::SigningKey;
+    use crate::participant::Secret;
+    #[cfg(feature = "dkg")]
+    use crate::signing_commitment::SigningCommitment;
+    use rand::thread_rng;
+    #[cfg(feature = "dkg")]
+    use std::collections::BTreeMap;
+
+    /// Binary capacity of the largest QR code (version 40) with the lowest error correction level.
+    const QR_CODE_CAPACITY: usize = 2953;
+
+    #[test]
+    fn serialization_roundtrip() {
+        let transaction_hash = [0xab; 32];
+        let roster = (0..5)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+        let signers = &roster[1..4];
+
+        let descriptor =
+            Descriptor::new(&transaction_hash, signers, 2).expect("descriptor creation failed");
+        let deserialized = Descriptor::deserialize_from(&descriptor.serialize()[..])
+            .expect("deserialization failed");
+        assert_eq!(deserialized, descriptor);
+
+        let compact = Descriptor::with_roster_checksum(&transaction_hash, signers, 2)
+            .expect("descriptor creation failed");
+        let deserialized =
+            Descriptor::deserialize_from(&compact.serialize()[..]).expect("deserialization failed");
+        assert_eq!(deserialized, compact);
+
+        let mut expected_signers = signers.to_vec();
+        expected_signers.sort_unstable();
+        assert_eq!(descriptor.signers(&roster), Some(expected_signers.clone()));
+        assert_eq!(deserialized.signers(&roster), Some(expected_signers));
+        assert_eq!(deserialized.signers(&roster[..3]), None);
+    }
+
+    #[test]
+    fn fits_in_qr_code() {
+        let transaction_hash = [0xab; 32];
+        let signers = (0..16)
+            .map(|_| Secret::random(thread_rng()).to_identity())
+            .collect::<Vec<_>>();
+
+        let descriptor =
+            Descriptor::new(&transaction_hash, &signers, 10).expect("descriptor creation failed");
+        assert!(descriptor.serialize().len() <= QR_CODE_CAPACITY);
+
+        let compact = Descriptor::with_roster_checksum(&transaction_hash, &signers, 10)
+            .expect("descriptor creation failed");
+        assert_eq!(compact.serialize().len(), 1 + 2 + 1 + 32 + CHECKSUM_LEN);
+    }
+
+    #[test]
+    #[cfg(feature = "dkg")]
+    fn approval_payload_roundtrip() {
+        let mut rng = thread_rng();
+        let transaction_hash = [0xab; 32];
+        let secrets = (0..3)
+            .map(|_| Secret::random(thread_rng()))
+            .collect::<Vec<_>>();
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+        let identifiers = identities
+            .iter()
+            .map(|identity| identity.to_frost_identifier())
+            .collect::<Vec<_>>();
+
+        let (secret_shares, frost_public_key_package) = split(
+            &SigningKey::new(&mut rng),
+            3,
+            2,
+            IdentifierList::Custom(&identifiers),
+            &mut rng,
+        )
+        .expect("signing key split failed");
+        let public_key_package =
+            PublicKeyPackage::from_frost(frost_public_key_package, identities.clone(), 2);
+
+        let commitments = secrets[..2]
+            .iter()
+            .map(|secret| {
+                let identifier = secret.to_identity().to_frost_identifier();
+                let commitment = SigningCommitment::try_from_secrets(
+                    secret,
+                    secret_shares[&identifier].signing_share(),
+                    &transaction_hash,
+                    &identities[..2],
+                )
+                .expect("commitment generation failed");
+                (identifier, *commitment.raw_commitments())
+            })
+            .collect::<BTreeMap<_, _>>();
+        let signing_package = SigningPackage::new(commitments, &transaction_hash);
+
+        let payload = ApprovalPayload::new(&signing_package, &public_key_package)
+            .expect("payload creation failed");
+        assert_eq!(payload.transaction_hash(), &transaction_hash);
+        assert_eq!(payload.participants(), 2);
+        assert_eq!(payload.min_signers(), 2);
+        assert_eq!(
+            payload.group_address(),
+            &group_verifying_key_bytes(&public_key_package)
+        );
+
+        let serialization = payload.serialize();
+        assert_eq!(serialization.len(), GROUP_ADDRESS_LEN + 2 + 2 + 1 + 32);
+        let deserialized =
+            ApprovalPayload::deserialize_from(&serialization[..]).expect("deserialization failed");
+        assert_eq!(deserialized, payload);
+        assert_eq!(deserialized.serialize(), serialization);
+
+        let stricter_public_key_package = PublicKeyPackage::from_frost(
+            public_key_package.frost_public_key_package().clone(),
+            identities,
+            3,
+        );
+        ApprovalPayload::new(&signing_package, &stricter_public_key_package)
+            .expect_err("payload creation should fail below the threshold");
+    }
+}
@@ -2,11 +2,27 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use std::io;
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
 
 use reddsa::frost::redjubjub::round2::SignatureShare as FrostSignatureShare;
 
+use crate::error::Error;
+use crate::frost;
+use crate::frost::keys::KeyPackage;
+use crate::frost::keys::PublicKeyPackage as FrostPublicKeyPackage;
+use crate::frost::keys::VerifyingShare;
+use crate::frost::Identifier;
+use crate::frost::RandomizedParams;
+use crate::frost::SigningPackage;
+use crate::identifiers::IdentifierMap;
+use crate::identifiers::MappingError;
+use crate::io;
 use crate::participant::{Identity, IDENTITY_LEN};
+use crate::signing_commitment::SigningCommitment;
 
 const FROST_SIGNATURE_SHARE_LEN: usize = 32;
 pub const SIGNATURE_SHARE_SERIALIZATION_LEN: usize = IDENTITY_LEN + FROST_SIGNATURE_SHARE_LEN;
@@ -65,10 +81,635 @@ impl SignatureShare {
     }
 }
 
-#[cfg(test)]
+/// Verifies a single FROST signature share against the commitments in `signing_package` and the
+/// verifying share of `identifier` in `public_key_package`, rerandomized with `randomized_params`.
+fn verify_frost_signature_share(
+    identifier: Identifier,
+    frost_signature_share: &FrostSignatureShare,
+    signing_package: &SigningPackage,
+    public_key_package: &FrostPublicKeyPackage,
+    randomized_params: &RandomizedParams,
+) -> Result<(), frost::Error> {
+    let verifying_share = public_key_package
+        .verifying_shares()
+        .get(&identifier)
+        .ok_or(frost::Error::UnknownIdentifier)?;
+    let signing_commitments = signing_package
+        .signing_commitments()
+        .get(&identifier)
+        .ok_or(frost::Error::MissingCommitment)?;
+
+    // Each signer adds the randomizer to its signing share, so the share has to be checked
+    // against the verifying share offset by the randomizer element
+    let randomized_verifying_share =
+        VerifyingShare::new(verifying_share.to_element() + *randomized_params.randomizer_element());
+    let randomized_verifying_key = randomized_params.randomized_verifying_key();
+
+    let binding_factor_list =
+        frost::frost::compute_binding_factor_list(signing_package, randomized_verifying_key, &[]);
+    let binding_factor = binding_factor_list
+        .get(&identifier)
+        .ok_or(frost::Error::UnknownIdentifier)?;
+    let group_commitment =
+        frost::frost::compute_group_commitment(signing_package, &binding_factor_list)?;
+    let challenge = frost::frost::challenge(
+        &group_commitment.to_element(),
+        randomized_verifying_key,
+        signing_package.message(),
+    );
+    let lambda_i = frost::frost::derive_interpolating_value(&identifier, signing_package)?;
+
+    frost_signature_share.verify(
+        identifier,
+        &signing_commitments.to_group_commitment_share(binding_factor),
+        &randomized_verifying_share,
+        lambda_i,
+        &challenge,
+    )
+}
+
+/// Verifies `signature_share` against the commitments of its signer in `signing_package` and the
+/// verifying share of its signer in `public_key_package`, rerandomized with `randomized_params`.
+///
+/// This allows telling which signer is at fault when an aggregated signature is invalid. The
+/// binding factor of the signer is recomputed from the message and all the commitments in
+/// `signing_package`, so a share computed with any other binding factor (for example, because the
+/// signer used a different view of the signing package) is rejected as well.
+pub fn verify_share(
+    signature_share: &SignatureShare,
+    signing_package: &SigningPackage,
+    public_key_package: &FrostPublicKeyPackage,
+    randomized_params: &RandomizedParams,
+) -> Result<(), Error> {
+    verify_frost_signature_share(
+        signature_share.identity().to_frost_identifier(),
+        signature_share.frost_signature_share(),
+        signing_package,
+        public_key_package,
+        randomized_params,
+    )
+    .map_err(Error::FrostError)
+}
+
+/// Verifies each of the `signature_shares` with [`verify_share`], and returns the identities of the
+/// signers that produced an invalid share, in the same order as `signature_shares`.
+#[must_use]
+pub fn identify_invalid_shares(
+    signature_shares: &[SignatureShare],
+    signing_package: &SigningPackage,
+    public_key_package: &FrostPublicKeyPackage,
+    randomized_params: &RandomizedParams,
+) -> Vec<Identity> {
+    signature_shares
+        .iter()
+        .filter(|signature_share| {
+            verify_share(
+                signature_share,
+                signing_package,
+                public_key_package,
+                randomized_params,
+            )
+            .is_err()
+        })
+        .map(|signature_share| signature_share.identity().clone())
+        .collect()
+}
+
+/// Error returned by [`ShareCollector::add`] when a signature share is rejected.
+#[derive(Clone, Debug)]
+pub enum BadShare {
+    /// A signature share from this identity was already collected.
+    Duplicate(Identity),
+    /// The signature share from this identity did not verify.
+    Invalid(Identity, frost::Error),
+}
+
+impl BadShare {
+    #[must_use]
+    pub fn identity(&self) -> &Identity {
+        match self {
+            Self::Duplicate(identity) => identity,
+            Self::Invalid(identity, _) => identity,
+        }
+    }
+}
+
+/// Collects [`SignatureShare`] structs for a signing operation, verifying each of them as soon as
+/// it is added. This allows a coordinator to detect and abort on a misbehaving signer early,
+/// rather than finding out only when aggregating the final signature.
+#[derive(Clone, Debug)]
+pub struct ShareCollector<'a> {
+    signing_package: &'a SigningPackage,
+    public_key_package: &'a FrostPublicKeyPackage,
+    randomized_params: &'a RandomizedParams,
+    frost_signature_shares: BTreeMap<Identifier, FrostSignatureShare>,
+}
+
+impl<'a> ShareCollector<'a> {
+    #[must_use]
+    pub fn new(
+        signing_package: &'a SigningPackage,
+        public_key_package: &'a FrostPublicKeyPackage,
+        randomized_params: &'a RandomizedParams,
+    ) -> Self {
+        Self {
+            signing_package,
+            public_key_package,
+            randomized_params,
+            frost_signature_shares: BTreeMap::new(),
+        }
+    }
+
+    /// Verifies `signature_share` and, if valid, adds it to the collected shares.
+    pub fn add(&mut self, signature_share: &SignatureShare) -> Result<(), BadShare> {
+        let identifier = signature_share.identity().to_frost_identifier();
+        if self.frost_signature_shares.contains_key(&identifier) {
+            return Err(BadShare::Duplicate(signature_share.identity().clone()));
+        }
+
+        verify_frost_signature_share(
+            identifier,
+            signature_share.frost_signature_share(),
+            self.signing_package,
+            self.public_key_package,
+            self.randomized_params,
+        )
+        .map_err(|e| BadShare::Invalid(signature_share.identity().clone(), e))?;
+
+        self.frost_signature_shares
+            .insert(identifier, *signature_share.frost_signature_share());
+        Ok(())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frost_signature_shares.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frost_signature_shares.is_empty()
+    }
+
+    /// Aggregates the collected signature shares into the final signature, which is valid under the
+    /// randomized verifying key.
+    pub fn finish(self) -> Result<frost::Signature, frost::Error> {
+        frost::aggregate(
+            self.signing_package,
+            &self.frost_signature_shares,
+            self.public_key_package,
+            self.randomized_params,
+        )
+    }
+}
+
+/// Aggregates the `signature_shares` of all the signers in `signing_package` into the final group
+/// signature, which is valid under the verifying key randomized with `randomized_params`.
+///
+/// Returns [`Error::MissingSignatureShare`] if a share is missing for any of the signers, and
+/// [`Error::DuplicateSignatureShare`] if more than one share is provided for the same signer. A
+/// share provided for a participant that is not a signer is rejected as invalid input.
+pub fn aggregate(
+    signing_package: &SigningPackage,
+    signature_shares: &[SignatureShare],
+    public_key_package: &FrostPublicKeyPackage,
+    randomized_params: &RandomizedParams,
+) -> Result<frost::Signature, Error> {
+    let mut identifiers = IdentifierMap::new();
+    let mut frost_signature_shares = BTreeMap::new();
+    for signature_share in signature_shares {
+        let identifier =
+            identifiers
+                .insert(signature_share.identity())
+                .map_err(|err| match err {
+                    MappingError::Duplicate(identity) => Error::DuplicateSignatureShare(identity),
+                    MappingError::Collision(identities) => {
+                        Error::IdentifierCollision { identities }
+                    }
+                })?;
+        if !signing_package
+            .signing_commitments()
+            .contains_key(&identifier)
+        {
+            return Err(Error::InvalidInput(format!(
+                "signature share provided for identity {}, which is not a signer",
+                signature_share.identity()
+            )));
+        }
+        frost_signature_shares.insert(identifier, *signature_share.frost_signature_share());
+    }
+
+    if let Some(identifier) = signing_package
+        .signing_commitments()
+        .keys()
+        .find(|identifier| !frost_signature_shares.contains_key(identifier))
+    {
+        return Err(Error::MissingSignatureShare(*identifier));
+    }
+
+    frost::aggregate(
+        signing_package,
+        &frost_signature_shares,
+        public_key_package,
+        randomized_params,
+    )
+    .map_err(Error::FrostError)
+}
+
+/// Verifies that `signature` is a valid signature of `message` by the group, under the verifying
+/// key randomized with `randomized_params`.
+pub fn verify_group_signature(
+    message: &[u8],
+    signature: &frost::Signature,
+    randomized_params: &RandomizedParams,
+) -> Result<(), Error> {
+    randomized_params
+        .randomized_verifying_key()
+        .verify(message, signature)
+        .map_err(Error::FrostError)
+}
+
+/// Verifies that the signers in `signing_package` are exactly the `expected_signers`.
+///
+/// Participants should use this before signing, to refuse signing packages that were built for
+/// a different set of signers than the one agreed for the session.
+pub fn verify_package_signers(
+    signing_package: &SigningPackage,
+    expected_signers: &[Identity],
+) -> Result<(), Error> {
+    let expected = expected_signers
+        .iter()
+        .map(|identity| (identity.to_frost_identifier(), identity))
+        .collect::<BTreeMap<_, _>>();
+    let actual = signing_package
+        .signing_commitments()
+        .keys()
+        .collect::<BTreeSet<_>>();
+
+    let missing = expected
+        .iter()
+        .filter(|(identifier, _)| !actual.contains(identifier))
+        .map(|(_, identity)| identity.to_string())
+        .collect::<Vec<_>>();
+    let unexpected = actual
+        .iter()
+        .filter(|identifier| !expected.contains_key(identifier))
+        .count();
+
+    if missing.is_empty() && unexpected == 0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "signing package does not match the expected signers: missing signers [{}], {} unexpected signers",
+            missing.join(", "),
+            unexpected
+        )))
+    }
+}
+
+/// Verifies that `signing_package` contains enough commitments to meet the threshold of
+/// `key_package`.
+///
+/// Participants should use this before signing: a signing package with fewer than
+/// `min_signers` commitments means that the session was misconfigured, and the signature shares
+/// produced for it could never be aggregated into a valid signature.
+pub fn verify_threshold_match(
+    key_package: &KeyPackage,
+    signing_package: &SigningPackage,
+) -> Result<(), Error> {
+    let min_signers = *key_package.min_signers() as usize;
+    let signers = signing_package.signing_commitments().len();
+
+    if signers < min_signers {
+        return Err(Error::InvalidInput(format!(
+            "signing package has {} signers, but the key package requires at least {}",
+            signers, min_signers
+        )));
+    }
+
+    Ok(())
+}
+
+/// Verifies that `my_commitment` appears unchanged in `signing_package`.
+///
+/// Participants should use this before producing their signature share, to detect a coordinator
+/// that omitted or substituted their commitment when building the signing package.
+pub fn prove_commitment_inclusion(
+    signing_package: &SigningPackage,
+    my_commitment: &SigningCommitment,
+) -> Result<(), Error> {
+    let identity = my_commitment.identity();
+    match signing_package
+        .signing_commitments()
+        .get(&identity.to_frost_identifier())
+    {
+        Some(commitments) if commitments == my_commitment.raw_commitments() => Ok(()),
+        Some(_) => Err(Error::InvalidInput(format!(
+            "signing package contains an altered commitment for identity {}",
+            identity
+        ))),
+        None => Err(Error::InvalidInput(format!(
+            "signing package does not contain a commitment for identity {}",
+            identity
+        ))),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
+    use crate::frost::frost::keys::IdentifierList;
+    use crate::frost::keys::split;
+    use crate::frost::Field;
+    use crate::frost::JubjubScalarField;
+    use crate::frost::Randomizer;
+    use crate::frost::SigningKey;
+    use crate::nonces::deterministic_signing_nonces;
+    use crate::participant::Secret;
     use hex_literal::hex;
+    use rand::thread_rng;
+
+    struct SigningSetup {
+        secrets: Vec<Secret>,
+        key_packages: Vec<KeyPackage>,
+        public_key_package: FrostPublicKeyPackage,
+        signing_package: SigningPackage,
+        randomized_params: RandomizedParams,
+    }
+
+    /// Runs a trusted dealer key generation for 3 participants, and builds a signing package for
+    /// all of them.
+    fn signing_setup(transaction_hash: &[u8]) -> SigningSetup {
+        let mut rng = thread_rng();
+
+        let secrets = (0..3).map(|_| Secret::random(&mut rng)).collect::<Vec<_>>();
+        let identifiers = secrets
+            .iter()
+            .map(|secret| secret.to_identity().to_frost_identifier())
+            .collect::<Vec<_>>();
+
+        let signing_key = SigningKey::new(&mut rng);
+        let (secret_shares, public_key_package) = split(
+            &signing_key,
+            3,
+            2,
+            IdentifierList::Custom(&identifiers),
+            &mut rng,
+        )
+        .expect("signing key split failed");
+
+        let key_packages = identifiers
+            .iter()
+            .map(|identifier| {
+                KeyPackage::try_from(secret_shares[identifier].clone())
+                    .expect("key package creation failed")
+            })
+            .collect::<Vec<_>>();
+
+        signing_setup_with_keys(secrets, key_packages, public_key_package, transaction_hash)
+    }
+
+    /// Builds a signing package for all the owners of `secrets`, using the given keys, and picks a
+    /// random randomizer for the signing operation.
+    fn signing_setup_with_keys(
+        secrets: Vec<Secret>,
+        key_packages: Vec<KeyPackage>,
+        public_key_package: FrostPublicKeyPackage,
+        transaction_hash: &[u8],
+    ) -> SigningSetup {
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let commitments = secrets
+            .iter()
+            .zip(key_packages.iter())
+            .map(|(secret, key_package)| {
+                let commitment = SigningCommitment::try_from_secrets(
+                    secret,
+                    key_package.signing_share(),
+                    transaction_hash,
+                    &identities,
+                )
+                .expect("commitment generation failed");
+                (
+                    commitment.identity().to_frost_identifier(),
+                    *commitment.raw_commitments(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        let signing_package = SigningPackage::new(commitments, transaction_hash);
+
+        let randomized_params = random_randomized_params(&public_key_package);
+
+        SigningSetup {
+            secrets,
+            key_packages,
+            public_key_package,
+            signing_package,
+            randomized_params,
+        }
+    }
+
+    fn random_randomized_params(public_key_package: &FrostPublicKeyPackage) -> RandomizedParams {
+        let randomizer = Randomizer::deserialize(&JubjubScalarField::serialize(
+            &JubjubScalarField::random(&mut thread_rng()),
+        ))
+        .expect("randomizer creation failed");
+        RandomizedParams::from_randomizer(public_key_package.verifying_key(), randomizer)
+    }
+
+    fn sign(setup: &SigningSetup, transaction_hash: &[u8]) -> Vec<SignatureShare> {
+        let identities = setup
+            .secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        identities
+            .iter()
+            .zip(setup.key_packages.iter())
+            .map(|(identity, key_package)| {
+                let nonces = deterministic_signing_nonces(
+                    key_package.signing_share(),
+                    transaction_hash,
+                    &identities,
+                );
+                let frost_signature_share = frost::round2::sign(
+                    &setup.signing_package,
+                    &nonces,
+                    key_package,
+                    *setup.randomized_params.randomizer(),
+                )
+                .expect("signing failed");
+                SignatureShare::from_frost(frost_signature_share, identity.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn share_collector() {
+        let transaction_hash = b"transaction hash";
+        let setup = signing_setup(transaction_hash);
+        let signature_shares = sign(&setup, transaction_hash);
+
+        let mut collector = ShareCollector::new(
+            &setup.signing_package,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        );
+        for signature_share in signature_shares.iter() {
+            collector.add(signature_share).expect("adding share failed");
+        }
+        assert_eq!(collector.len(), signature_shares.len());
+
+        let signature = collector.finish().expect("aggregation failed");
+        setup
+            .randomized_params
+            .randomized_verifying_key()
+            .verify(transaction_hash, &signature)
+            .expect("signature verification failed");
+    }
+
+    #[test]
+    fn share_collector_bad_share() {
+        let transaction_hash = b"transaction hash";
+        let setup = signing_setup(transaction_hash);
+        let signature_shares = sign(&setup, transaction_hash);
+
+        // Attribute the share of the first signer to the second signer
+        let bad_share = SignatureShare::from_frost(
+            *signature_shares[0].frost_signature_share(),
+            signature_shares[1].identity().clone(),
+        );
+
+        let mut collector = ShareCollector::new(
+            &setup.signing_package,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        );
+        collector
+            .add(&signature_shares[0])
+            .expect("adding share failed");
+
+        match collector.add(&bad_share) {
+            Err(BadShare::Invalid(identity, _)) => {
+                assert_eq!(&identity, signature_shares[1].identity())
+            }
+            _ => panic!("bad share should have been rejected"),
+        }
+
+        match collector.add(&signature_shares[0]) {
+            Err(BadShare::Duplicate(identity)) => {
+                assert_eq!(&identity, signature_shares[0].identity())
+            }
+            _ => panic!("duplicate share should have been rejected"),
+        }
+
+        assert_eq!(collector.len(), 1);
+    }
+
+    #[test]
+    fn invalid_share_identification() {
+        let transaction_hash = b"transaction hash";
+        let setup = signing_setup(transaction_hash);
+        let mut signature_shares = sign(&setup, transaction_hash);
+
+        for signature_share in signature_shares.iter() {
+            verify_share(
+                signature_share,
+                &setup.signing_package,
+                &setup.public_key_package,
+                &setup.randomized_params,
+            )
+            .expect("valid share was rejected");
+        }
+        assert!(identify_invalid_shares(
+            &signature_shares,
+            &setup.signing_package,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        )
+        .is_empty());
+
+        // Attribute the share of the third signer to the second signer
+        let culprit = signature_shares[1].identity().clone();
+        signature_shares[1] = SignatureShare::from_frost(
+            *signature_shares[2].frost_signature_share(),
+            culprit.clone(),
+        );
+
+        match verify_share(
+            &signature_shares[1],
+            &setup.signing_package,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        ) {
+            Err(Error::FrostError(_)) => (),
+            _ => panic!("invalid share should have been rejected"),
+        }
+        assert_eq!(
+            identify_invalid_shares(
+                &signature_shares,
+                &setup.signing_package,
+                &setup.public_key_package,
+                &setup.randomized_params,
+            ),
+            [culprit]
+        );
+    }
+
+    #[test]
+    fn perturbed_binding_factor() {
+        let transaction_hash = b"transaction hash";
+        let setup = signing_setup(transaction_hash);
+        let signature_shares = sign(&setup, transaction_hash);
+
+        let identities = setup
+            .secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+        let frost_signature_share = signature_shares[0].frost_signature_share();
+
+        verify_share(
+            &signature_shares[0],
+            &setup.signing_package,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        )
+        .expect("valid share was rejected");
+
+        // The share is `hiding + binding * binding_factor + ...`: adding `binding` yields the share
+        // that would have been computed with `binding_factor + 1`
+        let nonces = deterministic_signing_nonces(
+            setup.key_packages[0].signing_share(),
+            transaction_hash,
+            &identities,
+        );
+        let binding = JubjubScalarField::deserialize(&nonces.binding().serialize())
+            .expect("invalid binding nonce");
+        let share = JubjubScalarField::deserialize(&frost_signature_share.serialize())
+            .expect("invalid signature share");
+        let perturbed =
+            FrostSignatureShare::deserialize(JubjubScalarField::serialize(&(share + binding)))
+                .expect("invalid signature share");
+
+        let perturbed = SignatureShare::from_frost(perturbed, identities[0].clone());
+
+        match verify_share(
+            &perturbed,
+            &setup.signing_package,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        ) {
+            Err(Error::FrostError(_)) => (),
+            _ => panic!("share with a perturbed binding factor should have been rejected"),
+        }
+    }
 
     #[test]
     fn deserialization_regression() {
@@ -85,4 +726,201 @@ mod tests {
             SignatureShare::deserialize_from(&serialization[..]).expect("deserialization failed");
         assert_eq!(serialization, deserialized.serialize());
     }
+
+    #[test]
+    fn verify_package_signers_mismatch() {
+        let setup = signing_setup(b"transaction hash");
+        let identities = setup
+            .secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        verify_package_signers(&setup.signing_package, &identities)
+            .expect("matching signers were rejected");
+
+        match verify_package_signers(&setup.signing_package, &identities[..2]) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("unexpected signer should have been reported"),
+        }
+
+        let mut other_identities = identities.clone();
+        other_identities[2] = Secret::random(thread_rng()).to_identity();
+        match verify_package_signers(&setup.signing_package, &other_identities) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("missing signer should have been reported"),
+        }
+    }
+
+    #[test]
+    fn threshold_mismatch() {
+        let transaction_hash = b"transaction hash";
+        let setup = signing_setup(transaction_hash);
+        let key_package = &setup.key_packages[0];
+
+        verify_threshold_match(key_package, &setup.signing_package)
+            .expect("signing package meeting the threshold was rejected");
+
+        let identifier = *key_package.identifier();
+        let commitments = setup
+            .signing_package
+            .signing_commitments()
+            .iter()
+            .filter(|(id, _)| **id == identifier)
+            .map(|(id, commitments)| (*id, *commitments))
+            .collect::<BTreeMap<_, _>>();
+        let small_signing_package = SigningPackage::new(commitments, transaction_hash);
+
+        match verify_threshold_match(key_package, &small_signing_package) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("signing package below the threshold should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn commitment_inclusion() {
+        let transaction_hash = b"transaction hash";
+        let setup = signing_setup(transaction_hash);
+        let identities = setup
+            .secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let my_commitment = SigningCommitment::try_from_secrets(
+            &setup.secrets[0],
+            setup.key_packages[0].signing_share(),
+            transaction_hash,
+            &identities,
+        )
+        .expect("commitment generation failed");
+        prove_commitment_inclusion(&setup.signing_package, &my_commitment)
+            .expect("unaltered commitment was rejected");
+
+        // Replace the commitment of the first signer with the one of the second signer
+        let mut commitments = setup.signing_package.signing_commitments().clone();
+        let substituted = commitments[&identities[1].to_frost_identifier()];
+        commitments.insert(identities[0].to_frost_identifier(), substituted);
+        let altered_signing_package = SigningPackage::new(commitments.clone(), transaction_hash);
+        match prove_commitment_inclusion(&altered_signing_package, &my_commitment) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("altered commitment should have been detected"),
+        }
+
+        commitments.remove(&identities[0].to_frost_identifier());
+        let incomplete_signing_package = SigningPackage::new(commitments, transaction_hash);
+        match prove_commitment_inclusion(&incomplete_signing_package, &my_commitment) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("missing commitment should have been detected"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dkg")]
+    fn dkg_sign_aggregate_verify() {
+        use crate::dkg::round1;
+        use crate::dkg::round2;
+        use crate::dkg::round3;
+
+        let transaction_hash = b"transaction hash";
+        let secrets = (0..3)
+            .map(|_| Secret::random(thread_rng()))
+            .collect::<Vec<_>>();
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        // Key generation
+        let round1_outputs = identities
+            .iter()
+            .map(|identity| {
+                round1::round1(identity, 2, &identities, thread_rng()).expect("round 1 failed")
+            })
+            .collect::<Vec<_>>();
+        let round1_packages = round1_outputs
+            .iter()
+            .map(|(_, package)| package)
+            .collect::<Vec<_>>();
+        let round2_outputs = secrets
+            .iter()
+            .zip(round1_outputs.iter())
+            .map(|(secret, (round1_secret_package, _))| {
+                round2::round2(
+                    secret,
+                    round1_secret_package,
+                    round1_packages.iter().copied(),
+                    thread_rng(),
+                )
+                .expect("round 2 failed")
+            })
+            .collect::<Vec<_>>();
+        let (key_packages, public_key_packages): (Vec<_>, Vec<_>) = secrets
+            .iter()
+            .zip(round2_outputs.iter())
+            .map(|(secret, (round2_secret_package, _))| {
+                let (key_package, public_key_package, _) = round3::round3(
+                    secret,
+                    round2_secret_package,
+                    round1_packages.iter().copied(),
+                    round2_outputs.iter().map(|(_, packages)| packages),
+                )
+                .expect("round 3 failed");
+                (key_package, public_key_package)
+            })
+            .unzip();
+        let public_key_package = public_key_packages[0].frost_public_key_package().clone();
+
+        // Signing
+        let setup =
+            signing_setup_with_keys(secrets, key_packages, public_key_package, transaction_hash);
+        let signature_shares = sign(&setup, transaction_hash);
+
+        let signature = aggregate(
+            &setup.signing_package,
+            &signature_shares,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        )
+        .expect("aggregation failed");
+        verify_group_signature(transaction_hash, &signature, &setup.randomized_params)
+            .expect("signature verification failed");
+        verify_group_signature(b"other transaction", &signature, &setup.randomized_params)
+            .expect_err("signature of a different message should have been rejected");
+        verify_group_signature(
+            transaction_hash,
+            &signature,
+            &random_randomized_params(&setup.public_key_package),
+        )
+        .expect_err("signature under a different randomizer should have been rejected");
+
+        let missing = signature_shares[2].identity().to_frost_identifier();
+        match aggregate(
+            &setup.signing_package,
+            &signature_shares[..2],
+            &setup.public_key_package,
+            &setup.randomized_params,
+        ) {
+            Err(Error::MissingSignatureShare(identifier)) => assert_eq!(identifier, missing),
+            _ => panic!("missing signature share should have been rejected"),
+        }
+
+        let duplicated = [
+            signature_shares[0].clone(),
+            signature_shares[0].clone(),
+            signature_shares[1].clone(),
+            signature_shares[2].clone(),
+        ];
+        match aggregate(
+            &setup.signing_package,
+            &duplicated,
+            &setup.public_key_package,
+            &setup.randomized_params,
+        ) {
+            Err(Error::DuplicateSignatureShare(identity)) => {
+                assert_eq!(&identity, signature_shares[0].identity())
+            }
+            _ => panic!("duplicate signature share should have been rejected"),
+        }
+    }
 }
@@ -2,29 +2,160 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::checksum::check_version;
 use crate::checksum::Checksum;
 use crate::checksum::ChecksumError;
 use crate::checksum::ChecksumHasher;
 use crate::checksum::CHECKSUM_LEN;
+use crate::checksum::CHECKSUM_VERSION;
+use crate::checksum::VERSIONED_CHECKSUM_LEN;
+#[cfg(feature = "dkg")]
+use crate::dkg::round3::PublicKeyPackage;
+use crate::error::Error;
 use crate::frost::keys::SigningShare;
 use crate::frost::round1::NonceCommitment;
 use crate::frost::round1::SigningCommitments;
-use crate::nonces::deterministic_signing_nonces;
+use crate::frost::Identifier;
+use crate::frost::SigningPackage;
+use crate::identifiers::IdentifierMap;
+use crate::identifiers::MappingError;
+use crate::io;
+use crate::nonces::checked_signing_nonces;
+use crate::nonces::ShareProvider;
+use crate::nonces::DEFAULT_NONCES_SALT;
+#[cfg(feature = "std")]
+use crate::participant::verify_batch;
 use crate::participant::Identity;
+use crate::participant::IdentitySerialization;
 use crate::participant::Secret;
 use crate::participant::Signature;
 use crate::participant::SignatureError;
 use crate::participant::IDENTITY_LEN;
-use std::borrow::Borrow;
-use std::hash::Hasher;
-use std::io;
+use crate::serde::read_usize;
+use crate::serde::write_usize;
+#[cfg(feature = "audit")]
+use crate::verification::notify_failure;
+#[cfg(feature = "audit")]
+use crate::verification::FailureKind;
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hasher;
 
 const NONCE_COMMITMENT_LEN: usize = 32;
 pub const AUTHENTICATED_DATA_LEN: usize = IDENTITY_LEN + NONCE_COMMITMENT_LEN * 2 + CHECKSUM_LEN;
-pub const SIGNING_COMMITMENT_LEN: usize = AUTHENTICATED_DATA_LEN + Signature::BYTE_SIZE;
+/// Length of a serialized [`SigningCommitment`] without extension. This is the length of all the
+/// commitments created with [`SigningCommitment::try_from_secrets`]; see
+/// [`SigningCommitment::serialize_extended`] for the commitments that carry an extension.
+///
+/// The checksum version byte is not part of the authenticated data: it only tells how the checksum
+/// was computed, and the checksum itself is authenticated.
+pub const SIGNING_COMMITMENT_LEN: usize = AUTHENTICATED_DATA_LEN + 1 + Signature::BYTE_SIZE;
+pub const SIGNING_COMMITMENT_WITHOUT_IDENTITY_LEN: usize = SIGNING_COMMITMENT_LEN - IDENTITY_LEN;
+/// Length of a [`SigningCommitment`] without extension serialized with
+/// [`SigningCommitment::serialize_no_checksum`]. The checksum version byte is kept, so that the
+/// layout can still tell whether an extension follows.
+pub const SIGNING_COMMITMENT_WITHOUT_CHECKSUM_LEN: usize = SIGNING_COMMITMENT_LEN - CHECKSUM_LEN;
+/// Length of the legacy serialization of a [`SigningCommitment`], where the checksum is not
+/// prefixed by its version. See [`SigningCommitment::deserialize_v0_from`].
+pub const SIGNING_COMMITMENT_V0_LEN: usize = SIGNING_COMMITMENT_LEN - 1;
+
+/// Set in the checksum version byte of a serialized [`SigningCommitment`] when the checksum is
+/// followed by an extension.
+const EXTENDED_LAYOUT: u8 = 0x80;
+/// Length of the fixed part of the extension: the protocol version and the flags.
+const EXTENSION_LEN: usize = 2;
+/// Set in the flags of the extension when the flags are followed by the group hash.
+const FLAG_GROUP_HASH: u8 = 0x01;
+/// Set in the flags of the extension of dry-run commitments.
+const FLAG_DRY_RUN: u8 = 0x02;
+/// Flags of the extension that this version of the crate understands.
+const KNOWN_FLAGS: u8 = FLAG_GROUP_HASH | FLAG_DRY_RUN;
+/// Maximum length of a serialized [`SigningCommitment`], including the extension.
+pub const MAX_SIGNING_COMMITMENT_LEN: usize =
+    SIGNING_COMMITMENT_LEN + EXTENSION_LEN + GROUP_HASH_LEN;
+
+// The serialization format of commitments is fixed: the lengths above are derived from the sizes
+// of types defined by upstream crates, so pin them to catch any upstream change at build time,
+// instead of at runtime through deserialization failures of previously serialized commitments.
+const _: () = assert!(Signature::BYTE_SIZE == 64);
+const _: () = assert!(IDENTITY_LEN == 129);
+const _: () = assert!(
+    core::mem::size_of::<<crate::frost::JubjubGroup as crate::frost::Group>::Serialization>()
+        == NONCE_COMMITMENT_LEN
+);
+const _: () = assert!(SIGNING_COMMITMENT_LEN == 266);
+
+/// Names and sizes of the fields of a serialized [`SigningCommitment`] without extension, in
+/// serialization order.
+static SIGNING_COMMITMENT_LAYOUT: [(&str, usize); 6] = [
+    ("signature", Signature::BYTE_SIZE),
+    ("identity", IDENTITY_LEN),
+    ("hiding", NONCE_COMMITMENT_LEN),
+    ("binding", NONCE_COMMITMENT_LEN),
+    ("checksum_version", 1),
+    ("checksum", CHECKSUM_LEN),
+];
+
+/// Names and sizes of the fields of the extension that follows the checksum of some serialized
+/// [`SigningCommitment`] structs.
+static EXTENSION_LAYOUT: [(&str, usize); 2] = [("protocol_version", 1), ("flags", 1)];
+
+/// Fields of a [`SigningCommitment`] that are serialized in the extension.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct Extension {
+    protocol_version: u8,
+    group_hash: Option<[u8; GROUP_HASH_LEN]>,
+    dry_run: bool,
+}
+
+impl Extension {
+    const DEFAULT: Self = Self {
+        protocol_version: DEFAULT_PROTOCOL_VERSION,
+        group_hash: None,
+        dry_run: false,
+    };
+
+    fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.group_hash.is_some() {
+            flags |= FLAG_GROUP_HASH;
+        }
+        if self.dry_run {
+            flags |= FLAG_DRY_RUN;
+        }
+        flags
+    }
+}
+
+/// Returns the length of an extension with the given `flags`.
+fn extension_len(flags: u8) -> usize {
+    if flags & FLAG_GROUP_HASH != 0 {
+        EXTENSION_LEN + GROUP_HASH_LEN
+    } else {
+        EXTENSION_LEN
+    }
+}
+
+const DRY_RUN_DOMAIN: &[u8] = b"ironfish-frost dry run";
+const DRY_RUN_NONCES_SALT: &[u8] = DRY_RUN_DOMAIN;
+
+/// Protocol version of commitments created through [`SigningCommitment::try_from_secrets`].
+pub const DEFAULT_PROTOCOL_VERSION: u8 = 0;
+
+/// Length of the DKG group hash that commitments can be bound to. See
+/// [`SigningCommitment::from_secrets_for_dkg`].
+pub const GROUP_HASH_LEN: usize = 32;
 
+/// Computes the checksum of the transaction hash and the signers for a signing operation.
+///
+/// The order of `signing_participants` does not influence the output, and duplicates are ignored.
 #[must_use]
-fn input_checksum<I>(transaction_hash: &[u8], signing_participants: &[I]) -> Checksum
+pub fn input_checksum<I>(transaction_hash: &[u8], signing_participants: &[I]) -> Checksum
 where
     I: Borrow<Identity>,
 {
@@ -35,11 +166,141 @@ where
     signing_participants.sort_unstable();
     signing_participants.dedup();
 
+    sorted_input_checksum(transaction_hash, signing_participants)
+}
+
+/// Same as [`input_checksum`], but skips sorting and deduplicating `signing_participants`, which
+/// can be any iterator over identities.
+///
+/// Unlike [`input_checksum`], this does not allocate, so when computing checksums over the same set
+/// of participants multiple times, the set can be sorted once by the caller. The
+/// `input_checksum` benchmark compares the two functions. The caller must ensure that
+/// `signing_participants` is sorted and does not contain duplicates, otherwise the result won't
+/// match [`input_checksum`]. This is checked only in debug builds.
+#[must_use]
+pub fn input_checksum_presorted<I>(transaction_hash: &[u8], signing_participants: I) -> Checksum
+where
+    I: IntoIterator,
+    I::Item: Borrow<Identity>,
+{
+    let signing_participants = signing_participants.into_iter();
+
+    #[cfg(debug_assertions)]
+    let signing_participants = {
+        let mut previous: Option<Identity> = None;
+        signing_participants.inspect(move |id| {
+            let id = id.borrow();
+            assert!(
+                previous.as_ref().map_or(true, |previous| previous < id),
+                "signing_participants must be sorted and must not contain duplicates"
+            );
+            previous = Some(id.clone());
+        })
+    };
+
+    sorted_input_checksum(transaction_hash, signing_participants)
+}
+
+/// Maximum number of subsets that [`recover_participants`] is willing to examine.
+pub const MAX_RECOVERY_SUBSETS: u64 = 1 << 20;
+
+/// Finds the subset of `candidates` that produced `checksum` for `transaction_hash`.
+///
+/// All the subsets of `candidates` with a size between `min_signers` and `max_signers`
+/// (inclusive) are examined. The number of such subsets grows combinatorially with the number of
+/// candidates: for example, there are 184756 subsets of size 10 out of 20 candidates. To bound the
+/// cost of the search, this returns `None` without searching if more than
+/// [`MAX_RECOVERY_SUBSETS`] subsets would need to be examined. Returns `None` also if no subset
+/// matches.
+///
+/// The returned identities are sorted.
+#[must_use]
+pub fn recover_participants<I>(
+    checksum: Checksum,
+    transaction_hash: &[u8],
+    candidates: &[I],
+    min_signers: usize,
+    max_signers: usize,
+) -> Option<Vec<Identity>>
+where
+    I: Borrow<Identity>,
+{
+    let mut candidates = candidates.iter().map(Borrow::borrow).collect::<Vec<_>>();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let n = candidates.len();
+    let max_signers = max_signers.min(n);
+    if min_signers > max_signers {
+        return None;
+    }
+
+    let subsets_count = (min_signers..=max_signers)
+        .map(|k| binomial(n as u64, k as u64))
+        .fold(0u64, u64::saturating_add);
+    if subsets_count > MAX_RECOVERY_SUBSETS {
+        return None;
+    }
+
+    for k in min_signers..=max_signers {
+        // Iterate over all the combinations of `k` indices in lexicographic order
+        let mut indices = (0..k).collect::<Vec<_>>();
+        loop {
+            let subset = indices.iter().map(|&i| candidates[i]);
+            if sorted_input_checksum(transaction_hash, subset.clone()) == checksum {
+                return Some(subset.cloned().collect());
+            }
+
+            let Some(i) = (0..k).rev().find(|&i| indices[i] != i + n - k) else {
+                break;
+            };
+            indices[i] += 1;
+            for j in i + 1..k {
+                indices[j] = indices[j - 1] + 1;
+            }
+        }
+    }
+
+    None
+}
+
+/// Computes the binomial coefficient `n` choose `k`, saturating at `u64::MAX`.
+fn binomial(n: u64, k: u64) -> u64 {
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+        if result > u64::MAX as u128 {
+            return u64::MAX;
+        }
+    }
+    result as u64
+}
+
+/// Checksum used by dry-run commitments. Derived from [`input_checksum`], but in a separate
+/// domain so that it never matches the checksum of a real commitment.
+#[must_use]
+fn dry_run_checksum<I>(transaction_hash: &[u8], signing_participants: &[I]) -> Checksum
+where
+    I: Borrow<Identity>,
+{
+    let mut hasher = ChecksumHasher::new();
+    hasher.write(DRY_RUN_DOMAIN);
+    hasher.write(&input_checksum(transaction_hash, signing_participants).to_le_bytes());
+    hasher.finish()
+}
+
+#[must_use]
+fn sorted_input_checksum<I>(transaction_hash: &[u8], signing_participants: I) -> Checksum
+where
+    I: IntoIterator,
+    I::Item: Borrow<Identity>,
+{
     let mut hasher = ChecksumHasher::new();
     hasher.write(transaction_hash);
 
     for id in signing_participants {
-        hasher.write(&id.serialize());
+        hasher.write(&id.borrow().serialize());
     }
 
     hasher.finish()
@@ -67,6 +328,79 @@ fn authenticated_data(
     data
 }
 
+#[must_use]
+fn versioned_authenticated_data(
+    identity: &Identity,
+    raw_commitments: &SigningCommitments,
+    checksum: Checksum,
+    extension: &Extension,
+) -> Vec<u8> {
+    let mut data = authenticated_data(identity, raw_commitments, checksum).to_vec();
+    // The default protocol version is not part of the authenticated data, so that commitments
+    // generated before protocol versions were introduced stay valid
+    if extension.protocol_version != DEFAULT_PROTOCOL_VERSION {
+        data.push(extension.protocol_version);
+    }
+    // Same for the group hash and the dry-run marker. The length of the data is enough to tell
+    // which of the protocol version, the group hash, and the dry-run marker are present.
+    if let Some(group_hash) = &extension.group_hash {
+        data.extend_from_slice(group_hash);
+    }
+    if extension.dry_run {
+        data.extend_from_slice(DRY_RUN_DOMAIN);
+    }
+    data
+}
+
+/// Byte order used to serialize the checksum of a [`SigningCommitment`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+/// Whether the checksum of a serialized [`SigningCommitment`] is prefixed by its version.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum ChecksumLayout {
+    /// Legacy layout, from before checksums were versioned.
+    V0,
+    /// The checksum is prefixed by [`CHECKSUM_VERSION`].
+    V1,
+}
+
+/// How two [`SigningCommitment`] structs relate to each other. See
+/// [`SigningCommitment::relationship`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CommitmentRelationship {
+    /// The two commitments are exactly the same.
+    Identical,
+    /// The two commitments were generated by the same signer, for the same session, with the same
+    /// nonces, but their signatures are not byte-for-byte identical.
+    ///
+    /// Identities sign with Ed25519, which is deterministic, so an honest signer never produces two
+    /// different signatures for the same commitment: this means that one of the signatures was
+    /// malleated after being produced. Both commitments carry the same nonces, so they can be used
+    /// interchangeably, but the transport that delivered them should not be trusted to preserve
+    /// bytes.
+    RetrySameSession,
+    /// The two commitments were generated by different signers, or for different sessions.
+    DifferentSession,
+    /// The two commitments were generated by the same signer, for the same session, but with
+    /// different nonces. Because nonces are derived deterministically, this can only happen if the
+    /// signer deviated from the protocol.
+    Equivocation,
+}
+
+/// A byte that differs between two serialized [`SigningCommitment`] structs. See
+/// [`SigningCommitment::diff_serialized`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct FieldDiff {
+    /// Name of the field that contains the byte, as listed in [`SigningCommitment::layout`].
+    pub field: &'static str,
+    /// Offset of the byte in the serialization.
+    pub offset: usize,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct SigningCommitment {
     identity: Identity,
@@ -74,8 +408,18 @@ pub struct SigningCommitment {
     /// A checksum of the transaction hash and the signers for a signing operation. Used to quickly
     /// tell if a set of commitments were all generated from the same inputs.
     checksum: Checksum,
-    /// Signature that ensures that `hiding`, `binding`, and `checksum` were generated by the owner
-    /// of `identity`.
+    /// Version of the signing protocol this commitment was generated for. It is covered by
+    /// `signature`, so commitments from different protocol versions cannot be mixed.
+    protocol_version: u8,
+    /// Hash of the DKG group this commitment was generated for, if any. Like `protocol_version`,
+    /// this is covered by `signature`.
+    group_hash: Option<[u8; GROUP_HASH_LEN]>,
+    /// Whether this is a dry-run commitment, created with
+    /// [`SigningCommitment::from_secrets_dry_run`]. Also covered by `signature`, so a dry-run
+    /// commitment cannot be passed off as a real one.
+    dry_run: bool,
+    /// Signature that ensures that `hiding`, `binding`, `checksum`, `protocol_version`,
+    /// `group_hash`, and `dry_run` were generated by the owner of `identity`.
     signature: Signature,
 }
 
@@ -84,12 +428,16 @@ impl SigningCommitment {
         identity: Identity,
         raw_commitments: SigningCommitments,
         checksum: Checksum,
+        extension: Extension,
         signature: Signature,
     ) -> Result<Self, SignatureError> {
         let signing_commitment = Self {
             identity,
             raw_commitments,
             checksum,
+            protocol_version: extension.protocol_version,
+            group_hash: extension.group_hash,
+            dry_run: extension.dry_run,
             signature,
         };
         signing_commitment
@@ -97,7 +445,14 @@ impl SigningCommitment {
             .map(|_| signing_commitment)
     }
 
+    /// Same as [`SigningCommitment::try_from_secrets`], but panics if `secret_share` is rejected.
+    ///
+    /// # Panics
+    ///
+    /// With the `reject-weak-keys` feature, panics if `secret_share` is degenerate (see
+    /// [`is_degenerate_signing_share`](crate::nonces::is_degenerate_signing_share)).
     #[must_use]
+    #[deprecated(note = "use try_from_secrets, which returns an error instead of panicking")]
     pub fn from_secrets<I>(
         participant_secret: &Secret,
         secret_share: &SigningShare,
@@ -107,26 +462,264 @@ impl SigningCommitment {
     where
         I: Borrow<Identity>,
     {
-        let identity = participant_secret.to_identity();
+        Self::try_from_secrets(
+            participant_secret,
+            secret_share,
+            transaction_hash,
+            signing_participants,
+        )
+        .expect("refusing to generate a commitment with a degenerate signing share")
+    }
+
+    /// Creates a commitment for the signing session identified by `transaction_hash` and
+    /// `signing_participants`.
+    ///
+    /// With the `reject-weak-keys` feature, returns an error if `secret_share` is degenerate (see
+    /// [`is_degenerate_signing_share`](crate::nonces::is_degenerate_signing_share)).
+    pub fn try_from_secrets<I>(
+        participant_secret: &Secret,
+        secret_share: &SigningShare,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+    ) -> Result<SigningCommitment, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        Self::from_share(
+            participant_secret,
+            secret_share,
+            transaction_hash,
+            signing_participants,
+            Extension::DEFAULT,
+        )
+    }
+
+    /// Same as [`SigningCommitment::try_from_secrets`], but binds the commitment to the given
+    /// `protocol_version`.
+    pub fn from_secrets_versioned<I>(
+        participant_secret: &Secret,
+        secret_share: &SigningShare,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+        protocol_version: u8,
+    ) -> Result<SigningCommitment, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        Self::from_share(
+            participant_secret,
+            secret_share,
+            transaction_hash,
+            signing_participants,
+            Extension {
+                protocol_version,
+                ..Extension::DEFAULT
+            },
+        )
+    }
+
+    /// Same as [`SigningCommitment::try_from_secrets`], but binds the commitment to the DKG group
+    /// identified by `group_hash` (typically the hash returned by `keys::public_key_package_hash`).
+    ///
+    /// The group hash is serialized along with the commitment, and covered by its signature.
+    /// Receivers must check it, either with [`SigningCommitment::verify_group_hash`] or by
+    /// deserializing with [`SigningCommitment::deserialize_for_dkg_from`], which fails if the
+    /// commitment was generated for a different group. This prevents commitments generated for a
+    /// previous generation of a group from being replayed into a signing session of a new one.
+    pub fn from_secrets_for_dkg<I>(
+        participant_secret: &Secret,
+        secret_share: &SigningShare,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+        group_hash: [u8; GROUP_HASH_LEN],
+    ) -> Result<SigningCommitment, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        Self::from_share(
+            participant_secret,
+            secret_share,
+            transaction_hash,
+            signing_participants,
+            Extension {
+                group_hash: Some(group_hash),
+                ..Extension::DEFAULT
+            },
+        )
+    }
+
+    /// Creates a "dry-run" commitment, for use in session negotiation.
+    ///
+    /// Dry-run commitments prove that the participant is able to produce a commitment for the
+    /// session, without committing to the nonces that would be used for signing: the nonces are
+    /// derived from a separate domain, so abandoning the session does not put the real nonces at
+    /// risk. The checksum is also derived from a separate domain.
+    ///
+    /// Dry-run commitments are explicitly marked as such in their serialization, and the marker
+    /// is covered by their signature (see [`SigningCommitment::is_dry_run`]). They fail
+    /// [`SigningCommitment::verify_checksum`] and are rejected when building a real signing
+    /// package. Use [`SigningCommitment::verify_dry_run_checksum`] to validate them instead.
+    pub fn from_secrets_dry_run<I>(
+        participant_secret: &Secret,
+        secret_share: &SigningShare,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+    ) -> Result<SigningCommitment, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        Self::from_share(
+            participant_secret,
+            secret_share,
+            transaction_hash,
+            signing_participants,
+            Extension {
+                dry_run: true,
+                ..Extension::DEFAULT
+            },
+        )
+    }
+
+    /// Common implementation of the constructors that take a [`SigningShare`]. Nonces are derived
+    /// with [`checked_signing_nonces`], so that degenerate shares are rejected in a single place.
+    fn from_share<I>(
+        participant_secret: &Secret,
+        secret_share: &SigningShare,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+        extension: Extension,
+    ) -> Result<SigningCommitment, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        let (salt, checksum) = if extension.dry_run {
+            (
+                DRY_RUN_NONCES_SALT,
+                dry_run_checksum(transaction_hash, signing_participants),
+            )
+        } else {
+            (
+                DEFAULT_NONCES_SALT,
+                input_checksum(transaction_hash, signing_participants),
+            )
+        };
         let nonces =
-            deterministic_signing_nonces(secret_share, transaction_hash, signing_participants);
-        let raw_commitments = *nonces.commitments();
-        let checksum = input_checksum(transaction_hash, signing_participants);
-        let authenticated_data = authenticated_data(&identity, &raw_commitments, checksum);
+            checked_signing_nonces(secret_share, transaction_hash, signing_participants, salt)?;
+        Ok(Self::from_raw_commitments(
+            participant_secret,
+            *nonces.commitments(),
+            checksum,
+            extension,
+        ))
+    }
+
+    /// Same as [`SigningCommitment::try_from_secrets`], but obtains the nonce commitments from a
+    /// [`ShareProvider`] instead of a plaintext [`SigningShare`]. This allows the signing share to
+    /// never leave the provider (for example, a hardware security module).
+    pub fn from_provider<P, I>(
+        provider: &P,
+        participant_secret: &Secret,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+    ) -> Result<SigningCommitment, P::Error>
+    where
+        P: ShareProvider + ?Sized,
+        I: Borrow<Identity>,
+    {
+        let participants = signing_participants
+            .iter()
+            .map(Borrow::borrow)
+            .collect::<Vec<_>>();
+        let raw_commitments = provider.signing_commitments(transaction_hash, &participants)?;
+        Ok(Self::from_raw_commitments(
+            participant_secret,
+            raw_commitments,
+            input_checksum(transaction_hash, signing_participants),
+            Extension::DEFAULT,
+        ))
+    }
+
+    fn from_raw_commitments(
+        participant_secret: &Secret,
+        raw_commitments: SigningCommitments,
+        checksum: Checksum,
+        extension: Extension,
+    ) -> SigningCommitment {
+        let identity = participant_secret.to_identity();
+        let authenticated_data =
+            versioned_authenticated_data(&identity, &raw_commitments, checksum, &extension);
         let signature = participant_secret.sign(&authenticated_data);
         SigningCommitment {
             identity,
             raw_commitments,
             checksum,
+            protocol_version: extension.protocol_version,
+            group_hash: extension.group_hash,
+            dry_run: extension.dry_run,
             signature,
         }
     }
 
+    /// Same as [`SigningCommitment::try_from_secrets`], but first ensures that all the
+    /// `signing_participants` are members of the group described by `public_key_package`.
+    ///
+    /// Commitments generated for participants outside of the group would never lead to a valid
+    /// signature, because the other signers would derive different nonces and checksums.
+    #[cfg(feature = "dkg")]
+    pub fn from_secrets_for_group<I>(
+        participant_secret: &Secret,
+        secret_share: &SigningShare,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+        public_key_package: &PublicKeyPackage,
+    ) -> Result<SigningCommitment, Error>
+    where
+        I: Borrow<Identity>,
+    {
+        for participant in signing_participants.iter().map(Borrow::borrow) {
+            if !public_key_package.identities().contains(participant) {
+                return Err(Error::InvalidInput(format!(
+                    "signing participant {} is not a member of the group",
+                    participant
+                )));
+            }
+        }
+
+        Self::try_from_secrets(
+            participant_secret,
+            secret_share,
+            transaction_hash,
+            signing_participants,
+        )
+    }
+
     pub fn verify_authenticity(&self) -> Result<(), SignatureError> {
-        let authenticated_data =
-            authenticated_data(&self.identity, &self.raw_commitments, self.checksum);
-        self.identity
-            .verify_data(&authenticated_data, &self.signature)
+        let authenticated_data = versioned_authenticated_data(
+            &self.identity,
+            &self.raw_commitments,
+            self.checksum,
+            &self.extension(),
+        );
+        let result = self
+            .identity
+            .verify_data(&authenticated_data, &self.signature);
+        #[cfg(feature = "audit")]
+        if result.is_err() {
+            notify_failure(FailureKind::Authenticity, &self.identity);
+        }
+        result
+    }
+
+    /// Ensures that this commitment was generated for the `expected` protocol version.
+    pub fn verify_version(&self, expected: u8) -> Result<(), Error> {
+        if self.protocol_version == expected {
+            Ok(())
+        } else {
+            Err(Error::InvalidInput(format!(
+                "expected protocol version {}, got {}",
+                expected, self.protocol_version
+            )))
+        }
     }
 
     pub fn verify_checksum<I>(
@@ -138,9 +731,31 @@ impl SigningCommitment {
         I: Borrow<Identity>,
     {
         let computed_checksum = input_checksum(transaction_hash, signing_participants);
-        if self.checksum == computed_checksum {
+        if !self.dry_run && self.checksum == computed_checksum {
+            Ok(())
+        } else {
+            #[cfg(feature = "audit")]
+            notify_failure(FailureKind::Checksum, &self.identity);
+            Err(ChecksumError::SigningCommitmentError)
+        }
+    }
+
+    /// Verifies the checksum of a commitment created with
+    /// [`SigningCommitment::from_secrets_dry_run`].
+    pub fn verify_dry_run_checksum<I>(
+        &self,
+        transaction_hash: &[u8],
+        signing_participants: &[I],
+    ) -> Result<(), ChecksumError>
+    where
+        I: Borrow<Identity>,
+    {
+        if self.dry_run && self.checksum == dry_run_checksum(transaction_hash, signing_participants)
+        {
             Ok(())
         } else {
+            #[cfg(feature = "audit")]
+            notify_failure(FailureKind::Checksum, &self.identity);
             Err(ChecksumError::SigningCommitmentError)
         }
     }
@@ -165,283 +780,3123 @@ impl SigningCommitment {
         self.checksum
     }
 
-    pub fn serialize(&self) -> [u8; SIGNING_COMMITMENT_LEN] {
-        let mut bytes = [0u8; SIGNING_COMMITMENT_LEN];
-        self.serialize_into(&mut bytes[..])
-            .expect("serialization failed");
-        bytes
+    pub fn protocol_version(&self) -> u8 {
+        self.protocol_version
     }
 
-    pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
-        writer.write_all(&self.signature.to_bytes())?;
-        writer.write_all(&self.identity.serialize())?;
-        writer.write_all(&self.hiding().serialize())?;
-        writer.write_all(&self.binding().serialize())?;
-        writer.write_all(&self.checksum.to_le_bytes())?;
-        Ok(())
+    /// Returns the hash of the DKG group this commitment is bound to, if any. See
+    /// [`SigningCommitment::from_secrets_for_dkg`].
+    pub fn group_hash(&self) -> Option<&[u8; GROUP_HASH_LEN]> {
+        self.group_hash.as_ref()
     }
 
-    pub fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
-        let mut signature_bytes = [0u8; Signature::BYTE_SIZE];
-        reader.read_exact(&mut signature_bytes)?;
-        let signature = Signature::from_bytes(&signature_bytes);
+    /// Returns `true` if this commitment was created with
+    /// [`SigningCommitment::from_secrets_dry_run`], and cannot be used for signing.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
 
-        let identity = Identity::deserialize_from(&mut reader)?;
+    /// Ensures that this commitment is bound to the DKG group identified by `expected`.
+    pub fn verify_group_hash(&self, expected: &[u8; GROUP_HASH_LEN]) -> Result<(), Error> {
+        if self.group_hash.as_ref() == Some(expected) {
+            Ok(())
+        } else {
+            Err(Error::InvalidInput(
+                "commitment was not generated for the expected group".to_string(),
+            ))
+        }
+    }
 
-        let mut hiding = [0u8; 32];
-        reader.read_exact(&mut hiding)?;
-        let hiding = NonceCommitment::deserialize(hiding).map_err(io::Error::other)?;
+    /// Ensures that this commitment is bound to one of the DKG groups identified by
+    /// `allowed_group_hashes`, and returns the hash of the group that matched.
+    ///
+    /// The group hash is part of the serialization, so this can be used to route commitments
+    /// deserialized with [`SigningCommitment::deserialize_from`] to the session of their group.
+    pub fn verify_group_membership(
+        &self,
+        allowed_group_hashes: &[[u8; GROUP_HASH_LEN]],
+    ) -> Result<[u8; GROUP_HASH_LEN], Error> {
+        self.group_hash
+            .filter(|group_hash| allowed_group_hashes.contains(group_hash))
+            .ok_or_else(|| {
+                Error::InvalidInput(
+                    "commitment was not generated for any of the allowed groups".to_string(),
+                )
+            })
+    }
 
-        let mut binding = [0u8; 32];
-        reader.read_exact(&mut binding)?;
-        let binding = NonceCommitment::deserialize(binding).map_err(io::Error::other)?;
+    /// Returns the signature that authenticates this commitment, produced by the owner of
+    /// [`SigningCommitment::identity`].
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
 
-        let raw_commitments = SigningCommitments::new(hiding, binding);
+    /// Returns `true` if this commitment has the same nonce commitments and checksum as `other`,
+    /// ignoring the identity and the signature.
+    ///
+    /// This is mainly useful to debug the deterministic derivation of nonces.
+    #[must_use]
+    pub fn nonce_checksum_eq(&self, other: &Self) -> bool {
+        self.raw_commitments == other.raw_commitments && self.checksum == other.checksum
+    }
 
-        let mut checksum = [0u8; 8];
-        reader.read_exact(&mut checksum)?;
-        let checksum = Checksum::from_le_bytes(checksum);
+    /// Determines how this commitment relates to `other`. This is useful to tell apart legitimate
+    /// retransmissions from commitments that reuse the same session with different nonces.
+    ///
+    /// Both commitments are verified with [`SigningCommitment::verify_authenticity`] first, so
+    /// that forged commitments cannot be used to accuse a signer of equivocation.
+    pub fn relationship(&self, other: &Self) -> Result<CommitmentRelationship, SignatureError> {
+        self.verify_authenticity()?;
+        other.verify_authenticity()?;
+        Ok(if self == other {
+            CommitmentRelationship::Identical
+        } else if self.identity != other.identity
+            || self.checksum != other.checksum
+            || self.protocol_version != other.protocol_version
+            || self.group_hash != other.group_hash
+            || self.dry_run != other.dry_run
+        {
+            CommitmentRelationship::DifferentSession
+        } else if self.raw_commitments == other.raw_commitments {
+            CommitmentRelationship::RetrySameSession
+        } else {
+            CommitmentRelationship::Equivocation
+        })
+    }
 
-        Self::from_raw_parts(identity, raw_commitments, checksum, signature)
-            .map_err(io::Error::other)
+    /// Serializes the inner commitments using the native reddsa FROST format, so that they can be
+    /// consumed by plain FROST implementations.
+    ///
+    /// The output does not include the identity, the checksum or the signature of this
+    /// commitment: the authentication layer provided by this crate is dropped, and receivers
+    /// have no way to verify who produced the commitments.
+    pub fn to_frost_commitments(&self) -> Result<Vec<u8>, Error> {
+        self.raw_commitments.serialize().map_err(Error::FrostError)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::authenticated_data;
-    use super::SigningCommitment;
-    use crate::frost::keys::SigningShare;
-    use crate::participant::Secret;
-    use hex_literal::hex;
-    use rand::thread_rng;
+    /// Returns the names and sizes of the fields of a serialized commitment without extension, in
+    /// the same order as they are written by [`SigningCommitment::serialize_into`].
+    #[must_use]
+    pub fn layout() -> &'static [(&'static str, usize)] {
+        &SIGNING_COMMITMENT_LAYOUT
+    }
 
-    #[test]
-    fn serialization_round_trip() {
-        let mut rng = thread_rng();
+    /// Same as [`SigningCommitment::layout`], but includes the fields of the extension of this
+    /// commitment, if any.
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        let mut fields = Self::layout().to_vec();
+        if self.is_extended() {
+            fields.extend_from_slice(&EXTENSION_LAYOUT);
+        }
+        if self.group_hash.is_some() {
+            fields.push(("group_hash", GROUP_HASH_LEN));
+        }
+        fields
+    }
 
-        let secret = Secret::random(&mut rng);
-        let signing_share = SigningShare::default();
-        let signing_participants = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-        ];
+    /// Returns the bytes that differ between the serializations of this commitment and `other`,
+    /// labeled with the name of the field they belong to, in order of offset. Bytes that are
+    /// present in only one of the two serializations are reported as different.
+    #[must_use]
+    pub fn diff_serialized(&self, other: &Self) -> Vec<FieldDiff> {
+        let serialized = self.serialize_extended();
+        let other_serialized = other.serialize_extended();
+        let fields = if serialized.len() >= other_serialized.len() {
+            self.fields()
+        } else {
+            other.fields()
+        };
 
-        let commitment = SigningCommitment::from_secrets(
-            &secret,
-            &signing_share,
-            b"transaction hash",
-            &signing_participants,
-        );
+        let mut diffs = Vec::new();
+        let mut start = 0;
+        for (field, size) in fields {
+            let end = start + size;
+            diffs.extend(
+                (start..end)
+                    .filter(|&offset| serialized.get(offset) != other_serialized.get(offset))
+                    .map(|offset| FieldDiff { field, offset }),
+            );
+            start = end;
+        }
+        diffs
+    }
 
-        let serialized = commitment.serialize();
+    /// Serializes a commitment without extension into [`SIGNING_COMMITMENT_LEN`] bytes.
+    ///
+    /// Returns an error if the commitment carries an extension (see
+    /// [`SigningCommitment::is_extended`]), which does not fit in the fixed-size layout. Use
+    /// [`SigningCommitment::serialize_extended`] to serialize any commitment.
+    pub fn serialize(&self) -> Result<[u8; SIGNING_COMMITMENT_LEN], Error> {
+        self.ensure_not_extended()?;
+        let mut bytes = [0u8; SIGNING_COMMITMENT_LEN];
+        self.serialize_into(&mut bytes[..])
+            .expect("serialization failed");
+        Ok(bytes)
+    }
 
-        let deserialized =
-            SigningCommitment::deserialize_from(&serialized[..]).expect("deserialization failed");
+    /// Serializes this commitment, including its extension if any. See
+    /// [`SigningCommitment::serialize_into`] for the layout.
+    #[must_use]
+    pub fn serialize_extended(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.serialized_len());
+        self.serialize_into(&mut bytes)
+            .expect("serialization failed");
+        bytes
+    }
 
-        assert_eq!(deserialized, commitment);
+    /// Returns the length of the output of [`SigningCommitment::serialize_extended`].
+    #[must_use]
+    pub fn serialized_len(&self) -> usize {
+        SIGNING_COMMITMENT_LEN + self.extension_len()
     }
 
-    #[test]
-    fn deserialization_regression() {
-        let serialization = hex!(
-            "
-            307be5a2c20495d05966fc12b2cee3ea4d44cb3623f92b0f6a391c626fa7708e835
-            26e886448d5ef376c5d09675aed3e711cd3e0df9f6c607604e6a7371a210e725c3a
-            20a22aebc59d856bfbaa48fde8f8ea6fe48ddd978555932c283e760397f78b4b468
-            2f9b70f8baad6d7752f5e25bcbc6b3453d16d92589da722ad13a7390d0057c6aae8
-            363a50e835b89b44bccdd5889ef5a362fa89d841c96e65b34dbe3adf8f71faa041f
+    /// Serializes this commitment.
+    ///
+    /// Commitments for the [default protocol version](DEFAULT_PROTOCOL_VERSION) that are not bound
+    /// to a DKG group are serialized in [`SIGNING_COMMITMENT_LEN`] bytes, with the fields listed by
+    /// [`SigningCommitment::layout`]. For other commitments, the high bit of the checksum version
+    /// is set, and the checksum is followed by an extension made of the protocol version, a flags
+    /// byte, and the group hash if any, so that the deserialized commitment can be authenticated
+    /// without knowing these values in advance.
+    pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.signature.to_bytes())?;
+        writer.write_all(&self.identity.serialize())?;
+        self.serialize_commitments_into(writer, ByteOrder::LittleEndian)
+    }
+
+    /// Serializes this commitment in the legacy layout, where the checksum is not prefixed by its
+    /// version. Only meant for peers that have not been upgraded yet: the result must be read back
+    /// with [`SigningCommitment::deserialize_v0_from`].
+    ///
+    /// Returns an error if the commitment carries an extension (see
+    /// [`SigningCommitment::is_extended`]), which the legacy layout cannot represent.
+    pub fn serialize_v0(&self) -> Result<[u8; SIGNING_COMMITMENT_V0_LEN], Error> {
+        self.ensure_not_extended()?;
+        let mut bytes = [0u8; SIGNING_COMMITMENT_V0_LEN];
+        self.serialize_v0_into(&mut bytes[..])
+            .expect("serialization failed");
+        Ok(bytes)
+    }
+
+    fn serialize_v0_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.signature.to_bytes())?;
+        writer.write_all(&self.identity.serialize())?;
+        writer.write_all(&self.hiding().serialize())?;
+        writer.write_all(&self.binding().serialize())?;
+        writer.write_all(&self.checksum.to_le_bytes())
+    }
+
+    /// Serializes this commitment without the embedded [`Identity`].
+    ///
+    /// This is meant for channels where the identity of the sender is already known (for example,
+    /// because the transport is authenticated). The result can be turned back into a
+    /// [`SigningCommitment`] using [`SigningCommitment::deserialize_with_identity_from`].
+    ///
+    /// Returns an error if the commitment carries an extension (see
+    /// [`SigningCommitment::is_extended`]). Use
+    /// [`SigningCommitment::serialize_without_identity_into`] to serialize any commitment.
+    pub fn serialize_without_identity(
+        &self,
+    ) -> Result<[u8; SIGNING_COMMITMENT_WITHOUT_IDENTITY_LEN], Error> {
+        self.ensure_not_extended()?;
+        let mut bytes = [0u8; SIGNING_COMMITMENT_WITHOUT_IDENTITY_LEN];
+        self.serialize_without_identity_into(&mut bytes[..])
+            .expect("serialization failed");
+        Ok(bytes)
+    }
+
+    /// Same as [`SigningCommitment::serialize_without_identity`], but also supports commitments
+    /// that carry an extension, which is written after the checksum.
+    pub fn serialize_without_identity_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.signature.to_bytes())?;
+        self.serialize_commitments_into(writer, ByteOrder::LittleEndian)
+    }
+
+    /// Serializes this commitment without the checksum.
+    ///
+    /// This is meant for channels where the session is already bound out-of-band, so that both
+    /// ends know the expected checksum. The result can be turned back into a
+    /// [`SigningCommitment`] using [`SigningCommitment::deserialize_with_checksum_from`].
+    ///
+    /// Returns an error if the commitment carries an extension (see
+    /// [`SigningCommitment::is_extended`]). Use [`SigningCommitment::serialize_no_checksum_into`]
+    /// to serialize any commitment.
+    pub fn serialize_no_checksum(
+        &self,
+    ) -> Result<[u8; SIGNING_COMMITMENT_WITHOUT_CHECKSUM_LEN], Error> {
+        self.ensure_not_extended()?;
+        let mut bytes = [0u8; SIGNING_COMMITMENT_WITHOUT_CHECKSUM_LEN];
+        self.serialize_no_checksum_into(&mut bytes[..])
+            .expect("serialization failed");
+        Ok(bytes)
+    }
+
+    /// Same as [`SigningCommitment::serialize_no_checksum`], but also supports commitments that
+    /// carry an extension. Like [`SigningCommitment::serialize_into`], the extension is written
+    /// after the checksum version byte.
+    pub fn serialize_no_checksum_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.signature.to_bytes())?;
+        writer.write_all(&self.identity.serialize())?;
+        writer.write_all(&self.hiding().serialize())?;
+        writer.write_all(&self.binding().serialize())?;
+        writer.write_all(&[self.layout_byte()])?;
+        self.serialize_extension_into(writer)
+    }
+
+    /// Serializes this commitment in the same way as [`SigningCommitment::serialize`], except that
+    /// the checksum is written in big-endian (network) byte order.
+    ///
+    /// This is a distinct format, meant only for peers that expect big-endian integers: it is not
+    /// compatible with [`SigningCommitment::deserialize_from`], and must be read back with
+    /// [`SigningCommitment::deserialize_be_from`].
+    ///
+    /// Returns an error if the commitment carries an extension (see
+    /// [`SigningCommitment::is_extended`]). Use [`SigningCommitment::serialize_be_into`] to
+    /// serialize any commitment.
+    pub fn serialize_be(&self) -> Result<[u8; SIGNING_COMMITMENT_LEN], Error> {
+        self.ensure_not_extended()?;
+        let mut bytes = [0u8; SIGNING_COMMITMENT_LEN];
+        self.serialize_be_into(&mut bytes[..])
+            .expect("serialization failed");
+        Ok(bytes)
+    }
+
+    /// Same as [`SigningCommitment::serialize_be`], but also supports commitments that carry an
+    /// extension, which is written after the checksum.
+    pub fn serialize_be_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.signature.to_bytes())?;
+        writer.write_all(&self.identity.serialize())?;
+        self.serialize_commitments_into(writer, ByteOrder::BigEndian)
+    }
+
+    fn serialize_commitments_into<W: io::Write>(
+        &self,
+        mut writer: W,
+        byte_order: ByteOrder,
+    ) -> io::Result<()> {
+        writer.write_all(&self.hiding().serialize())?;
+        writer.write_all(&self.binding().serialize())?;
+        writer.write_all(&[self.layout_byte()])?;
+        let checksum = match byte_order {
+            ByteOrder::LittleEndian => self.checksum.to_le_bytes(),
+            ByteOrder::BigEndian => self.checksum.to_be_bytes(),
+        };
+        writer.write_all(&checksum)?;
+        self.serialize_extension_into(writer)
+    }
+
+    /// Returns `true` if the serialization of this commitment carries an extension, because its
+    /// protocol version, group hash, or dry-run marker do not have their default value. Such
+    /// commitments must be serialized with [`SigningCommitment::serialize_extended`] or
+    /// [`SigningCommitment::serialize_into`].
+    #[must_use]
+    pub fn is_extended(&self) -> bool {
+        self.extension() != Extension::DEFAULT
+    }
+
+    fn ensure_not_extended(&self) -> Result<(), Error> {
+        if self.is_extended() {
+            Err(Error::InvalidInput(
+                "commitment carries an extension, which does not fit in a fixed-size layout"
+                    .to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn extension(&self) -> Extension {
+        Extension {
+            protocol_version: self.protocol_version,
+            group_hash: self.group_hash,
+            dry_run: self.dry_run,
+        }
+    }
+
+    /// Returns the length of the extension of this commitment, or 0 if it is not extended.
+    fn extension_len(&self) -> usize {
+        if self.is_extended() {
+            extension_len(self.extension().flags())
+        } else {
+            0
+        }
+    }
+
+    fn layout_byte(&self) -> u8 {
+        if self.is_extended() {
+            CHECKSUM_VERSION | EXTENDED_LAYOUT
+        } else {
+            CHECKSUM_VERSION
+        }
+    }
+
+    fn serialize_extension_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        if self.is_extended() {
+            let extension = self.extension();
+            writer.write_all(&[extension.protocol_version, extension.flags()])?;
+            if let Some(group_hash) = extension.group_hash {
+                writer.write_all(&group_hash)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes a commitment produced by [`SigningCommitment::serialize_into`], whatever its
+    /// protocol version. Use [`SigningCommitment::verify_version`] or
+    /// [`SigningCommitment::deserialize_versioned_from`] to restrict the accepted versions.
+    pub fn deserialize_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let signature = Self::deserialize_signature_from(&mut reader)?;
+        let identity = Identity::deserialize_from(&mut reader)?;
+        Self::deserialize_commitments_from(
+            reader,
+            identity,
+            signature,
+            ByteOrder::LittleEndian,
+            ChecksumLayout::V1,
+        )
+    }
+
+    /// Deserializes a commitment, failing if it was generated for a protocol version other than
+    /// `protocol_version`.
+    pub fn deserialize_versioned_from<R: io::Read>(
+        reader: R,
+        protocol_version: u8,
+    ) -> io::Result<Self> {
+        let commitment = Self::deserialize_from(reader)?;
+        commitment
+            .verify_version(protocol_version)
+            .map_err(io::Error::other)?;
+        Ok(commitment)
+    }
+
+    /// Deserializes a commitment serialized in the legacy layout, where the checksum is not
+    /// prefixed by its version (see [`SigningCommitment::serialize_v0`]). Serializing the result
+    /// with [`SigningCommitment::serialize`] upgrades it to the current layout.
+    pub fn deserialize_v0_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let signature = Self::deserialize_signature_from(&mut reader)?;
+        let identity = Identity::deserialize_from(&mut reader)?;
+        Self::deserialize_commitments_from(
+            reader,
+            identity,
+            signature,
+            ByteOrder::LittleEndian,
+            ChecksumLayout::V0,
+        )
+    }
+
+    /// Deserializes a commitment generated by [`SigningCommitment::from_secrets_for_dkg`], failing
+    /// if the commitment was generated for a different group, or was not bound to any group.
+    pub fn deserialize_for_dkg_from<R: io::Read>(
+        reader: R,
+        group_hash: [u8; GROUP_HASH_LEN],
+    ) -> io::Result<Self> {
+        let commitment = Self::deserialize_from(reader)?;
+        commitment
+            .verify_group_hash(&group_hash)
+            .map_err(io::Error::other)?;
+        Ok(commitment)
+    }
+
+    /// Deserializes a commitment produced by [`SigningCommitment::serialize_without_identity`],
+    /// reattaching the known `identity` of the sender.
+    ///
+    /// The authenticity of the commitment is verified against `identity`, so this fails if the
+    /// commitment was not produced by the owner of `identity`.
+    pub fn deserialize_with_identity_from<R: io::Read>(
+        mut reader: R,
+        identity: Identity,
+    ) -> io::Result<Self> {
+        let signature = Self::deserialize_signature_from(&mut reader)?;
+        Self::deserialize_commitments_from(
+            reader,
+            identity,
+            signature,
+            ByteOrder::LittleEndian,
+            ChecksumLayout::V1,
+        )
+    }
+
+    /// Deserializes a commitment produced by [`SigningCommitment::serialize_be`].
+    pub fn deserialize_be_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let signature = Self::deserialize_signature_from(&mut reader)?;
+        let identity = Identity::deserialize_from(&mut reader)?;
+        Self::deserialize_commitments_from(
+            reader,
+            identity,
+            signature,
+            ByteOrder::BigEndian,
+            ChecksumLayout::V1,
+        )
+    }
+
+    /// Deserializes a commitment produced by [`SigningCommitment::serialize_no_checksum`],
+    /// reattaching the known `checksum` of the session.
+    ///
+    /// The checksum is part of the data signed by the sender, so this fails if `checksum` is not
+    /// the one the commitment was generated with.
+    pub fn deserialize_with_checksum_from<R: io::Read>(
+        mut reader: R,
+        checksum: Checksum,
+    ) -> io::Result<Self> {
+        let signature = Self::deserialize_signature_from(&mut reader)?;
+        let identity = Identity::deserialize_from(&mut reader)?;
+        let raw_commitments = Self::deserialize_raw_commitments_from(&mut reader)?;
+        let extended = Self::deserialize_layout_from(&mut reader)?;
+        let extension = if extended {
+            Self::deserialize_extension_from(&mut reader)?
+        } else {
+            Extension::DEFAULT
+        };
+
+        let commitment =
+            Self::from_raw_parts(identity, raw_commitments, checksum, extension, signature)
+                .map_err(io::Error::other)?;
+
+        if extended && !commitment.is_extended() {
+            return Err(io::Error::other("commitment extension is not needed"));
+        }
+        Ok(commitment)
+    }
+
+    /// Writes a sequence of commitments to `writer`, framed so that they can be read back with
+    /// [`SigningCommitment::read_all`] without knowing the size of each commitment in advance.
+    ///
+    /// The output consists of the number of commitments as a `u32`, followed by each commitment
+    /// serialized with [`SigningCommitment::serialize_into`] and prefixed by its length as a
+    /// `u32`.
+    pub fn write_all<W: io::Write>(
+        mut writer: W,
+        commitments: &[SigningCommitment],
+    ) -> io::Result<()> {
+        write_usize(&mut writer, commitments.len())?;
+        for commitment in commitments {
+            write_usize(&mut writer, commitment.serialized_len())?;
+            commitment.serialize_into(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a sequence of commitments written by [`SigningCommitment::write_all`].
+    ///
+    /// The number of commitments is read from the sequence itself, so `max_count` is only an upper
+    /// bound on it: this prevents a corrupt or malicious count from making the reader wait for, and
+    /// allocate memory for, an arbitrary number of commitments. Fails if the sequence contains more
+    /// than `max_count` commitments, or if any of the commitments is invalid. With the `std`
+    /// feature, a truncated sequence results in an
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error.
+    pub fn read_all<R: io::Read>(mut reader: R, max_count: usize) -> io::Result<Vec<Self>> {
+        let count = read_usize(&mut reader)?;
+        if count > max_count {
+            return Err(io::Error::other(format!(
+                "expected at most {} commitments, got {}",
+                max_count, count
+            )));
+        }
+
+        let mut commitments = Vec::with_capacity(count);
+        let mut element = Vec::with_capacity(MAX_SIGNING_COMMITMENT_LEN);
+        for index in 0..count {
+            let len = read_usize(&mut reader)?;
+            if len > MAX_SIGNING_COMMITMENT_LEN {
+                return Err(io::Error::other(format!(
+                    "invalid commitment at index {}: length {} exceeds the maximum of {}",
+                    index, len, MAX_SIGNING_COMMITMENT_LEN
+                )));
+            }
+            element.resize(len, 0);
+            reader.read_exact(&mut element)?;
+
+            let mut bytes = &element[..];
+            let commitment = Self::deserialize_from(&mut bytes).map_err(|err| {
+                io::Error::other(format!("invalid commitment at index {}: {}", index, err))
+            })?;
+            if !bytes.is_empty() {
+                return Err(io::Error::other(format!(
+                    "invalid commitment at index {}: trailing bytes",
+                    index
+                )));
+            }
+            commitments.push(commitment);
+        }
+        Ok(commitments)
+    }
+
+    fn deserialize_signature_from<R: io::Read>(mut reader: R) -> io::Result<Signature> {
+        let mut signature_bytes = [0u8; Signature::BYTE_SIZE];
+        reader.read_exact(&mut signature_bytes)?;
+        Ok(Signature::from_bytes(&signature_bytes))
+    }
+
+    fn deserialize_commitments_from<R: io::Read>(
+        mut reader: R,
+        identity: Identity,
+        signature: Signature,
+        byte_order: ByteOrder,
+        checksum_layout: ChecksumLayout,
+    ) -> io::Result<Self> {
+        let raw_commitments = Self::deserialize_raw_commitments_from(&mut reader)?;
+
+        let extended = match checksum_layout {
+            ChecksumLayout::V0 => false,
+            ChecksumLayout::V1 => Self::deserialize_layout_from(&mut reader)?,
+        };
+
+        let mut checksum = [0u8; 8];
+        reader.read_exact(&mut checksum)?;
+        let checksum = match byte_order {
+            ByteOrder::LittleEndian => Checksum::from_le_bytes(checksum),
+            ByteOrder::BigEndian => Checksum::from_be_bytes(checksum),
+        };
+
+        let extension = if extended {
+            Self::deserialize_extension_from(&mut reader)?
+        } else {
+            Extension::DEFAULT
+        };
+
+        let commitment =
+            Self::from_raw_parts(identity, raw_commitments, checksum, extension, signature)
+                .map_err(io::Error::other)?;
+
+        // Reject extensions that only carry default values, so that each commitment has a single
+        // serialization
+        if extended && !commitment.is_extended() {
+            return Err(io::Error::other("commitment extension is not needed"));
+        }
+        Ok(commitment)
+    }
+
+    /// Reads the checksum version byte, and returns whether the checksum is followed by an
+    /// extension.
+    fn deserialize_layout_from<R: io::Read>(mut reader: R) -> io::Result<bool> {
+        let mut layout = [0u8; 1];
+        reader.read_exact(&mut layout)?;
+        check_version(layout[0] & !EXTENDED_LAYOUT).map_err(io::Error::other)?;
+        Ok(layout[0] & EXTENDED_LAYOUT != 0)
+    }
+
+    /// Reads the extension written by [`SigningCommitment::serialize_extension_into`].
+    fn deserialize_extension_from<R: io::Read>(mut reader: R) -> io::Result<Extension> {
+        let mut extension = [0u8; EXTENSION_LEN];
+        reader.read_exact(&mut extension)?;
+        let [protocol_version, flags] = extension;
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(io::Error::other("unsupported commitment flags"));
+        }
+
+        let group_hash = if flags & FLAG_GROUP_HASH != 0 {
+            let mut group_hash = [0u8; GROUP_HASH_LEN];
+            reader.read_exact(&mut group_hash)?;
+            Some(group_hash)
+        } else {
+            None
+        };
+
+        Ok(Extension {
+            protocol_version,
+            group_hash,
+            dry_run: flags & FLAG_DRY_RUN != 0,
+        })
+    }
+
+    fn deserialize_raw_commitments_from<R: io::Read>(
+        mut reader: R,
+    ) -> io::Result<SigningCommitments> {
+        let mut hiding = [0u8; 32];
+        reader.read_exact(&mut hiding)?;
+        let hiding = NonceCommitment::deserialize(hiding).map_err(io::Error::other)?;
+
+        let mut binding = [0u8; 32];
+        reader.read_exact(&mut binding)?;
+        let binding = NonceCommitment::deserialize(binding).map_err(io::Error::other)?;
+
+        Ok(SigningCommitments::new(hiding, binding))
+    }
+}
+
+/// Verifies the authenticity of all the `commitments` at once.
+///
+/// This is equivalent to calling [`SigningCommitment::verify_authenticity`] on each commitment,
+/// but uses batch signature verification, which is significantly faster for large sets of
+/// commitments. On failure, returns the index of each commitment that failed verification,
+/// along with the corresponding error.
+#[cfg(feature = "std")]
+pub fn verify_commitments_authenticity(
+    commitments: &[SigningCommitment],
+) -> Result<(), Vec<(usize, SignatureError)>> {
+    let authenticated_data = commitments
+        .iter()
+        .map(|commitment| {
+            versioned_authenticated_data(
+                &commitment.identity,
+                &commitment.raw_commitments,
+                commitment.checksum,
+                &commitment.extension(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let items = commitments
+        .iter()
+        .zip(authenticated_data.iter())
+        .map(|(commitment, data)| (&commitment.identity, &data[..], &commitment.signature))
+        .collect::<Vec<_>>();
+
+    verify_batch(&items).map_err(|failed| {
+        failed
+            .into_iter()
+            .map(|index| {
+                // Commitments rejected only by the batch equation do not have an individual error
+                let error = commitments[index]
+                    .verify_authenticity()
+                    .err()
+                    .unwrap_or_else(SignatureError::new);
+                (index, error)
+            })
+            .collect()
+    })
+}
+
+/// Verifies the authenticity of each of the `commitments`, using at most `max_threads` threads.
+///
+/// Unlike [`verify_commitments_authenticity`], this returns one result per commitment, in the same
+/// order as `commitments`. A `max_threads` of 0 is treated as 1.
+#[cfg(feature = "parallel")]
+pub fn verify_parallel(
+    commitments: &[SigningCommitment],
+    max_threads: usize,
+) -> Vec<Result<(), SignatureError>> {
+    if commitments.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = commitments.len().div_ceil(max_threads.max(1));
+
+    std::thread::scope(|scope| {
+        let handles = commitments
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(SigningCommitment::verify_authenticity)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("verification thread panicked"))
+            .collect()
+    })
+}
+
+/// Serializes the commitment as a byte string with the same layout as
+/// [`SigningCommitment::serialize_extended`].
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for SigningCommitment {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.serialize_extended())
+    }
+}
+
+/// Deserializes a commitment from a byte string, verifying its authenticity like
+/// [`SigningCommitment::deserialize_from`] does.
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for SigningCommitment {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::serde::deserialize_bytes_with(deserializer, |reader| Self::deserialize_from(reader))
+    }
+}
+
+/// Borrowed view over a serialized [`SigningCommitment`].
+///
+/// The accessors read fields directly from the underlying buffer, without decompressing points
+/// or verifying the signature, which makes this suitable for routing or filtering decisions.
+/// Because nothing is validated, values returned by the view must not be trusted until the
+/// commitment is fully deserialized with [`SigningCommitmentRef::into_owned`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SigningCommitmentRef<'a>(&'a [u8]);
+
+impl<'a> SigningCommitmentRef<'a> {
+    const IDENTITY_OFFSET: usize = Signature::BYTE_SIZE;
+    const LAYOUT_OFFSET: usize = SIGNING_COMMITMENT_LEN - VERSIONED_CHECKSUM_LEN;
+    const CHECKSUM_OFFSET: usize = SIGNING_COMMITMENT_LEN - CHECKSUM_LEN;
+    const FLAGS_OFFSET: usize = SIGNING_COMMITMENT_LEN + 1;
+
+    /// Wraps a serialized commitment. Fails if `bytes` does not have the length of a serialized
+    /// [`SigningCommitment`], as indicated by its layout.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, Error> {
+        let expected_len = if bytes
+            .get(Self::LAYOUT_OFFSET)
+            .is_some_and(|layout| layout & EXTENDED_LAYOUT != 0)
+        {
+            SIGNING_COMMITMENT_LEN + extension_len(*bytes.get(Self::FLAGS_OFFSET).unwrap_or(&0))
+        } else {
+            SIGNING_COMMITMENT_LEN
+        };
+        if bytes.len() != expected_len {
+            return Err(Error::InvalidInput(format!(
+                "expected {} bytes, got {}",
+                expected_len,
+                bytes.len()
+            )));
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Returns the serialized identity of the sender, without validating it.
+    pub fn identity_bytes(&self) -> &'a IdentitySerialization {
+        self.0[Self::IDENTITY_OFFSET..Self::IDENTITY_OFFSET + IDENTITY_LEN]
+            .try_into()
+            .expect("length checked at construction")
+    }
+
+    /// Returns the checksum of the commitment, without validating it.
+    pub fn checksum(&self) -> Checksum {
+        Checksum::from_le_bytes(
+            self.0[Self::CHECKSUM_OFFSET..Self::CHECKSUM_OFFSET + CHECKSUM_LEN]
+                .try_into()
+                .expect("length checked at construction"),
+        )
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Fully deserializes and validates the commitment.
+    pub fn into_owned(self) -> io::Result<SigningCommitment> {
+        SigningCommitment::deserialize_from(self.0)
+    }
+}
+
+/// Builds a FROST [`SigningPackage`] from a set of [`SigningCommitment`] structs.
+///
+/// The order of `commitments` does not influence the output. Returns an error if more than one
+/// commitment is provided for the same identity, or if any of the commitments is a dry-run
+/// commitment.
+pub fn signing_package<'a, I>(
+    commitments: I,
+    transaction_hash: &[u8],
+) -> Result<SigningPackage, Error>
+where
+    I: IntoIterator<Item = &'a SigningCommitment>,
+{
+    let mut identifiers = IdentifierMap::new();
+    let mut raw_commitments = BTreeMap::new();
+    for commitment in commitments {
+        if commitment.is_dry_run() {
+            return Err(Error::InvalidInput(format!(
+                "dry-run commitment provided for identity {}",
+                commitment.identity()
+            )));
+        }
+        let identifier = identifiers
+            .insert(commitment.identity())
+            .map_err(|err| match err {
+                MappingError::Duplicate(identity) => Error::InvalidInput(format!(
+                    "multiple commitments provided for identity {}",
+                    identity
+                )),
+                MappingError::Collision(identities) => Error::IdentifierCollision { identities },
+            })?;
+        raw_commitments.insert(identifier, *commitment.raw_commitments());
+    }
+    Ok(SigningPackage::new(raw_commitments, transaction_hash))
+}
+
+/// Builds a FROST [`SigningPackage`] from the valid subset of `commitments`.
+///
+/// Unlike [`signing_package`], this does not fail on the first invalid commitment. Commitments
+/// are rejected if their signer is not one of `signing_participants`, if their checksum does not
+/// match `transaction_hash` and `signing_participants`, or if another commitment was already
+/// accepted for the same signer. Returns the package, the identities of the signers whose
+/// commitments were accepted, and the identities of the signers whose commitments were rejected,
+/// along with the reason for rejection.
+#[allow(clippy::type_complexity)]
+pub fn build_signing_package_lenient<'a, I, P>(
+    commitments: I,
+    transaction_hash: &[u8],
+    signing_participants: &[P],
+) -> (SigningPackage, Vec<Identity>, Vec<(Identity, Error)>)
+where
+    I: IntoIterator<Item = &'a SigningCommitment>,
+    P: Borrow<Identity>,
+{
+    let mut identifiers = IdentifierMap::new();
+    let mut raw_commitments = BTreeMap::new();
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+
+    for commitment in commitments {
+        let identity = commitment.identity();
+
+        if !signing_participants
+            .iter()
+            .any(|participant| participant.borrow() == identity)
+        {
+            rejected.push((
+                identity.clone(),
+                Error::InvalidInput(format!("unexpected signer {}", identity)),
+            ));
+            continue;
+        }
+
+        if let Err(e) = commitment.verify_checksum(transaction_hash, signing_participants) {
+            rejected.push((identity.clone(), Error::ChecksumError(e)));
+            continue;
+        }
+
+        match identifiers.insert(identity) {
+            Ok(identifier) => {
+                raw_commitments.insert(identifier, *commitment.raw_commitments());
+                accepted.push(identity.clone());
+            }
+            Err(MappingError::Duplicate(identity)) => rejected.push((
+                identity.clone(),
+                Error::InvalidInput(format!(
+                    "multiple commitments provided for identity {}",
+                    identity
+                )),
+            )),
+            Err(MappingError::Collision(identities)) => {
+                rejected.push((identity.clone(), Error::IdentifierCollision { identities }))
+            }
+        }
+    }
+
+    (
+        SigningPackage::new(raw_commitments, transaction_hash),
+        accepted,
+        rejected,
+    )
+}
+
+/// Returns a canonical serialization of the [`SigningPackage`] built from `commitments` and
+/// `transaction_hash`.
+///
+/// The result does not depend on the order of `commitments`, so coordinators can compare the
+/// serialization (or a digest of it) to confirm that they all assembled the same package.
+pub fn canonical_package_bytes<'a, I>(
+    commitments: I,
+    transaction_hash: &[u8],
+) -> Result<Vec<u8>, Error>
+where
+    I: IntoIterator<Item = &'a SigningCommitment>,
+{
+    signing_package(commitments, transaction_hash)?
+        .serialize()
+        .map_err(Error::FrostError)
+}
+
+/// Progress of a coordinator towards collecting enough commitments to sign.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RemainingReport {
+    /// Number of additional commitments required to reach the threshold.
+    pub needed: usize,
+    /// Participants that are available and have not sent a commitment yet.
+    pub candidates: Vec<Identity>,
+}
+
+/// Computes how many more commitments are needed to reach `min_signers`, given the commitments
+/// `received` so far, and which of the `available` participants can still provide them.
+///
+/// Multiple commitments from the same identity are counted only once.
+pub fn remaining_for_threshold(
+    received: &[SigningCommitment],
+    available: &[Identity],
+    min_signers: usize,
+) -> RemainingReport {
+    let received = received
+        .iter()
+        .map(SigningCommitment::identity)
+        .collect::<BTreeSet<_>>();
+    let candidates = available
+        .iter()
+        .filter(|identity| !received.contains(identity))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect();
+    RemainingReport {
+        needed: min_signers.saturating_sub(received.len()),
+        candidates,
+    }
+}
+
+/// Parses commitments serialized in the native reddsa FROST format by a plain FROST
+/// implementation, associating them with `identity`.
+///
+/// Unlike [`SigningCommitment`], the resulting commitments are **not authenticated**: there is no
+/// signature proving that they were produced by `identity`, nor a checksum binding them to a
+/// transaction and set of signers. Callers must establish authenticity by other means (for
+/// example, an authenticated transport). The result can be inserted directly in the map used to
+/// build a [`SigningPackage`].
+pub fn from_frost_commitments(
+    identity: &Identity,
+    bytes: &[u8],
+) -> Result<(Identifier, SigningCommitments), Error> {
+    let commitments = SigningCommitments::deserialize(bytes).map_err(Error::FrostError)?;
+    Ok((identity.to_frost_identifier(), commitments))
+}
+
+/// Partitions `commitments` by checksum, so that commitments belonging to different signing
+/// sessions can be processed separately.
+///
+/// Within each group, commitments keep the order in which they were provided.
+pub fn group_by_checksum<I>(commitments: I) -> BTreeMap<Checksum, Vec<SigningCommitment>>
+where
+    I: IntoIterator<Item = SigningCommitment>,
+{
+    let mut groups = BTreeMap::<_, Vec<_>>::new();
+    for commitment in commitments {
+        groups
+            .entry(commitment.checksum())
+            .or_default()
+            .push(commitment);
+    }
+    groups
+}
+
+/// Sums the weights of the signers of `commitments`, as given by `weights`.
+///
+/// Each signer is counted only once, even if multiple commitments from the same signer are
+/// present. Signers that are not in `weights` have a weight of 0.
+#[must_use]
+pub fn total_weight<'a, I>(commitments: I, weights: &BTreeMap<Identity, u32>) -> u64
+where
+    I: IntoIterator<Item = &'a SigningCommitment>,
+{
+    commitments
+        .into_iter()
+        .map(SigningCommitment::identity)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|identity| weights.get(identity).copied().unwrap_or(0) as u64)
+        .sum()
+}
+
+/// Returns `true` if the [`total_weight`] of `commitments` is at least `required`.
+#[must_use]
+pub fn meets_weight_threshold<'a, I>(
+    commitments: I,
+    weights: &BTreeMap<Identity, u32>,
+    required: u64,
+) -> bool
+where
+    I: IntoIterator<Item = &'a SigningCommitment>,
+{
+    total_weight(commitments, weights) >= required
+}
+
+/// Length of the digests returned by [`commitment_digest`].
+pub const COMMITMENT_DIGEST_LEN: usize = 32;
+
+const COMMITMENT_DIGEST_CONTEXT: &str = "ironfish-frost 2024 SigningCommitment digest";
+
+/// Number of cells that each digest is inserted into by [`ReconcileSketch`].
+const SKETCH_HASH_COUNT: usize = 3;
+const SKETCH_CELL_LEN: usize = 4 + COMMITMENT_DIGEST_LEN + CHECKSUM_LEN;
+
+/// Computes a digest that identifies `commitment`, as used by [`ReconcileSketch`].
+#[must_use]
+pub fn commitment_digest(commitment: &SigningCommitment) -> [u8; COMMITMENT_DIGEST_LEN] {
+    blake3::derive_key(COMMITMENT_DIGEST_CONTEXT, &commitment.serialize_extended())
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct SketchCell {
+    count: i32,
+    digest_sum: [u8; COMMITMENT_DIGEST_LEN],
+    hash_sum: Checksum,
+}
+
+impl SketchCell {
+    const EMPTY: Self = Self {
+        count: 0,
+        digest_sum: [0u8; COMMITMENT_DIGEST_LEN],
+        hash_sum: 0,
+    };
+
+    fn toggle(&mut self, digest: &[u8; COMMITMENT_DIGEST_LEN], count: i32) {
+        self.count = self.count.wrapping_add(count);
+        for (sum, byte) in self.digest_sum.iter_mut().zip(digest) {
+            *sum ^= byte;
+        }
+        self.hash_sum ^= sketch_hash(digest);
+    }
+
+    fn is_pure(&self) -> bool {
+        (self.count == 1 || self.count == -1) && self.hash_sum == sketch_hash(&self.digest_sum)
+    }
+}
+
+#[must_use]
+fn sketch_hash(digest: &[u8; COMMITMENT_DIGEST_LEN]) -> Checksum {
+    let mut hasher = ChecksumHasher::new();
+    hasher.write(digest);
+    hasher.finish()
+}
+
+/// Difference between two sets of commitments, as returned by [`ReconcileSketch::decode`].
+///
+/// Commitments are identified by their [`commitment_digest`]. Digests are sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Difference {
+    /// Digests of the commitments that are only in the local set.
+    pub only_local: Vec<[u8; COMMITMENT_DIGEST_LEN]>,
+    /// Digests of the commitments that are only in the remote set.
+    pub only_remote: Vec<[u8; COMMITMENT_DIGEST_LEN]>,
+}
+
+/// Invertible Bloom lookup table of commitment digests, used to reconcile two sets of commitments
+/// without exchanging them in full.
+///
+/// Two coordinators build a sketch of their own set with the same number of cells, and exchange
+/// the [encoded](ReconcileSketch::encode) sketches. [`ReconcileSketch::decode`] then recovers the
+/// digests of the commitments that are in only one of the two sets. The size of a sketch depends
+/// only on its number of cells, not on the size of the set: decoding succeeds with high probability
+/// as long as the number of cells is at least 1.5 times the size of the difference (plus a small
+/// constant for small differences).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReconcileSketch {
+    cells: Vec<SketchCell>,
+}
+
+impl ReconcileSketch {
+    /// Creates an empty sketch with at least `cells` cells.
+    #[must_use]
+    pub fn new(cells: usize) -> Self {
+        let cells = cells.max(1).div_ceil(SKETCH_HASH_COUNT) * SKETCH_HASH_COUNT;
+        Self {
+            cells: vec![SketchCell::EMPTY; cells],
+        }
+    }
+
+    /// Creates a sketch with at least `cells` cells, containing all the `commitments`.
+    #[must_use]
+    pub fn from_commitments<'a, I>(commitments: I, cells: usize) -> Self
+    where
+        I: IntoIterator<Item = &'a SigningCommitment>,
+    {
+        let mut sketch = Self::new(cells);
+        for commitment in commitments {
+            sketch.insert(commitment);
+        }
+        sketch
+    }
+
+    /// Returns the number of cells of this sketch.
+    #[must_use]
+    pub fn cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn insert(&mut self, commitment: &SigningCommitment) {
+        self.toggle(&commitment_digest(commitment), 1);
+    }
+
+    /// Returns the indexes of the cells that `digest` is inserted into.
+    fn positions(&self, digest: &[u8; COMMITMENT_DIGEST_LEN]) -> [usize; SKETCH_HASH_COUNT] {
+        // The table is split into one region per hash function, so that each digest is always
+        // inserted into distinct cells
+        let region_len = self.cells.len() / SKETCH_HASH_COUNT;
+        let mut positions = [0usize; SKETCH_HASH_COUNT];
+        for (region, chunk) in digest.chunks_exact(8).take(SKETCH_HASH_COUNT).enumerate() {
+            let index = u64::from_le_bytes(chunk.try_into().expect("chunk is 8 bytes long"));
+            positions[region] = region * region_len + (index % region_len as u64) as usize;
+        }
+        positions
+    }
+
+    fn toggle(&mut self, digest: &[u8; COMMITMENT_DIGEST_LEN], count: i32) {
+        for index in self.positions(digest) {
+            self.cells[index].toggle(digest, count);
+        }
+    }
+
+    /// Computes the difference between the set of commitments of this sketch (the local set) and
+    /// the set of `other` (the remote set).
+    ///
+    /// Returns an error if the sketches have a different number of cells, if either sketch is
+    /// malformed, or if the difference is too large to be decoded with the number of cells of the
+    /// sketches. In the latter case, the reconciliation can be retried with larger sketches.
+    pub fn decode(&self, other: &Self) -> Result<Difference, Error> {
+        if self.cells.len() != other.cells.len() {
+            return Err(Error::InvalidInput(format!(
+                "sketches have a different number of cells: {} and {}",
+                self.cells.len(),
+                other.cells.len()
+            )));
+        }
+
+        let mut diff = Self {
+            cells: self
+                .cells
+                .iter()
+                .zip(&other.cells)
+                .map(|(local, remote)| {
+                    let mut digest_sum = local.digest_sum;
+                    for (sum, byte) in digest_sum.iter_mut().zip(&remote.digest_sum) {
+                        *sum ^= byte;
+                    }
+                    SketchCell {
+                        count: local.count.wrapping_sub(remote.count),
+                        digest_sum,
+                        hash_sum: local.hash_sum ^ remote.hash_sum,
+                    }
+                })
+                .collect(),
+        };
+
+        let mut only_local = Vec::new();
+        let mut only_remote = Vec::new();
+        // Each digest of a well-formed difference is peeled from a different cell, so decoding
+        // never takes more than one iteration per cell
+        for _ in 0..diff.cells.len() {
+            let Some((index, cell)) = diff
+                .cells
+                .iter()
+                .copied()
+                .enumerate()
+                .find(|(_, cell)| cell.is_pure())
+            else {
+                break;
+            };
+            // A pure cell that the digest would not have been inserted into can only come from a
+            // crafted sketch. Peeling it would not empty the cell, and decoding would not terminate
+            if !diff.positions(&cell.digest_sum).contains(&index) {
+                return Err(Error::InvalidInput(
+                    "sketch contains a digest in an unexpected cell".to_string(),
+                ));
+            }
+            if cell.count == 1 {
+                only_local.push(cell.digest_sum);
+            } else {
+                only_remote.push(cell.digest_sum);
+            }
+            diff.toggle(&cell.digest_sum, -cell.count);
+        }
+
+        if diff.cells.iter().any(|cell| *cell != SketchCell::EMPTY) {
+            return Err(Error::InvalidInput(
+                "difference is too large to be decoded from the sketches".to_string(),
+            ));
+        }
+
+        only_local.sort_unstable();
+        only_remote.sort_unstable();
+        Ok(Difference {
+            only_local,
+            only_remote,
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + self.cells.len() * SKETCH_CELL_LEN);
+        self.encode_into(&mut bytes).expect("serialization failed");
+        bytes
+    }
+
+    pub fn encode_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        write_usize(&mut writer, self.cells.len())?;
+        for cell in &self.cells {
+            writer.write_all(&cell.count.to_le_bytes())?;
+            writer.write_all(&cell.digest_sum)?;
+            writer.write_all(&cell.hash_sum.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a sketch produced by [`ReconcileSketch::encode`], rejecting sketches with more
+    /// than `max_cells` cells.
+    pub fn decode_from<R: io::Read>(mut reader: R, max_cells: usize) -> io::Result<Self> {
+        let len = read_usize(&mut reader)?;
+        if len == 0 || len % SKETCH_HASH_COUNT != 0 {
+            return Err(io::Error::other("invalid number of sketch cells"));
+        }
+        if len > max_cells {
+            return Err(io::Error::other("too many sketch cells"));
+        }
+
+        let mut cells = Vec::with_capacity(len);
+        for _ in 0..len {
+            let mut cell = [0u8; SKETCH_CELL_LEN];
+            reader.read_exact(&mut cell)?;
+            let (count, rest) = cell.split_at(4);
+            let (digest_sum, hash_sum) = rest.split_at(COMMITMENT_DIGEST_LEN);
+            cells.push(SketchCell {
+                count: i32::from_le_bytes(count.try_into().expect("count is 4 bytes long")),
+                digest_sum: digest_sum.try_into().expect("digest is 32 bytes long"),
+                hash_sum: Checksum::from_le_bytes(
+                    hash_sum.try_into().expect("checksum is 8 bytes long"),
+                ),
+            });
+        }
+        Ok(Self { cells })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::authenticated_data;
+    use super::build_signing_package_lenient;
+    use super::canonical_package_bytes;
+    use super::commitment_digest;
+    use super::from_frost_commitments;
+    use super::group_by_checksum;
+    use super::input_checksum;
+    use super::input_checksum_presorted;
+    use super::meets_weight_threshold;
+    use super::recover_participants;
+    use super::remaining_for_threshold;
+    use super::signing_package;
+    use super::total_weight;
+    use super::verify_commitments_authenticity;
+    #[cfg(feature = "parallel")]
+    use super::verify_parallel;
+    use super::versioned_authenticated_data;
+    use super::CommitmentRelationship;
+    use super::ReconcileSketch;
+    use super::SigningCommitment;
+    use super::SigningCommitmentRef;
+    use super::FLAG_DRY_RUN;
+    use super::GROUP_HASH_LEN;
+    use super::SIGNING_COMMITMENT_LEN;
+    use super::SIGNING_COMMITMENT_WITHOUT_CHECKSUM_LEN;
+    use crate::checksum::ChecksumError;
+    use crate::checksum::CHECKSUM_VERSION;
+    use crate::checksum::VERSIONED_CHECKSUM_LEN;
+    use crate::error::Error;
+    use crate::frost::keys::SigningShare;
+    use crate::nonces::test_signing_share;
+    use crate::participant::Secret;
+    use crate::participant::Signature;
+    use crate::participant::IDENTITY_LEN;
+    use ed25519_dalek::hazmat::raw_sign;
+    use ed25519_dalek::hazmat::ExpandedSecretKey;
+    use hex_literal::hex;
+    use rand::thread_rng;
+    use sha2::Sha512;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn serialization_round_trip() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let serialized = commitment.serialize().expect("serialization failed");
+
+        let deserialized =
+            SigningCommitment::deserialize_from(&serialized[..]).expect("deserialization failed");
+
+        assert_eq!(deserialized, commitment);
+    }
+
+    #[test]
+    fn serialization_without_identity_round_trip() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let serialized = commitment
+            .serialize_without_identity()
+            .expect("serialization failed");
+
+        let deserialized = SigningCommitment::deserialize_with_identity_from(
+            &serialized[..],
+            secret.to_identity(),
+        )
+        .expect("deserialization failed");
+        assert_eq!(deserialized, commitment);
+
+        let wrong_identity = Secret::random(&mut rng).to_identity();
+        SigningCommitment::deserialize_with_identity_from(&serialized[..], wrong_identity)
+            .expect_err("deserialization with the wrong identity should have failed");
+    }
+
+    #[test]
+    #[cfg(feature = "dkg")]
+    fn from_secrets_for_group() {
+        use crate::dkg::round3::PublicKeyPackage;
+        use crate::frost::frost::keys::IdentifierList;
+        use crate::frost::keys::split;
+        use crate::frost::SigningKey;
+
+        let mut rng = thread_rng();
+
+        let secrets = [
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+        ];
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+        let identifiers = identities
+            .iter()
+            .map(|identity| identity.to_frost_identifier())
+            .collect::<Vec<_>>();
+
+        let (_, frost_public_key_package) = split(
+            &SigningKey::new(&mut rng),
+            3,
+            2,
+            IdentifierList::Custom(&identifiers),
+            &mut rng,
+        )
+        .expect("signing key split failed");
+        let public_key_package =
+            PublicKeyPackage::from_frost(frost_public_key_package, identities.clone(), 2);
+
+        let signing_share = test_signing_share();
+
+        let commitment = SigningCommitment::from_secrets_for_group(
+            &secrets[0],
+            &signing_share,
+            b"transaction hash",
+            &identities[..2],
+            &public_key_package,
+        )
+        .expect("commitment generation failed");
+        assert_eq!(
+            commitment,
+            SigningCommitment::try_from_secrets(
+                &secrets[0],
+                &signing_share,
+                b"transaction hash",
+                &identities[..2],
+            )
+            .expect("commitment generation failed")
+        );
+
+        let non_member = Secret::random(&mut rng).to_identity();
+        let result = SigningCommitment::from_secrets_for_group(
+            &secrets[0],
+            &signing_share,
+            b"transaction hash",
+            &[&identities[0], &identities[1], &non_member],
+            &public_key_package,
+        );
+        match result {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("commitment generation should have failed with InvalidInput"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "reject-weak-keys")]
+    fn degenerate_signing_share() {
+        let secret = Secret::random(thread_rng());
+        let share = SigningShare::default();
+        let participants = [secret.to_identity()];
+
+        let results = [
+            SigningCommitment::try_from_secrets(&secret, &share, b"tx", &participants),
+            SigningCommitment::from_secrets_versioned(&secret, &share, b"tx", &participants, 2),
+            SigningCommitment::from_secrets_for_dkg(&secret, &share, b"tx", &participants, [1; 32]),
+            SigningCommitment::from_secrets_dry_run(&secret, &share, b"tx", &participants),
+            SigningCommitment::from_provider(&share, &secret, b"tx", &participants),
+        ];
+        for result in results {
+            match result {
+                Err(Error::InvalidInput(_)) => (),
+                _ => panic!("degenerate signing share should have been rejected"),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "reject-weak-keys")]
+    #[should_panic(expected = "degenerate signing share")]
+    #[allow(deprecated)]
+    fn degenerate_signing_share_infallible_constructor() {
+        let secret = Secret::random(thread_rng());
+        let _ = SigningCommitment::from_secrets(
+            &secret,
+            &SigningShare::default(),
+            b"transaction hash",
+            &[secret.to_identity()],
+        );
+    }
+
+    #[test]
+    fn diff_serialized() {
+        let secret = Secret::random(thread_rng());
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &test_signing_share(),
+            b"transaction hash",
+            &[secret.to_identity()],
+        )
+        .expect("commitment generation failed");
+        assert!(commitment.diff_serialized(&commitment).is_empty());
+
+        let other = SigningCommitment {
+            identity: Secret::random(thread_rng()).to_identity(),
+            ..commitment.clone()
+        };
+        let diffs = commitment.diff_serialized(&other);
+
+        let identity_offset = Signature::BYTE_SIZE;
+        assert!(!diffs.is_empty());
+        for diff in diffs {
+            assert_eq!(diff.field, "identity");
+            assert!((identity_offset..identity_offset + IDENTITY_LEN).contains(&diff.offset));
+            assert_ne!(
+                commitment.serialize().expect("serialization failed")[diff.offset],
+                other.serialize().expect("serialization failed")[diff.offset]
+            );
+        }
+    }
+
+    #[test]
+    fn layout() {
+        let layout = SigningCommitment::layout();
+        assert_eq!(
+            layout.iter().map(|(_, size)| size).sum::<usize>(),
+            SIGNING_COMMITMENT_LEN
+        );
+
+        let secret = Secret::random(thread_rng());
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &test_signing_share(),
+            b"transaction hash",
+            &[secret.to_identity()],
+        )
+        .expect("commitment generation failed");
+        let serialized = commitment.serialize().expect("serialization failed");
+
+        let mut offset = 0;
+        for &(name, size) in layout {
+            let field = &serialized[offset..offset + size];
+            match name {
+                "signature" => assert_eq!(field, commitment.signature.to_bytes()),
+                "identity" => assert_eq!(field, commitment.identity().serialize()),
+                "hiding" => assert_eq!(field, commitment.hiding().serialize()),
+                "binding" => assert_eq!(field, commitment.binding().serialize()),
+                "checksum_version" => assert_eq!(field, [CHECKSUM_VERSION]),
+                "checksum" => assert_eq!(field, commitment.checksum().to_le_bytes()),
+                _ => panic!("unexpected field {}", name),
+            }
+            offset += size;
+        }
+    }
+
+    #[test]
+    fn deserialization_regression() {
+        let serialization = hex!(
+            "
+            307be5a2c20495d05966fc12b2cee3ea4d44cb3623f92b0f6a391c626fa7708e835
+            26e886448d5ef376c5d09675aed3e711cd3e0df9f6c607604e6a7371a210e725c3a
+            20a22aebc59d856bfbaa48fde8f8ea6fe48ddd978555932c283e760397f78b4b468
+            2f9b70f8baad6d7752f5e25bcbc6b3453d16d92589da722ad13a7390d0057c6aae8
+            363a50e835b89b44bccdd5889ef5a362fa89d841c96e65b34dbe3adf8f71faa041f
             394ef6b127c4b6b1e43714f32c450e8d3d089b376915acd6500639cad9b202c479e
             4216e2d4d16cad09b634e01270f4a52707d924fd9834e6206f48f04388ae90bcd63
             f901369c6034760245574a2d3068f52b617d33ca1a417ea391d3785b542f5
         "
         );
-        let deserialized = SigningCommitment::deserialize_from(&serialization[..])
+        let deserialized = SigningCommitment::deserialize_v0_from(&serialization[..])
+            .expect("deserialization failed");
+        assert_eq!(
+            serialization,
+            deserialized.serialize_v0().expect("serialization failed")
+        );
+
+        // Same commitment in the v1 layout, which carries the checksum version right before the
+        // checksum
+        let upgraded = hex!(
+            "
+            307be5a2c20495d05966fc12b2cee3ea4d44cb3623f92b0f6a391c626fa7708e835
+            26e886448d5ef376c5d09675aed3e711cd3e0df9f6c607604e6a7371a210e725c3a
+            20a22aebc59d856bfbaa48fde8f8ea6fe48ddd978555932c283e760397f78b4b468
+            2f9b70f8baad6d7752f5e25bcbc6b3453d16d92589da722ad13a7390d0057c6aae8
+            363a50e835b89b44bccdd5889ef5a362fa89d841c96e65b34dbe3adf8f71faa041f
+            394ef6b127c4b6b1e43714f32c450e8d3d089b376915acd6500639cad9b202c479e
+            4216e2d4d16cad09b634e01270f4a52707d924fd9834e6206f48f04388ae90bcd63
+            f901369c6034760245574a2d3068f52b617d33ca1a41701ea391d3785b542f5
+        "
+        );
+        assert_eq!(
+            SigningCommitment::deserialize_from(&upgraded[..]).expect("deserialization failed"),
+            deserialized
+        );
+        assert_eq!(
+            upgraded,
+            deserialized.serialize().expect("serialization failed")
+        );
+
+        SigningCommitment::deserialize_from(&serialization[..])
+            .expect_err("v0 layout should have been rejected");
+    }
+
+    #[test]
+    fn unsupported_checksum_version() {
+        let secret = Secret::random(thread_rng());
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &test_signing_share(),
+            b"transaction hash",
+            &[secret.to_identity()],
+        )
+        .expect("commitment generation failed");
+
+        let mut serialized = commitment.serialize().expect("serialization failed");
+        serialized[SIGNING_COMMITMENT_LEN - VERSIONED_CHECKSUM_LEN] = CHECKSUM_VERSION + 1;
+        let err = SigningCommitment::deserialize_from(&serialized[..])
+            .expect_err("unknown checksum version should have been rejected");
+        match err
+            .get_ref()
+            .and_then(|err| err.downcast_ref::<ChecksumError>())
+        {
+            Some(ChecksumError::UnsupportedVersion(version)) => {
+                assert_eq!(*version, CHECKSUM_VERSION + 1)
+            }
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_invalid_deserialization() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let serialized = commitment.serialize().expect("serialization failed");
+
+        for index in 0..serialized.len() {
+            let mut invalid_serialization = serialized;
+            invalid_serialization[index] ^= 0xff;
+            assert!(SigningCommitment::deserialize_from(&invalid_serialization[..]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_valid_signature() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        assert!(commitment.verify_authenticity().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_signature() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let unrelated_secret = Secret::random(&mut rng);
+        let invalid_signature = unrelated_secret.sign(&authenticated_data(
+            commitment.identity(),
+            commitment.raw_commitments(),
+            commitment.checksum(),
+        ));
+
+        let invalid_commitment = SigningCommitment {
+            identity: commitment.identity().clone(),
+            raw_commitments: *commitment.raw_commitments(),
+            checksum: commitment.checksum(),
+            protocol_version: commitment.protocol_version(),
+            group_hash: commitment.group_hash().copied(),
+            dry_run: commitment.is_dry_run(),
+            signature: invalid_signature,
+        };
+
+        assert!(invalid_commitment.verify_authenticity().is_err());
+    }
+
+    #[test]
+    fn from_provider() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
+
+        let commitment = SigningCommitment::from_provider(
+            &signing_share,
+            &secret,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        assert_eq!(
+            commitment,
+            SigningCommitment::try_from_secrets(
+                &secret,
+                &signing_share,
+                b"transaction hash",
+                &signing_participants,
+            )
+            .expect("commitment generation failed")
+        );
+    }
+
+    #[test]
+    fn test_protocol_version() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
+
+        let commitment_v1 = SigningCommitment::from_secrets_versioned(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+            1,
+        )
+        .expect("commitment generation failed");
+        let commitment_v2 = SigningCommitment::from_secrets_versioned(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+            2,
+        )
+        .expect("commitment generation failed");
+
+        commitment_v2
+            .verify_version(2)
+            .expect("version verification failed");
+        commitment_v1
+            .verify_version(2)
+            .expect_err("version verification should have failed");
+
+        // The protocol version is covered by the signature
+        let forged_commitment = SigningCommitment {
+            protocol_version: 2,
+            ..commitment_v1.clone()
+        };
+        forged_commitment
+            .verify_authenticity()
+            .expect_err("authenticity verification should have failed");
+
+        // The protocol version is serialized in an extension
+        assert!(commitment_v2.is_extended());
+        let serialized = commitment_v2.serialize_extended();
+        assert_eq!(serialized.len(), commitment_v2.serialized_len());
+        assert_eq!(serialized.len(), SIGNING_COMMITMENT_LEN + 2);
+        let deserialized =
+            SigningCommitment::deserialize_from(&serialized[..]).expect("deserialization failed");
+        assert_eq!(deserialized, commitment_v2);
+        assert_eq!(deserialized.protocol_version(), 2);
+        let deserialized = SigningCommitment::deserialize_versioned_from(&serialized[..], 2)
             .expect("deserialization failed");
-        assert_eq!(serialization, deserialized.serialize());
+        assert_eq!(deserialized, commitment_v2);
+        SigningCommitment::deserialize_versioned_from(&serialized[..], 1)
+            .expect_err("deserialization should have failed");
+        assert_eq!(
+            SigningCommitmentRef::new(&serialized[..])
+                .expect("invalid length")
+                .into_owned()
+                .expect("deserialization failed"),
+            commitment_v2
+        );
+
+        // The protocol version is authenticated even when it is read from the serialization
+        let mut forged = serialized.clone();
+        forged[SIGNING_COMMITMENT_LEN] = 1;
+        SigningCommitment::deserialize_from(&forged[..])
+            .expect_err("deserialization of a forged protocol version should have failed");
+
+        // Commitments for the default protocol version keep the fixed-size layout
+        let commitment_v0 = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        assert!(!commitment_v0.is_extended());
+        assert_eq!(
+            commitment_v0.serialize_extended(),
+            commitment_v0.serialize().expect("serialization failed")
+        );
+        assert_eq!(
+            SigningCommitment::deserialize_versioned_from(
+                &commitment_v0.serialize().expect("serialization failed")[..],
+                0
+            )
+            .expect("deserialization failed"),
+            commitment_v0
+        );
+    }
+
+    #[test]
+    fn fixed_size_serialization_of_extended_commitment() {
+        let secret = Secret::random(thread_rng());
+        let commitment = SigningCommitment::from_secrets_versioned(
+            &secret,
+            &test_signing_share(),
+            b"transaction hash",
+            &[secret.to_identity()],
+            2,
+        )
+        .expect("commitment generation failed");
+        commitment
+            .serialize()
+            .expect_err("extended commitment should not fit in the fixed-size layout");
+        commitment
+            .serialize_v0()
+            .expect_err("extended commitment should not fit in the legacy layout");
+        commitment
+            .serialize_without_identity()
+            .expect_err("extended commitment should not fit in the fixed-size layout");
+        commitment
+            .serialize_no_checksum()
+            .expect_err("extended commitment should not fit in the fixed-size layout");
+        commitment
+            .serialize_be()
+            .expect_err("extended commitment should not fit in the fixed-size layout");
+    }
+
+    #[test]
+    fn test_relationship() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let other_secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let other_signing_share =
+            SigningShare::deserialize(*b"some other signing share.......\0").unwrap();
+        let signing_participants = [
+            secret.to_identity(),
+            other_secret.to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        assert_eq!(
+            commitment
+                .relationship(&commitment.clone())
+                .expect("relationship failed"),
+            CommitmentRelationship::Identical
+        );
+
+        // Commitments that fail authentication are not compared
+        let forged_commitment = SigningCommitment {
+            signature: secret.sign(b"not the authenticated data"),
+            ..commitment.clone()
+        };
+        commitment
+            .relationship(&forged_commitment)
+            .expect_err("forged commitment should have been rejected");
+        forged_commitment
+            .relationship(&commitment)
+            .expect_err("forged commitment should have been rejected");
+
+        let other_session = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"other transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        assert_eq!(
+            commitment
+                .relationship(&other_session)
+                .expect("relationship failed"),
+            CommitmentRelationship::DifferentSession
+        );
+
+        let other_signer = SigningCommitment::try_from_secrets(
+            &other_secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        assert_eq!(
+            commitment
+                .relationship(&other_signer)
+                .expect("relationship failed"),
+            CommitmentRelationship::DifferentSession
+        );
+
+        let equivocation = SigningCommitment::try_from_secrets(
+            &secret,
+            &other_signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        assert_eq!(
+            commitment
+                .relationship(&equivocation)
+                .expect("relationship failed"),
+            CommitmentRelationship::Equivocation
+        );
+
+        // A signer that does not derive its Ed25519 nonces deterministically produces a different,
+        // but still valid, signature for the same commitment
+        let authenticated_data = versioned_authenticated_data(
+            commitment.identity(),
+            commitment.raw_commitments(),
+            commitment.checksum(),
+            &commitment.extension(),
+        );
+        let mut expanded_secret_key = ExpandedSecretKey::from(secret.signing_key().as_bytes());
+        expanded_secret_key.hash_prefix = [0x42; 32];
+        let resigned = SigningCommitment {
+            signature: raw_sign::<Sha512>(
+                &expanded_secret_key,
+                &authenticated_data,
+                &secret.signing_key().verifying_key(),
+            ),
+            ..commitment.clone()
+        };
+        assert_ne!(resigned.signature(), commitment.signature());
+        assert_eq!(
+            commitment
+                .relationship(&resigned)
+                .expect("relationship failed"),
+            CommitmentRelationship::RetrySameSession
+        );
+    }
+
+    #[test]
+    fn test_checksum_stability() {
+        let mut rng = thread_rng();
+
+        let secret1 = Secret::random(&mut rng);
+        let secret2 = Secret::random(&mut rng);
+        let signing_share1 = test_signing_share();
+        let signing_share2 = test_signing_share();
+        let transaction_hash = b"something";
+        let signing_participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment1 = SigningCommitment::try_from_secrets(
+            &secret1,
+            &signing_share1,
+            transaction_hash,
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let commitment2 = SigningCommitment::try_from_secrets(
+            &secret2,
+            &signing_share2,
+            transaction_hash,
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        assert_ne!(commitment1, commitment2);
+        assert_eq!(commitment1.checksum(), commitment2.checksum());
+    }
+
+    #[test]
+    fn test_checksum_presorted() {
+        let mut rng = thread_rng();
+
+        let mut signing_participants = (0..10)
+            .map(|_| Secret::random(&mut rng).to_identity())
+            .collect::<Vec<_>>();
+        let checksum = input_checksum(b"something", &signing_participants);
+
+        signing_participants.sort_unstable();
+        assert_eq!(
+            checksum,
+            input_checksum_presorted(b"something", &signing_participants)
+        );
+        assert_eq!(
+            checksum,
+            input_checksum_presorted(b"something", signing_participants.iter().cloned())
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must be sorted")]
+    fn test_checksum_presorted_unsorted_input() {
+        let mut rng = thread_rng();
+
+        let mut signing_participants = (0..3)
+            .map(|_| Secret::random(&mut rng).to_identity())
+            .collect::<Vec<_>>();
+        signing_participants.sort_unstable();
+        signing_participants.reverse();
+
+        let _ = input_checksum_presorted(b"something", &signing_participants);
+    }
+
+    #[test]
+    fn test_checksum_variation_with_transaction_hash() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment1 = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"something",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let commitment2 = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"something else",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        assert_ne!(commitment1.checksum(), commitment2.checksum());
+    }
+
+    #[test]
+    fn test_checksum_variation_with_signers_list() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let transaction_hash = b"something";
+        let signing_participants1 = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+        let signing_participants2 = [
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+            Secret::random(&mut rng).to_identity(),
+        ];
+
+        let commitment1 = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            transaction_hash,
+            &signing_participants1,
+        )
+        .expect("commitment generation failed");
+
+        let commitment2 = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            transaction_hash,
+            &signing_participants2,
+        )
+        .expect("commitment generation failed");
+
+        assert_ne!(commitment1.checksum(), commitment2.checksum());
+    }
+
+    #[test]
+    fn canonical_package_bytes_stability() {
+        let mut rng = thread_rng();
+
+        let secrets = [
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+        ];
+        let signing_share = test_signing_share();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let commitments = secrets
+            .iter()
+            .map(|secret| {
+                SigningCommitment::try_from_secrets(
+                    secret,
+                    &signing_share,
+                    b"transaction hash",
+                    &signing_participants,
+                )
+                .expect("commitment generation failed")
+            })
+            .collect::<Vec<_>>();
+
+        let bytes1 = canonical_package_bytes(&commitments, b"transaction hash")
+            .expect("serialization failed");
+        let bytes2 = canonical_package_bytes(
+            [&commitments[2], &commitments[0], &commitments[1]],
+            b"transaction hash",
+        )
+        .expect("serialization failed");
+        assert_eq!(bytes1, bytes2);
+
+        let bytes3 = canonical_package_bytes(&commitments, b"other transaction hash")
+            .expect("serialization failed");
+        assert_ne!(bytes1, bytes3);
+
+        canonical_package_bytes([&commitments[0], &commitments[0]], b"transaction hash")
+            .expect_err("duplicate commitments should have been rejected");
+    }
+
+    #[test]
+    fn signing_commitment_ref() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        let serialized = commitment.serialize().expect("serialization failed");
+
+        let view = SigningCommitmentRef::new(&serialized[..]).expect("invalid length");
+        assert_eq!(view.identity_bytes(), &commitment.identity().serialize());
+        assert_eq!(view.checksum(), commitment.checksum());
+        assert_eq!(
+            view.into_owned().expect("deserialization failed"),
+            commitment
+        );
+
+        SigningCommitmentRef::new(&serialized[1..]).expect_err("invalid length was accepted");
+
+        // Commitments with an extension can be viewed too
+        let extended_commitments = [
+            SigningCommitment::from_secrets_versioned(
+                &secret,
+                &signing_share,
+                b"transaction hash",
+                &signing_participants,
+                1,
+            )
+            .expect("commitment generation failed"),
+            SigningCommitment::from_secrets_for_dkg(
+                &secret,
+                &signing_share,
+                b"transaction hash",
+                &signing_participants,
+                [1u8; GROUP_HASH_LEN],
+            )
+            .expect("commitment generation failed"),
+            SigningCommitment::from_secrets_dry_run(
+                &secret,
+                &signing_share,
+                b"transaction hash",
+                &signing_participants,
+            )
+            .expect("commitment generation failed"),
+        ];
+        for commitment in extended_commitments {
+            let serialized = commitment.serialize_extended();
+            assert!(serialized.len() > SIGNING_COMMITMENT_LEN);
+
+            let view = SigningCommitmentRef::new(&serialized[..]).expect("invalid length");
+            assert_eq!(view.identity_bytes(), &commitment.identity().serialize());
+            assert_eq!(view.checksum(), commitment.checksum());
+            assert_eq!(
+                view.into_owned().expect("deserialization failed"),
+                commitment
+            );
+        }
+    }
+
+    #[test]
+    fn remaining_for_threshold_report() {
+        let mut rng = thread_rng();
+
+        let secrets = [
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+        ];
+        let signing_share = test_signing_share();
+        let available = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let received = [SigningCommitment::try_from_secrets(
+            &secrets[1],
+            &signing_share,
+            b"transaction hash",
+            &available,
+        )
+        .expect("commitment generation failed")];
+
+        let report = remaining_for_threshold(&received, &available, 2);
+        assert_eq!(report.needed, 1);
+        let mut expected = vec![available[0].clone(), available[2].clone()];
+        expected.sort();
+        assert_eq!(report.candidates, expected);
+
+        let report = remaining_for_threshold(&received, &available, 1);
+        assert_eq!(report.needed, 0);
     }
 
     #[test]
-    fn test_invalid_deserialization() {
+    fn frost_commitments_round_trip() {
         let mut rng = thread_rng();
 
         let secret = Secret::random(&mut rng);
-        let signing_share = SigningShare::default();
-        let signing_participants = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-        ];
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
 
-        let commitment = SigningCommitment::from_secrets(
+        let commitment = SigningCommitment::try_from_secrets(
             &secret,
             &signing_share,
             b"transaction hash",
             &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let raw = commitment
+            .to_frost_commitments()
+            .expect("serialization failed");
+        let (identifier, raw_commitments) =
+            from_frost_commitments(commitment.identity(), &raw).expect("deserialization failed");
+
+        assert_eq!(identifier, commitment.identity().to_frost_identifier());
+        assert_eq!(&raw_commitments, commitment.raw_commitments());
+
+        from_frost_commitments(commitment.identity(), &raw[1..])
+            .expect_err("invalid commitments were accepted");
+    }
+
+    #[test]
+    fn group_by_checksum_sessions() {
+        let mut rng = thread_rng();
+
+        let secrets = [Secret::random(&mut rng), Secret::random(&mut rng)];
+        let signing_share = test_signing_share();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let commitments = [b"transaction hash 1", b"transaction hash 2"]
+            .iter()
+            .flat_map(|transaction_hash| {
+                secrets.iter().map(|secret| {
+                    SigningCommitment::try_from_secrets(
+                        secret,
+                        &signing_share,
+                        &transaction_hash[..],
+                        &signing_participants,
+                    )
+                    .expect("commitment generation failed")
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let groups = group_by_checksum(commitments.iter().cloned());
+        assert_eq!(groups.len(), 2);
+        for (checksum, group) in groups {
+            assert_eq!(group.len(), 2);
+            assert!(group
+                .iter()
+                .all(|commitment| commitment.checksum() == checksum));
+        }
+    }
+
+    /// Returns a digest that is inserted into cell `i % 7` of the first region of a 21-cell
+    /// sketch, so that the outcome of decoding does not depend on random values.
+    fn sketch_digest(i: u64) -> [u8; 32] {
+        let mut digest = [0u8; 32];
+        for (chunk, multiplier) in digest.chunks_exact_mut(8).zip([1, 3, 5, 7]) {
+            chunk.copy_from_slice(&(i * multiplier).to_le_bytes());
+        }
+        digest
+    }
+
+    fn sketch_with_digests<I: IntoIterator<Item = u64>>(digests: I) -> ReconcileSketch {
+        let mut sketch = ReconcileSketch::new(21);
+        for i in digests {
+            sketch.toggle(&sketch_digest(i), 1);
+        }
+        sketch
+    }
+
+    #[test]
+    fn reconcile_sketch() {
+        // Both sets share digests 6..40; 0..3 are only local, 3..6 only remote
+        let local = sketch_with_digests((0..3).chain(6..40));
+        let remote = sketch_with_digests(3..40);
+        assert_eq!(local.cells(), 21);
+
+        let remote = ReconcileSketch::decode_from(&remote.encode()[..], 21)
+            .expect("sketch deserialization failed");
+        let difference = local.decode(&remote).expect("sketch decoding failed");
+
+        assert_eq!(
+            difference.only_local,
+            (0..3).map(sketch_digest).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            difference.only_remote,
+            (3..6).map(sketch_digest).collect::<Vec<_>>()
         );
 
-        let serialized = commitment.serialize();
+        let same = local.decode(&local).expect("sketch decoding failed");
+        assert!(same.only_local.is_empty());
+        assert!(same.only_remote.is_empty());
 
-        for index in 0..serialized.len() {
-            let mut invalid_serialization = serialized;
-            invalid_serialization[index] ^= 0xff;
-            assert!(SigningCommitment::deserialize_from(&invalid_serialization[..]).is_err());
+        // Each cell holds at least 7 of the 50 digests, so none of them can be peeled
+        let empty = ReconcileSketch::new(21);
+        match empty.decode(&sketch_with_digests(0..50)) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("decoding should have failed"),
+        }
+        match empty.decode(&ReconcileSketch::new(30)) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("sketches of different sizes should have been rejected"),
+        }
+
+        // Commitments are inserted by their digest
+        let secret = Secret::random(thread_rng());
+        let signing_share = test_signing_share();
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &[secret.to_identity()],
+        )
+        .expect("commitment generation failed");
+        let mut expected = ReconcileSketch::new(21);
+        expected.toggle(&commitment_digest(&commitment), 1);
+        assert_eq!(
+            ReconcileSketch::from_commitments([&commitment], 21),
+            expected
+        );
+    }
+
+    #[test]
+    fn reconcile_sketch_misplaced_digest() {
+        // Digest 1 belongs to cell 1, so a pure cell 0 can only come from a crafted sketch. Peeling
+        // it would never empty the cell
+        let mut crafted = ReconcileSketch::new(21);
+        crafted.cells[0].toggle(&sketch_digest(1), 1);
+        let crafted = ReconcileSketch::decode_from(&crafted.encode()[..], 21)
+            .expect("sketch deserialization failed");
+
+        match ReconcileSketch::new(21).decode(&crafted) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("misplaced digest should have been rejected"),
         }
     }
 
     #[test]
-    fn test_valid_signature() {
+    fn build_signing_package_lenient_partial_batch() {
         let mut rng = thread_rng();
 
-        let secret = Secret::random(&mut rng);
-        let signing_share = SigningShare::default();
-        let signing_participants = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
+        let secrets = [
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+        ];
+        let outsider = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let commitment = |secret: &Secret, transaction_hash: &[u8]| {
+            SigningCommitment::try_from_secrets(
+                secret,
+                &signing_share,
+                transaction_hash,
+                &signing_participants,
+            )
+            .expect("commitment generation failed")
+        };
+        let commitments = [
+            commitment(&secrets[0], b"transaction hash"),
+            commitment(&secrets[1], b"transaction hash"),
+            commitment(&secrets[1], b"transaction hash"),
+            commitment(&secrets[2], b"other transaction hash"),
+            commitment(&outsider, b"transaction hash"),
         ];
 
-        let commitment = SigningCommitment::from_secrets(
+        let (signing_package, accepted, rejected) =
+            build_signing_package_lenient(&commitments, b"transaction hash", &signing_participants);
+
+        assert_eq!(accepted, signing_participants[..2]);
+        assert_eq!(signing_package.signing_commitments().len(), 2);
+        assert_eq!(signing_package.message(), b"transaction hash");
+        for identity in &accepted {
+            assert_eq!(
+                signing_package.signing_commitment(&identity.to_frost_identifier()),
+                Some(
+                    *commitments[0..3]
+                        .iter()
+                        .find(|commitment| commitment.identity() == identity)
+                        .unwrap()
+                        .raw_commitments()
+                )
+            );
+        }
+
+        let rejected = rejected
+            .into_iter()
+            .map(|(identity, _)| identity)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rejected,
+            [
+                signing_participants[1].clone(),
+                signing_participants[2].clone(),
+                outsider.to_identity(),
+            ]
+        );
+    }
+
+    #[test]
+    fn signature_accessor() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
+
+        let commitment = SigningCommitment::try_from_secrets(
             &secret,
             &signing_share,
             b"transaction hash",
             &signing_participants,
-        );
+        )
+        .expect("commitment generation failed");
 
-        assert!(commitment.verify_authenticity().is_ok());
+        let authenticated_data = versioned_authenticated_data(
+            commitment.identity(),
+            commitment.raw_commitments(),
+            commitment.checksum(),
+            &commitment.extension(),
+        );
+        commitment
+            .identity()
+            .verify_data(&authenticated_data, commitment.signature())
+            .expect("signature verification failed");
     }
 
     #[test]
-    fn test_invalid_signature() {
+    fn big_endian_serialization_round_trip() {
         let mut rng = thread_rng();
 
         let secret = Secret::random(&mut rng);
-        let signing_share = SigningShare::default();
-        let signing_participants = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-        ];
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
 
-        let commitment = SigningCommitment::from_secrets(
+        let commitment = SigningCommitment::try_from_secrets(
             &secret,
             &signing_share,
             b"transaction hash",
             &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let serialized = commitment.serialize_be().expect("serialization failed");
+        assert_ne!(
+            serialized,
+            commitment.serialize().expect("serialization failed")
         );
 
-        let unrelated_secret = Secret::random(&mut rng);
-        let invalid_signature = unrelated_secret.sign(&authenticated_data(
-            commitment.identity(),
-            commitment.raw_commitments(),
-            commitment.checksum(),
-        ));
+        let deserialized = SigningCommitment::deserialize_be_from(&serialized[..])
+            .expect("deserialization failed");
+        assert_eq!(deserialized, commitment);
+    }
 
-        let invalid_commitment = SigningCommitment {
-            identity: commitment.identity().clone(),
-            raw_commitments: *commitment.raw_commitments(),
-            checksum: commitment.checksum(),
-            signature: invalid_signature,
+    #[test]
+    fn dry_run_commitment() {
+        let mut rng = thread_rng();
+
+        let secrets = [Secret::random(&mut rng), Secret::random(&mut rng)];
+        let signing_share = test_signing_share();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let real = SigningCommitment::try_from_secrets(
+            &secrets[0],
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        let dry_run = SigningCommitment::from_secrets_dry_run(
+            &secrets[0],
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        assert_ne!(dry_run.raw_commitments(), real.raw_commitments());
+        dry_run
+            .verify_dry_run_checksum(b"transaction hash", &signing_participants)
+            .expect("dry-run checksum verification failed");
+        dry_run
+            .verify_checksum(b"transaction hash", &signing_participants)
+            .expect_err("dry-run commitment passed real checksum verification");
+        real.verify_dry_run_checksum(b"transaction hash", &signing_participants)
+            .expect_err("real commitment passed dry-run checksum verification");
+
+        let (package, accepted, rejected) =
+            build_signing_package_lenient([&dry_run], b"transaction hash", &signing_participants);
+        assert!(package.signing_commitments().is_empty());
+        assert!(accepted.is_empty());
+        assert_eq!(rejected.len(), 1);
+
+        assert!(dry_run.is_dry_run());
+        assert!(!real.is_dry_run());
+        let deserialized = SigningCommitment::deserialize_from(&dry_run.serialize_extended()[..])
+            .expect("deserialization failed");
+        assert_eq!(deserialized, dry_run);
+        assert!(deserialized.is_dry_run());
+
+        assert!(signing_package([&dry_run], b"transaction hash").is_err());
+        assert!(canonical_package_bytes([&dry_run], b"transaction hash").is_err());
+        assert!(canonical_package_bytes([&real], b"transaction hash").is_ok());
+
+        // The dry-run marker is authenticated, so it cannot be stripped
+        let forged = SigningCommitment {
+            dry_run: false,
+            ..dry_run.clone()
         };
+        assert!(forged.verify_authenticity().is_err());
+        let mut serialized = dry_run.serialize_extended();
+        let flags = serialized.len() - 1;
+        serialized[flags] &= !FLAG_DRY_RUN;
+        assert!(SigningCommitment::deserialize_from(&serialized[..]).is_err());
+    }
 
-        assert!(invalid_commitment.verify_authenticity().is_err());
+    #[test]
+    fn recover_participants_from_checksum() {
+        let mut rng = thread_rng();
+
+        let candidates = (0..6)
+            .map(|_| Secret::random(&mut rng).to_identity())
+            .collect::<Vec<_>>();
+        let mut signing_participants = vec![
+            candidates[4].clone(),
+            candidates[1].clone(),
+            candidates[2].clone(),
+        ];
+        let checksum = input_checksum(b"transaction hash", &signing_participants);
+        signing_participants.sort();
+
+        assert_eq!(
+            recover_participants(checksum, b"transaction hash", &candidates, 2, 4),
+            Some(signing_participants)
+        );
+        assert_eq!(
+            recover_participants(checksum, b"other transaction hash", &candidates, 2, 4),
+            None
+        );
+        assert_eq!(
+            recover_participants(checksum, b"transaction hash", &candidates, 4, 6),
+            None
+        );
     }
 
     #[test]
-    fn test_checksum_stability() {
+    fn commitments_weight() {
         let mut rng = thread_rng();
 
-        let secret1 = Secret::random(&mut rng);
-        let secret2 = Secret::random(&mut rng);
-        let signing_share1 = SigningShare::default();
-        let signing_share2 = SigningShare::default();
-        let transaction_hash = b"something";
-        let signing_participants = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
+        let secrets = [
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
+            Secret::random(&mut rng),
         ];
+        let signing_share = test_signing_share();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let commitments = secrets
+            .iter()
+            .map(|secret| {
+                SigningCommitment::try_from_secrets(
+                    secret,
+                    &signing_share,
+                    b"transaction hash",
+                    &signing_participants,
+                )
+                .expect("commitment generation failed")
+            })
+            .collect::<Vec<_>>();
+
+        let weights = signing_participants
+            .iter()
+            .cloned()
+            .zip([1, 2, 4])
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(total_weight(&commitments, &weights), 7);
+        assert_eq!(total_weight(&commitments[..2], &weights), 3);
+        assert_eq!(
+            total_weight([&commitments[2], &commitments[2]], &weights),
+            4
+        );
+        assert!(meets_weight_threshold(&commitments, &weights, 7));
+        assert!(!meets_weight_threshold(&commitments[1..2], &weights, 3));
+    }
 
-        let commitment1 = SigningCommitment::from_secrets(
-            &secret1,
-            &signing_share1,
-            transaction_hash,
+    #[test]
+    fn serialization_without_checksum_round_trip() {
+        let mut rng = thread_rng();
+
+        let secret = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
+
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let serialized = commitment
+            .serialize_no_checksum()
+            .expect("serialization failed");
+        assert_eq!(serialized.len(), SIGNING_COMMITMENT_WITHOUT_CHECKSUM_LEN);
+
+        let deserialized = SigningCommitment::deserialize_with_checksum_from(
+            &serialized[..],
+            input_checksum(b"transaction hash", &signing_participants),
+        )
+        .expect("deserialization failed");
+        assert_eq!(deserialized, commitment);
+
+        SigningCommitment::deserialize_with_checksum_from(
+            &serialized[..],
+            input_checksum(b"other transaction hash", &signing_participants),
+        )
+        .expect_err("deserialization with the wrong checksum should have failed");
+
+        // The protocol version is kept in the extension
+        let commitment_v2 = SigningCommitment::from_secrets_versioned(
+            &secret,
+            &signing_share,
+            b"transaction hash",
             &signing_participants,
+            2,
+        )
+        .expect("commitment generation failed");
+        let mut serialized = Vec::new();
+        commitment_v2
+            .serialize_no_checksum_into(&mut serialized)
+            .expect("serialization failed");
+        assert_eq!(
+            serialized.len(),
+            SIGNING_COMMITMENT_WITHOUT_CHECKSUM_LEN + 2
         );
+        let deserialized = SigningCommitment::deserialize_with_checksum_from(
+            &serialized[..],
+            input_checksum(b"transaction hash", &signing_participants),
+        )
+        .expect("deserialization failed");
+        assert_eq!(deserialized, commitment_v2);
+        assert_eq!(deserialized.protocol_version(), 2);
+    }
+
+    #[test]
+    fn test_nonce_checksum_eq() {
+        let mut rng = thread_rng();
+
+        let secret1 = Secret::random(&mut rng);
+        let secret2 = Secret::random(&mut rng);
+        let signing_share = test_signing_share();
+        let signing_participants = [secret1.to_identity(), secret2.to_identity()];
 
-        let commitment2 = SigningCommitment::from_secrets(
+        let commitment1 = SigningCommitment::try_from_secrets(
+            &secret1,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        let commitment2 = SigningCommitment::try_from_secrets(
             &secret2,
-            &signing_share2,
-            transaction_hash,
+            &signing_share,
+            b"transaction hash",
             &signing_participants,
-        );
+        )
+        .expect("commitment generation failed");
+        let commitment3 = SigningCommitment::try_from_secrets(
+            &secret2,
+            &signing_share,
+            b"other transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
 
+        assert!(commitment1.nonce_checksum_eq(&commitment2));
         assert_ne!(commitment1, commitment2);
-        assert_eq!(commitment1.checksum(), commitment2.checksum());
+        assert!(!commitment1.nonce_checksum_eq(&commitment3));
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::super::SigningCommitment;
+        use super::test_signing_share;
+        use crate::participant::Secret;
+        use rand::thread_rng;
+
+        fn commitment() -> SigningCommitment {
+            let mut rng = thread_rng();
+            let signing_share = test_signing_share();
+            let signing_participants = [
+                Secret::random(&mut rng).to_identity(),
+                Secret::random(&mut rng).to_identity(),
+            ];
+            SigningCommitment::try_from_secrets(
+                &Secret::random(&mut rng),
+                &signing_share,
+                b"transaction hash",
+                &signing_participants,
+            )
+            .expect("commitment generation failed")
+        }
+
+        #[test]
+        fn serde_json_round_trip() {
+            let commitment = commitment();
+            let json = serde_json::to_string(&commitment).expect("serialization failed");
+            let deserialized: SigningCommitment =
+                serde_json::from_str(&json).expect("deserialization failed");
+            assert_eq!(deserialized, commitment);
+        }
+
+        #[test]
+        fn bincode_round_trip() {
+            let commitment = commitment();
+            let bytes = bincode::serialize(&commitment).expect("serialization failed");
+            let deserialized: SigningCommitment =
+                bincode::deserialize(&bytes).expect("deserialization failed");
+            assert_eq!(deserialized, commitment);
+        }
+
+        #[test]
+        fn serde_rejects_tampered_commitment() {
+            let mut serialized = commitment().serialize_extended();
+            let last = serialized.len() - 1;
+            serialized[last] ^= 0xff;
+
+            let json = serde_json::to_string(&serialized).expect("serialization failed");
+            serde_json::from_str::<SigningCommitment>(&json)
+                .expect_err("deserialization of a tampered commitment should have failed");
+        }
+
+        #[test]
+        fn serde_rejects_trailing_bytes() {
+            let mut serialized = commitment().serialize_extended();
+            serialized.push(0);
+
+            let json = serde_json::to_string(&serialized).expect("serialization failed");
+            serde_json::from_str::<SigningCommitment>(&json)
+                .expect_err("deserialization with trailing bytes should have failed");
+        }
     }
 
     #[test]
-    fn test_checksum_variation_with_transaction_hash() {
+    fn batch_authenticity_verification() {
+        let mut rng = thread_rng();
+
+        let signing_share = test_signing_share();
+        let secrets = (0..5).map(|_| Secret::random(&mut rng)).collect::<Vec<_>>();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+
+        let mut commitments = secrets
+            .iter()
+            .map(|secret| {
+                SigningCommitment::try_from_secrets(
+                    secret,
+                    &signing_share,
+                    b"transaction hash",
+                    &signing_participants,
+                )
+                .expect("commitment generation failed")
+            })
+            .collect::<Vec<_>>();
+
+        verify_commitments_authenticity(&commitments).expect("batch verification failed");
+        verify_commitments_authenticity(&[]).expect("batch verification failed");
+
+        commitments[1].checksum ^= 1;
+        commitments[3].signature = commitments[2].signature;
+
+        for (index, commitment) in commitments.iter().enumerate() {
+            assert_eq!(
+                commitment.verify_authenticity().is_err(),
+                index == 1 || index == 3
+            );
+        }
+
+        let failed = verify_commitments_authenticity(&commitments)
+            .expect_err("batch verification should have failed")
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        assert_eq!(failed, [1, 3]);
+    }
+
+    #[test]
+    fn group_hash_binding() {
         let mut rng = thread_rng();
 
         let secret = Secret::random(&mut rng);
-        let signing_share = SigningShare::default();
-        let signing_participants = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-        ];
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
+        let old_group_hash = [1u8; 32];
+        let new_group_hash = [2u8; 32];
 
-        let commitment1 = SigningCommitment::from_secrets(
+        let old_commitment = SigningCommitment::from_secrets_for_dkg(
             &secret,
             &signing_share,
-            b"something",
+            b"transaction hash",
             &signing_participants,
-        );
-
-        let commitment2 = SigningCommitment::from_secrets(
+            old_group_hash,
+        )
+        .expect("commitment generation failed");
+        let new_commitment = SigningCommitment::from_secrets_for_dkg(
             &secret,
             &signing_share,
-            b"something else",
+            b"transaction hash",
             &signing_participants,
+            new_group_hash,
+        )
+        .expect("commitment generation failed");
+
+        new_commitment
+            .verify_authenticity()
+            .expect("authenticity verification failed");
+        new_commitment
+            .verify_group_hash(&new_group_hash)
+            .expect("group hash verification failed");
+        match old_commitment.verify_group_hash(&new_group_hash) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("commitment with an old group hash should have been rejected"),
+        }
+
+        let serialized = new_commitment.serialize_extended();
+        let deserialized =
+            SigningCommitment::deserialize_for_dkg_from(&serialized[..], new_group_hash)
+                .expect("deserialization failed");
+        assert_eq!(deserialized, new_commitment);
+
+        SigningCommitment::deserialize_for_dkg_from(
+            &old_commitment.serialize_extended()[..],
+            new_group_hash,
+        )
+        .expect_err("deserialization with an old group hash should have failed");
+
+        // The group hash is serialized, so the commitment can be deserialized without knowing it
+        assert_eq!(
+            serialized.len(),
+            SIGNING_COMMITMENT_LEN + 2 + GROUP_HASH_LEN
+        );
+        let deserialized =
+            SigningCommitment::deserialize_from(&serialized[..]).expect("deserialization failed");
+        assert_eq!(deserialized.group_hash(), Some(&new_group_hash));
+        assert_eq!(
+            SigningCommitmentRef::new(&serialized[..])
+                .expect("invalid length")
+                .into_owned()
+                .expect("deserialization failed"),
+            new_commitment
         );
 
-        assert_ne!(commitment1.checksum(), commitment2.checksum());
+        // Commitments without a group hash are rejected when a group is expected
+        let unbound_commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        SigningCommitment::deserialize_for_dkg_from(
+            &unbound_commitment
+                .serialize()
+                .expect("serialization failed")[..],
+            new_group_hash,
+        )
+        .expect_err("deserialization without a group hash should have failed");
+
+        // The serialized group hash is authenticated
+        let mut forged = serialized.clone();
+        *forged.last_mut().expect("serialization is empty") ^= 0xff;
+        SigningCommitment::deserialize_from(&forged[..])
+            .expect_err("deserialization of a forged group hash should have failed");
+
+        // Forging the group hash invalidates the signature
+        let forged_commitment = SigningCommitment {
+            group_hash: Some(new_group_hash),
+            ..old_commitment.clone()
+        };
+        forged_commitment
+            .verify_authenticity()
+            .expect_err("authenticity verification should have failed");
     }
 
     #[test]
-    fn test_checksum_variation_with_signers_list() {
+    fn group_membership() {
         let mut rng = thread_rng();
 
         let secret = Secret::random(&mut rng);
-        let signing_share = SigningShare::default();
-        let transaction_hash = b"something";
-        let signing_participants1 = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-        ];
-        let signing_participants2 = [
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-            Secret::random(&mut rng).to_identity(),
-        ];
+        let signing_share = test_signing_share();
+        let signing_participants = [secret.to_identity(), Secret::random(&mut rng).to_identity()];
+        let allowed_group_hashes = [[1u8; 32], [2u8; 32], [3u8; 32]];
 
-        let commitment1 = SigningCommitment::from_secrets(
+        let commitment = SigningCommitment::from_secrets_for_dkg(
             &secret,
             &signing_share,
-            transaction_hash,
-            &signing_participants1,
+            b"transaction hash",
+            &signing_participants,
+            [2u8; 32],
+        )
+        .expect("commitment generation failed");
+        assert_eq!(
+            commitment
+                .verify_group_membership(&allowed_group_hashes)
+                .expect("group membership verification failed"),
+            [2u8; 32]
+        );
+
+        // Commitments received over the wire can be routed without knowing their group in advance
+        let received = SigningCommitment::deserialize_from(&commitment.serialize_extended()[..])
+            .expect("deserialization failed");
+        assert_eq!(
+            received
+                .verify_group_membership(&allowed_group_hashes)
+                .expect("group membership verification failed"),
+            [2u8; 32]
         );
 
-        let commitment2 = SigningCommitment::from_secrets(
+        let other_commitment = SigningCommitment::from_secrets_for_dkg(
             &secret,
             &signing_share,
-            transaction_hash,
-            &signing_participants2,
+            b"transaction hash",
+            &signing_participants,
+            [4u8; 32],
+        )
+        .expect("commitment generation failed");
+        match other_commitment.verify_group_membership(&allowed_group_hashes) {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("commitment for an unknown group should have been rejected"),
+        }
+
+        let unbound_commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+        unbound_commitment
+            .verify_group_membership(&allowed_group_hashes)
+            .expect_err("commitment without a group hash should have been rejected");
+    }
+
+    #[test]
+    fn framed_round_trip() {
+        let mut rng = thread_rng();
+
+        let signing_share = test_signing_share();
+        let secrets = (0..3).map(|_| Secret::random(&mut rng)).collect::<Vec<_>>();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+        let commitments = secrets
+            .iter()
+            .map(|secret| {
+                SigningCommitment::try_from_secrets(
+                    secret,
+                    &signing_share,
+                    b"transaction hash",
+                    &signing_participants,
+                )
+                .expect("commitment generation failed")
+            })
+            .collect::<Vec<_>>();
+
+        let mut framed = Vec::new();
+        SigningCommitment::write_all(&mut framed, &commitments).expect("framing failed");
+        assert_eq!(
+            framed.len(),
+            4 + commitments.len() * (4 + SIGNING_COMMITMENT_LEN)
         );
 
-        assert_ne!(commitment1.checksum(), commitment2.checksum());
+        let read = SigningCommitment::read_all(&framed[..], 3).expect("reading failed");
+        assert_eq!(read, commitments);
+
+        SigningCommitment::read_all(&framed[..], 2)
+            .expect_err("reading more than max_count commitments should have failed");
+
+        // Truncated final element
+        let err = SigningCommitment::read_all(&framed[..framed.len() - 1], 3)
+            .expect_err("reading a truncated sequence should have failed");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        // Truncated length prefix
+        let err = SigningCommitment::read_all(&framed[..4 + 2], 3)
+            .expect_err("reading a truncated sequence should have failed");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        // Corrupt element between two valid ones
+        let mut corrupt = framed.clone();
+        let offset = 4 + (4 + SIGNING_COMMITMENT_LEN) + 4 + SIGNING_COMMITMENT_LEN - 1;
+        corrupt[offset] ^= 0xff;
+        let err = SigningCommitment::read_all(&corrupt[..], 3)
+            .expect_err("reading a corrupt commitment should have failed");
+        assert_ne!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        // Element with a length prefix larger than the commitment
+        let mut padded = Vec::new();
+        padded.extend_from_slice(&1u32.to_le_bytes());
+        padded.extend_from_slice(&(SIGNING_COMMITMENT_LEN as u32 + 1).to_le_bytes());
+        padded.extend_from_slice(&commitments[0].serialize().expect("serialization failed"));
+        padded.push(0);
+        SigningCommitment::read_all(&padded[..], 3)
+            .expect_err("reading a commitment with trailing bytes should have failed");
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_verification_matches_sequential() {
+        let mut rng = thread_rng();
+
+        let signing_share = test_signing_share();
+        let secrets = (0..7).map(|_| Secret::random(&mut rng)).collect::<Vec<_>>();
+        let signing_participants = secrets
+            .iter()
+            .map(|secret| secret.to_identity())
+            .collect::<Vec<_>>();
+        let mut commitments = secrets
+            .iter()
+            .map(|secret| {
+                SigningCommitment::try_from_secrets(
+                    secret,
+                    &signing_share,
+                    b"transaction hash",
+                    &signing_participants,
+                )
+                .expect("commitment generation failed")
+            })
+            .collect::<Vec<_>>();
+        commitments[2].checksum ^= 1;
+        commitments[6].checksum ^= 1;
+
+        let sequential = commitments
+            .iter()
+            .map(|commitment| commitment.verify_authenticity().is_ok())
+            .collect::<Vec<_>>();
+        assert_eq!(sequential, [true, true, false, true, true, true, false]);
+
+        for max_threads in [0, 1, 2, 3, 7, 16] {
+            let parallel = verify_parallel(&commitments, max_threads)
+                .iter()
+                .map(Result::is_ok)
+                .collect::<Vec<_>>();
+            assert_eq!(parallel, sequential);
+        }
+
+        assert!(verify_parallel(&[], 4).is_empty());
     }
 }
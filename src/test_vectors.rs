@@ -0,0 +1,205 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Named serialization vectors, to detect accidental changes to the serialization formats.
+//!
+//! Each [`Vector`] holds a serialization recorded with a given version of the format. New
+//! vectors should be added whenever a format is introduced or changed, keeping the vectors for the
+//! previous versions if they are still supported.
+
+#[cfg(feature = "dkg")]
+use crate::dkg::round3::PublicKeyPackage;
+use crate::error::Error;
+use crate::participant::Identity;
+use crate::signature_share::SignatureShare;
+use crate::signing_commitment::SigningCommitment;
+use hex_literal::hex;
+
+/// Types that serialization vectors can be provided for.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Identity,
+    SigningCommitment,
+    SignatureShare,
+    #[cfg(feature = "dkg")]
+    PublicKeyPackage,
+}
+
+/// A serialization of a value of type [`Kind`], recorded with a given `version` of the format.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Vector {
+    pub name: &'static str,
+    pub version: u8,
+    pub kind: Kind,
+    pub serialization: &'static [u8],
+}
+
+static VECTORS: &[Vector] = &[
+    Vector {
+        name: "signing_commitment_v0",
+        version: 0,
+        kind: Kind::SigningCommitment,
+        serialization: &hex!(
+            "
+            307be5a2c20495d05966fc12b2cee3ea4d44cb3623f92b0f6a391c626fa7708e835
+            26e886448d5ef376c5d09675aed3e711cd3e0df9f6c607604e6a7371a210e725c3a
+            20a22aebc59d856bfbaa48fde8f8ea6fe48ddd978555932c283e760397f78b4b468
+            2f9b70f8baad6d7752f5e25bcbc6b3453d16d92589da722ad13a7390d0057c6aae8
+            363a50e835b89b44bccdd5889ef5a362fa89d841c96e65b34dbe3adf8f71faa041f
+            394ef6b127c4b6b1e43714f32c450e8d3d089b376915acd6500639cad9b202c479e
+            4216e2d4d16cad09b634e01270f4a52707d924fd9834e6206f48f04388ae90bcd63
+            f901369c6034760245574a2d3068f52b617d33ca1a417ea391d3785b542f5
+        "
+        ),
+    },
+    Vector {
+        name: "signing_commitment_v1",
+        version: 1,
+        kind: Kind::SigningCommitment,
+        serialization: &hex!(
+            "
+            307be5a2c20495d05966fc12b2cee3ea4d44cb3623f92b0f6a391c626fa7708e835
+            26e886448d5ef376c5d09675aed3e711cd3e0df9f6c607604e6a7371a210e725c3a
+            20a22aebc59d856bfbaa48fde8f8ea6fe48ddd978555932c283e760397f78b4b468
+            2f9b70f8baad6d7752f5e25bcbc6b3453d16d92589da722ad13a7390d0057c6aae8
+            363a50e835b89b44bccdd5889ef5a362fa89d841c96e65b34dbe3adf8f71faa041f
+            394ef6b127c4b6b1e43714f32c450e8d3d089b376915acd6500639cad9b202c479e
+            4216e2d4d16cad09b634e01270f4a52707d924fd9834e6206f48f04388ae90bcd63
+            f901369c6034760245574a2d3068f52b617d33ca1a41701ea391d3785b542f5
+        "
+        ),
+    },
+    Vector {
+        name: "identity_v0",
+        version: 0,
+        kind: Kind::Identity,
+        serialization: &hex!(
+            "
+            723c692fa94b563faa41cf99b5759f8d0dc2606c90df30e8ae1813b1068d5cddd60
+            0c88978733e6399d65a1b5c5c025030a3505737c4207075e11fce168ba97246a545
+            92e5217ea54b1bc68efcdf15e8bf227d098d84d403994cebe774a119cfcbcec2eee
+            a73d09306e395019d9b891e8c9d6acaa1751cacde66f4010f748c9e0d
+        "
+        ),
+    },
+    Vector {
+        name: "signature_share_v0",
+        version: 0,
+        kind: Kind::SignatureShare,
+        serialization: &hex!(
+            "
+            723c692fa94b563faa41cf99b5759f8d0dc2606c90df30e8ae1813b1068d5cddd60
+            0c88978733e6399d65a1b5c5c025030a3505737c4207075e11fce168ba97246a545
+            92e5217ea54b1bc68efcdf15e8bf227d098d84d403994cebe774a119cfcbcec2eee
+            a73d09306e395019d9b891e8c9d6acaa1751cacde66f4010f748c9e0d705b03830c
+            7c83e0c5e95a21dfb8f1976cf40cd67541f38bb75d1fed1bb9cc09
+        "
+        ),
+    },
+    #[cfg(feature = "dkg")]
+    Vector {
+        name: "public_key_package_v0",
+        version: 0,
+        kind: Kind::PublicKeyPackage,
+        serialization: &hex!(
+            "
+            a600000000c3d2051e02b62709a88950f3a75eb0d03a9510123a72947eb083b5822
+            e874793e8f40f6b0ba381109571a24f9f87421f0393f45cee913ef891bd75eb7ba7
+            a5611858a80305cf631451a7d94604cb32d11285ebd4b6ee797eceaa464b2dfcd09
+            7295cacf90c579265130e9e37225a23dfd51da2c4b8db499cb0e7aa6b03a15cde2d
+            b678e99c94974b2a766f83b134c5c782803f5f5a65bc4a6392f6a81062ad8292e84
+            4f3c00200000072cf6be086f2453ec7ce6f7b76fbb35c4dcf6fac1737dbcc2a2467
+            b3f0c8453574ad36e9dd2b092aa0870930ed6be8d9ba40c146c5b2110fbb03f7e3b
+            60e5d63347f47bd69c418630d0c4d3301f0a910c3d127c9d7064cedf26c0c2f0cea
+            9486a8993eea77744ead60ea210bc43a4c56be4933762dddaba145fb215c5dbaebc
+            a0272586e451ceb90d00fde8fa96f7eba99845066803aef4073ca39f3af9050b9f0
+            bc63deb3652c1455090070a8dd3376128e093726a055bab2e2d2325cb5c978b62eb
+            a97c6b42325cf4fc106321b7c8979fc123dc77a5da91ace3b3245405d680b9bcc13
+            5828ac28415305d74abe2ca084639dd1ab7bb8c69930cf0a55a1151022020200
+        "
+        ),
+    },
+];
+
+/// Returns all the known serialization vectors.
+#[must_use]
+pub fn vectors() -> &'static [Vector] {
+    VECTORS
+}
+
+/// Returns the serialization vector called `name`, if any.
+#[must_use]
+pub fn vector(name: &str) -> Option<&'static Vector> {
+    VECTORS.iter().find(|vector| vector.name == name)
+}
+
+/// Deserializes the vector called `name`, serializes it again, and checks that the result matches
+/// the recorded serialization.
+pub fn verify_vector(name: &str) -> Result<(), Error> {
+    let vector = vector(name)
+        .ok_or_else(|| Error::InvalidInput(format!("unknown serialization vector {}", name)))?;
+    let bytes = vector.serialization;
+    let deserialization_failed =
+        |err| Error::InvalidInput(format!("vector {} deserialization failed: {}", name, err));
+
+    let reserialized = match vector.kind {
+        Kind::Identity => Identity::deserialize_from(bytes)
+            .map_err(deserialization_failed)?
+            .serialize()
+            .to_vec(),
+        Kind::SigningCommitment if vector.version == 0 => {
+            SigningCommitment::deserialize_v0_from(bytes)
+                .map_err(deserialization_failed)?
+                .serialize_v0()?
+                .to_vec()
+        }
+        Kind::SigningCommitment => SigningCommitment::deserialize_from(bytes)
+            .map_err(deserialization_failed)?
+            .serialize_extended(),
+        Kind::SignatureShare => SignatureShare::deserialize_from(bytes)
+            .map_err(deserialization_failed)?
+            .serialize()
+            .to_vec(),
+        #[cfg(feature = "dkg")]
+        Kind::PublicKeyPackage => PublicKeyPackage::deserialize_from(bytes)
+            .map_err(deserialization_failed)?
+            .serialize(),
+    };
+
+    if reserialized == bytes {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "vector {} does not serialize to the recorded bytes",
+            name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_vectors() {
+        for vector in vectors() {
+            verify_vector(vector.name).expect("vector verification failed");
+        }
+    }
+
+    #[test]
+    fn unique_names() {
+        for (i, vector) in vectors().iter().enumerate() {
+            assert!(vectors()[..i].iter().all(|other| other.name != vector.name));
+        }
+    }
+
+    #[test]
+    fn unknown_vector() {
+        match verify_vector("no such vector") {
+            Err(Error::InvalidInput(_)) => (),
+            _ => panic!("unknown vector should have been rejected"),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Audit trail of verification failures.
+//!
+//! Failed verifications are normally reported to the caller as errors, and it is up to the
+//! caller to record them. Deployments that want a complete audit trail can instead install a
+//! [`FailureSink`] with [`set_failure_sink`], which gets notified of every failure regardless of
+//! how the caller handles the error.
+
+use crate::participant::Identity;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FailureKind {
+    /// The signature of a [`SigningCommitment`](crate::signing_commitment::SigningCommitment) did
+    /// not verify.
+    Authenticity,
+    /// The checksum of a [`SigningCommitment`](crate::signing_commitment::SigningCommitment) did
+    /// not match the expected transaction hash and signers.
+    Checksum,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VerificationFailure {
+    pub kind: FailureKind,
+    /// Identity that produced the data that failed verification.
+    pub identity: Identity,
+    pub timestamp: SystemTime,
+}
+
+/// Receiver of [`VerificationFailure`] notifications.
+///
+/// Implementations are called synchronously from the verification functions, so they should
+/// return quickly and must not panic.
+pub trait FailureSink: Send + Sync {
+    fn notify(&self, failure: &VerificationFailure);
+}
+
+static FAILURE_SINK: RwLock<Option<Box<dyn FailureSink>>> = RwLock::new(None);
+
+/// Installs `sink` as the process-wide receiver of verification failures, replacing the previous
+/// one (if any). Passing `None` disables notifications.
+pub fn set_failure_sink(sink: Option<Box<dyn FailureSink>>) {
+    *FAILURE_SINK.write().unwrap_or_else(PoisonError::into_inner) = sink;
+}
+
+pub(crate) fn notify_failure(kind: FailureKind, identity: &Identity) {
+    let sink = FAILURE_SINK.read().unwrap_or_else(PoisonError::into_inner);
+    if let Some(sink) = sink.as_ref() {
+        sink.notify(&VerificationFailure {
+            kind,
+            identity: identity.clone(),
+            timestamp: SystemTime::now(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::set_failure_sink;
+    use super::FailureKind;
+    use super::FailureSink;
+    use super::VerificationFailure;
+    use crate::nonces::test_signing_share;
+    use crate::participant::Secret;
+    use crate::signing_commitment::SigningCommitment;
+    use rand::thread_rng;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CollectingSink(Arc<Mutex<Vec<VerificationFailure>>>);
+
+    impl FailureSink for CollectingSink {
+        fn notify(&self, failure: &VerificationFailure) {
+            self.0.lock().unwrap().push(failure.clone());
+        }
+    }
+
+    #[test]
+    fn checksum_failure_notifies_sink() {
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        set_failure_sink(Some(Box::new(CollectingSink(failures.clone()))));
+
+        let secret = Secret::random(thread_rng());
+        let signing_share = test_signing_share();
+        let signing_participants = [
+            secret.to_identity(),
+            Secret::random(thread_rng()).to_identity(),
+        ];
+        let commitment = SigningCommitment::try_from_secrets(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        let dry_run_commitment = SigningCommitment::from_secrets_dry_run(
+            &secret,
+            &signing_share,
+            b"transaction hash",
+            &signing_participants,
+        )
+        .expect("commitment generation failed");
+
+        commitment
+            .verify_checksum(b"other transaction hash", &signing_participants)
+            .expect_err("checksum verification should have failed");
+        dry_run_commitment
+            .verify_dry_run_checksum(b"other transaction hash", &signing_participants)
+            .expect_err("dry-run checksum verification should have failed");
+        set_failure_sink(None);
+
+        // Other tests may run concurrently, so only look at failures for this identity
+        let failures = failures
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|failure| failure.identity == secret.to_identity())
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(failures.len(), 2);
+        assert!(failures
+            .iter()
+            .all(|failure| failure.kind == FailureKind::Checksum));
+    }
+}
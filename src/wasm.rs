@@ -0,0 +1,319 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Bindings for JavaScript, through `wasm_bindgen`.
+//!
+//! All the values cross the JavaScript boundary in their binary serialization, as `Uint8Array`.
+//! Lists of values (identities, DKG packages) are passed as the concatenation of their
+//! serializations. Errors are raised as JavaScript exceptions.
+
+use crate::dkg::round1;
+use crate::dkg::round2;
+use crate::dkg::round3;
+use crate::frost::keys::KeyPackage;
+use crate::participant::Identity;
+use crate::participant::Secret;
+use crate::signing_commitment::SigningCommitment;
+use rand_core::OsRng;
+use std::fmt;
+use std::io;
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+fn js_error<E: fmt::Display>(err: E) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+/// Deserializes a concatenation of values with `read`, until `bytes` is exhausted.
+fn read_concatenated<T, F>(mut bytes: &[u8], read: F) -> io::Result<Vec<T>>
+where
+    F: Fn(&mut &[u8]) -> io::Result<T>,
+{
+    let mut values = Vec::new();
+    while !bytes.is_empty() {
+        values.push(read(&mut bytes)?);
+    }
+    Ok(values)
+}
+
+fn read_identities(bytes: &[u8]) -> Result<Vec<Identity>, JsError> {
+    read_concatenated(bytes, |reader| Identity::deserialize_from(reader)).map_err(js_error)
+}
+
+fn read_secret(bytes: &[u8]) -> Result<Secret, JsError> {
+    Secret::deserialize_from(bytes).map_err(js_error)
+}
+
+/// A [`SigningCommitment`], exposed to JavaScript as `SigningCommitment`.
+#[wasm_bindgen(js_name = SigningCommitment)]
+#[derive(Clone, Debug)]
+pub struct WasmSigningCommitment(SigningCommitment);
+
+#[wasm_bindgen(js_class = SigningCommitment)]
+impl WasmSigningCommitment {
+    /// See [`SigningCommitment::try_from_secrets`]. `key_package` is the serialized key package
+    /// returned by [`dkg_round3`], and `signing_participants` the concatenation of the serialized
+    /// identities of the signers.
+    #[wasm_bindgen(js_name = fromSecrets)]
+    pub fn from_secrets(
+        secret: &[u8],
+        key_package: &[u8],
+        transaction_hash: &[u8],
+        signing_participants: &[u8],
+    ) -> Result<WasmSigningCommitment, JsError> {
+        let secret = read_secret(secret)?;
+        let key_package = KeyPackage::deserialize(key_package).map_err(js_error)?;
+        let signing_participants = read_identities(signing_participants)?;
+        SigningCommitment::try_from_secrets(
+            &secret,
+            key_package.signing_share(),
+            transaction_hash,
+            &signing_participants,
+        )
+        .map(Self)
+        .map_err(js_error)
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<WasmSigningCommitment, JsError> {
+        SigningCommitment::deserialize_from(bytes)
+            .map(Self)
+            .map_err(js_error)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        self.0.serialize_extended()
+    }
+
+    #[wasm_bindgen(js_name = verifyAuthenticity)]
+    pub fn verify_authenticity(&self) -> Result<(), JsError> {
+        self.0.verify_authenticity().map_err(js_error)
+    }
+
+    pub fn identity(&self) -> Vec<u8> {
+        self.0.identity().serialize().to_vec()
+    }
+}
+
+/// The output of [`dkg_round1`] and [`dkg_round2`]: an encrypted secret package to keep, and a
+/// public package to send to the other participants.
+#[wasm_bindgen]
+#[allow(missing_debug_implementations)]
+pub struct DkgRoundOutput {
+    secret_package: Vec<u8>,
+    public_package: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl DkgRoundOutput {
+    #[wasm_bindgen(getter, js_name = secretPackage)]
+    pub fn secret_package(&self) -> Vec<u8> {
+        self.secret_package.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicPackage)]
+    pub fn public_package(&self) -> Vec<u8> {
+        self.public_package.clone()
+    }
+}
+
+/// The output of [`dkg_round3`]. The key package and the group secret key are wiped on drop.
+#[wasm_bindgen]
+#[allow(missing_debug_implementations)]
+pub struct DkgRound3Output {
+    key_package: Vec<u8>,
+    public_key_package: Vec<u8>,
+    group_secret_key: Vec<u8>,
+}
+
+impl Drop for DkgRound3Output {
+    fn drop(&mut self) {
+        self.key_package.zeroize();
+        self.group_secret_key.zeroize();
+    }
+}
+
+#[wasm_bindgen]
+impl DkgRound3Output {
+    #[wasm_bindgen(getter, js_name = keyPackage)]
+    pub fn key_package(&self) -> Vec<u8> {
+        self.key_package.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicKeyPackage)]
+    pub fn public_key_package(&self) -> Vec<u8> {
+        self.public_key_package.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = groupSecretKey)]
+    pub fn group_secret_key(&self) -> Vec<u8> {
+        self.group_secret_key.clone()
+    }
+}
+
+/// See [`round1::round1`]. `participants` is the concatenation of the serialized identities of all
+/// the participants, including `self_identity`.
+#[wasm_bindgen(js_name = dkgRound1)]
+pub fn dkg_round1(
+    self_identity: &[u8],
+    min_signers: u16,
+    participants: &[u8],
+) -> Result<DkgRoundOutput, JsError> {
+    let self_identity = Identity::deserialize_from(self_identity).map_err(js_error)?;
+    let participants = read_identities(participants)?;
+    let (secret_package, public_package) =
+        round1::round1(&self_identity, min_signers, &participants, OsRng).map_err(js_error)?;
+    Ok(DkgRoundOutput {
+        secret_package,
+        public_package: public_package.serialize(),
+    })
+}
+
+/// See [`round2::round2`]. `round1_public_packages` is the concatenation of the round 1 public
+/// packages of all the participants.
+#[wasm_bindgen(js_name = dkgRound2)]
+pub fn dkg_round2(
+    secret: &[u8],
+    round1_secret_package: &[u8],
+    round1_public_packages: &[u8],
+) -> Result<DkgRoundOutput, JsError> {
+    let secret = read_secret(secret)?;
+    let round1_public_packages = read_concatenated(round1_public_packages, |reader| {
+        round1::PublicPackage::deserialize_from(reader)
+    })
+    .map_err(js_error)?;
+    let (secret_package, public_package) = round2::round2(
+        &secret,
+        round1_secret_package,
+        &round1_public_packages,
+        OsRng,
+    )
+    .map_err(js_error)?;
+    Ok(DkgRoundOutput {
+        secret_package,
+        public_package: public_package.serialize(),
+    })
+}
+
+/// See [`round3::round3`]. `round1_public_packages` and `round2_public_packages` are the
+/// concatenations of the public packages of all the participants for the respective rounds.
+#[wasm_bindgen(js_name = dkgRound3)]
+pub fn dkg_round3(
+    secret: &[u8],
+    round2_secret_package: &[u8],
+    round1_public_packages: &[u8],
+    round2_public_packages: &[u8],
+) -> Result<DkgRound3Output, JsError> {
+    let secret = read_secret(secret)?;
+    let round1_public_packages = read_concatenated(round1_public_packages, |reader| {
+        round1::PublicPackage::deserialize_from(reader)
+    })
+    .map_err(js_error)?;
+    let round2_public_packages = read_concatenated(round2_public_packages, |reader| {
+        round2::CombinedPublicPackage::deserialize_from(reader)
+    })
+    .map_err(js_error)?;
+    let (key_package, public_key_package, group_secret_key) = round3::round3(
+        &secret,
+        round2_secret_package,
+        &round1_public_packages,
+        &round2_public_packages,
+    )
+    .map_err(js_error)?;
+    Ok(DkgRound3Output {
+        key_package: key_package.serialize().map_err(js_error)?,
+        public_key_package: public_key_package.serialize(),
+        group_secret_key: group_secret_key.to_vec(),
+    })
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn dkg_2_of_3() {
+        let secrets = (0..3).map(|_| Secret::random(OsRng)).collect::<Vec<_>>();
+        let identities = secrets
+            .iter()
+            .map(|secret| secret.to_identity().serialize())
+            .collect::<Vec<_>>();
+        let participants = identities.concat();
+
+        let round1_outputs = identities
+            .iter()
+            .map(|identity| dkg_round1(identity, 2, &participants).expect("round 1 failed"))
+            .collect::<Vec<_>>();
+        let round1_public_packages = round1_outputs
+            .iter()
+            .map(|output| output.public_package())
+            .collect::<Vec<_>>()
+            .concat();
+
+        let round2_outputs = secrets
+            .iter()
+            .zip(round1_outputs.iter())
+            .map(|(secret, round1_output)| {
+                dkg_round2(
+                    &secret.serialize(),
+                    &round1_output.secret_package(),
+                    &round1_public_packages,
+                )
+                .expect("round 2 failed")
+            })
+            .collect::<Vec<_>>();
+
+        let round3_outputs = secrets
+            .iter()
+            .enumerate()
+            .map(|(i, secret)| {
+                let round2_public_packages = round2_outputs
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, output)| output.public_package())
+                    .collect::<Vec<_>>()
+                    .concat();
+                dkg_round3(
+                    &secret.serialize(),
+                    &round2_outputs[i].secret_package(),
+                    &round1_public_packages,
+                    &round2_public_packages,
+                )
+                .expect("round 3 failed")
+            })
+            .collect::<Vec<_>>();
+
+        for output in &round3_outputs[1..] {
+            assert_eq!(
+                output.public_key_package(),
+                round3_outputs[0].public_key_package()
+            );
+            assert_eq!(
+                output.group_secret_key(),
+                round3_outputs[0].group_secret_key()
+            );
+        }
+
+        let transaction_hash = [0xab; 32];
+        let signing_participants = identities[..2].concat();
+        let commitment = WasmSigningCommitment::from_secrets(
+            &secrets[0].serialize(),
+            &round3_outputs[0].key_package(),
+            &transaction_hash,
+            &signing_participants,
+        )
+        .expect("commitment creation failed");
+        let deserialized = WasmSigningCommitment::deserialize(&commitment.serialize())
+            .expect("deserialization failed");
+        deserialized
+            .verify_authenticity()
+            .expect("authenticity verification failed");
+        assert_eq!(deserialized.identity(), identities[0].to_vec());
+
+        assert!(dkg_round1(&identities[0], 2, &participants[1..]).is_err());
+    }
+}